@@ -100,6 +100,20 @@ pub enum Command {
     /// Lists all discovered fonts in system and custom font paths.
     Fonts(FontsCommand),
 
+    /// Runs a language server that communicates over stdio.
+    Lsp(LspCommand),
+
+    /// Runs a compile server that keeps fonts, packages, and caches warm
+    /// across compilations requested over JSON-RPC.
+    ServeCompile(ServeCompileCommand),
+
+    /// Formats Typst source files.
+    Fmt(FmtCommand),
+
+    /// Runs a project's tests and compares their output to references.
+    #[command(visible_alias = "t")]
+    Test(TestCommand),
+
     /// Self update the Typst CLI.
     #[cfg_attr(not(feature = "self-update"), clap(hide = true))]
     Update(UpdateCommand),
@@ -238,6 +252,124 @@ pub struct FontsCommand {
     pub variants: bool,
 }
 
+/// Runs a language server that communicates over stdio.
+#[derive(Debug, Clone, Parser)]
+pub struct LspCommand {
+    /// Path to the Typst file that is compiled to produce diagnostics and
+    /// document-aware completions.
+    ///
+    /// Defaults to the file that was most recently opened in the editor.
+    #[clap(long = "main", value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub main: Option<PathBuf>,
+
+    /// World arguments.
+    #[clap(flatten)]
+    pub world: WorldArgs,
+
+    /// Processing arguments.
+    #[clap(flatten)]
+    pub process: ProcessArgs,
+}
+
+/// Runs a compile server that keeps fonts, packages, and caches warm across
+/// compilations requested over JSON-RPC.
+///
+/// Messages are framed with a `Content-Length` header, like in the language
+/// server protocol. The `compile` method takes the `input` file and optionally
+/// `inputs` (the `sys.inputs` as a string dictionary), `format` (`pdf`, `png`,
+/// `svg`, or `html`), `pages` (e.g. `"1,3-5"`), `ppi`, `pretty`,
+/// `creationTimestamp`, and `output`. It returns whether compilation
+/// succeeded, the `diagnostics` in the same shape as `--diagnostic-format
+/// json`, and the `artifacts`. Each artifact has the base64-encoded `data`, or
+/// the `path` it was written to if an `output` was given. The `shutdown` and
+/// `exit` methods stop the server.
+#[derive(Debug, Clone, Parser)]
+pub struct ServeCompileCommand {
+    /// Listens on a Unix domain socket at this path instead of communicating
+    /// over stdio.
+    ///
+    /// Clients are served one after another.
+    #[clap(long, value_name = "PATH")]
+    pub socket: Option<PathBuf>,
+
+    /// World arguments.
+    #[clap(flatten)]
+    pub world: WorldArgs,
+
+    /// Processing arguments.
+    #[clap(flatten)]
+    pub process: ProcessArgs,
+}
+
+/// Formats Typst source files.
+#[derive(Debug, Clone, Parser)]
+pub struct FmtCommand {
+    /// Paths to Typst files or directories to format in place. Directories
+    /// are searched recursively for `.typ` files. Use `-` to read from stdin
+    /// and write to stdout.
+    #[clap(
+        required = true,
+        value_parser = input_value_parser(),
+        value_hint = ValueHint::AnyPath,
+    )]
+    pub input: Vec<Input>,
+
+    /// Only checks whether the files are formatted, listing those that are
+    /// not, instead of changing them.
+    #[arg(long)]
+    pub check: bool,
+
+    /// The number of spaces per indentation level in code.
+    #[arg(long, default_value_t = 2)]
+    pub indent: usize,
+
+    /// The column after which long argument lists and other parenthesized
+    /// lists are wrapped.
+    #[arg(long, default_value_t = 80)]
+    pub max_width: usize,
+}
+
+/// Runs a project's tests and compares their output to references.
+///
+/// Every Typst file in the test directory is a test. The pages of a test are
+/// rendered and compared to the PNG references in `ref/<test>/`. On mismatch,
+/// the rendered pages and diff images are written to `diff/<test>/`. Tests
+/// without visible output need no references and only check that their
+/// `assert` calls hold.
+#[derive(Debug, Clone, Parser)]
+pub struct TestCommand {
+    /// The directory containing the tests. Defaults to `tests` in the project
+    /// root.
+    #[clap(value_hint = ValueHint::DirPath)]
+    pub dir: Option<PathBuf>,
+
+    /// Only runs tests whose path relative to the test directory contains
+    /// one of these strings.
+    #[arg(long = "filter", value_name = "PATTERN")]
+    pub filter: Vec<String>,
+
+    /// Creates missing references and replaces the ones that don't match.
+    #[arg(long)]
+    pub update: bool,
+
+    /// The maximum difference per color channel for a pixel to still match
+    /// its reference.
+    #[arg(long, default_value_t = 0)]
+    pub tolerance: u8,
+
+    /// The PPI (pixels per inch) at which pages are rendered.
+    #[arg(long = "ppi", default_value_t = 144.0)]
+    pub ppi: f64,
+
+    /// World arguments.
+    #[clap(flatten)]
+    pub world: WorldArgs,
+
+    /// Processing arguments.
+    #[clap(flatten)]
+    pub process: ProcessArgs,
+}
+
 /// Update the CLI using a pre-compiled binary from a Typst GitHub release.
 #[derive(Debug, Clone, Parser)]
 pub struct UpdateCommand {
@@ -352,6 +484,57 @@ pub struct CompileArgs {
     #[arg(long = "no-pdf-tags")]
     pub no_pdf_tags: bool,
 
+    /// Encrypts the PDF with AES-256 and requires this password to open it.
+    #[arg(
+        long = "pdf-user-password",
+        env = "TYPST_PDF_USER_PASSWORD",
+        value_name = "PASSWORD",
+        hide_env_values = true
+    )]
+    pub pdf_user_password: Option<String>,
+
+    /// Encrypts the PDF with AES-256 and grants full access to anyone who
+    /// opens it with this password, regardless of `--pdf-deny`.
+    ///
+    /// If only a user password is given, a random owner password is used, so
+    /// that nobody gets full access.
+    #[arg(
+        long = "pdf-owner-password",
+        env = "TYPST_PDF_OWNER_PASSWORD",
+        value_name = "PASSWORD",
+        hide_env_values = true
+    )]
+    pub pdf_owner_password: Option<String>,
+
+    /// One (or multiple comma-separated) operations that users of the PDF may
+    /// not perform unless they know the owner password. Requires
+    /// `--pdf-user-password` or `--pdf-owner-password`.
+    #[arg(long = "pdf-deny", value_delimiter = ',')]
+    pub pdf_deny: Vec<PdfPermission>,
+
+    /// Path to the ICC profile of the printing process that the PDF is
+    /// prepared for.
+    ///
+    /// Required for the PDF/X standards, which embed it as the document's
+    /// output intent. Print providers usually specify which profile to use.
+    #[arg(
+        long = "pdf-output-profile",
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath
+    )]
+    pub pdf_output_profile: Option<PathBuf>,
+
+    /// The registered name of the printing condition that the output profile
+    /// characterizes, like `FOGRA39`. Requires `--pdf-output-profile`.
+    ///
+    /// If omitted, the printing condition is described by the profile alone.
+    #[arg(
+        long = "pdf-output-condition",
+        value_name = "NAME",
+        requires = "pdf_output_profile"
+    )]
+    pub pdf_output_condition: Option<String>,
+
     /// The PPI (pixels per inch) to use for PNG export.
     #[arg(long = "ppi", default_value_t = 144.0)]
     pub ppi: f64,
@@ -418,6 +601,25 @@ pub struct WorldArgs {
     #[clap(flatten)]
     pub package: PackageArgs,
 
+    /// Persists font metadata, decoded images, and parsed package sources in
+    /// this directory, so that later runs can reuse them.
+    ///
+    /// This is useful when many documents that share fonts, images, and
+    /// packages are compiled in separate invocations, e.g. in CI. The
+    /// directory may be shared between concurrent runs and Typst versions.
+    #[clap(long = "cache-dir", env = "TYPST_CACHE_DIR", value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// The maximum size of the cache directory in megabytes. When it is
+    /// exceeded, the least recently used entries are removed.
+    #[clap(
+        long = "cache-limit",
+        env = "TYPST_CACHE_LIMIT",
+        value_name = "MB",
+        default_value_t = 1024
+    )]
+    pub cache_limit: u64,
+
     /// The document's creation date formatted as a UNIX timestamp.
     ///
     /// For more information, see <https://reproducible-builds.org/specs/source-date-epoch/>.
@@ -639,6 +841,8 @@ pub enum DiagnosticFormat {
     #[default]
     Human,
     Short,
+    Json,
+    Sarif,
 }
 
 display_possible_values!(DiagnosticFormat);
@@ -708,10 +912,40 @@ pub enum PdfStandard {
     /// PDF/UA-1.
     #[value(name = "ua-1")]
     UA_1,
+    /// PDF/X-1a:2003.
+    #[value(name = "x-1a")]
+    X_1a,
+    /// PDF/X-3:2003.
+    #[value(name = "x-3")]
+    X_3,
+    /// PDF/X-4.
+    #[value(name = "x-4")]
+    X_4,
 }
 
 display_possible_values!(PdfStandard);
 
+/// An operation that can be restricted in an encrypted PDF.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum PdfPermission {
+    /// Printing the document.
+    Print,
+    /// Printing the document at full quality instead of a low resolution.
+    HighQualityPrint,
+    /// Modifying the document's contents.
+    Modify,
+    /// Copying text and graphics.
+    Copy,
+    /// Adding annotations and filling in form fields.
+    Annotate,
+    /// Filling in form fields.
+    FillForms,
+    /// Inserting, rotating, or deleting pages.
+    Assemble,
+}
+
+display_possible_values!(PdfPermission);
+
 /// Output file format for query and info commands
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
 pub enum SerializationFormat {
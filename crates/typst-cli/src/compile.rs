@@ -9,7 +9,7 @@ use typst::diag::{
     At, HintedStrResult, HintedString, SourceDiagnostic, SourceResult, StrResult, Warned,
     bail,
 };
-use typst::foundations::{Datetime, Smart};
+use typst::foundations::{Bytes, Datetime, Smart};
 use typst::layout::PageRanges;
 use typst::syntax::Span;
 use typst_bundle::{Bundle, BundleOptions, VirtualFs};
@@ -17,14 +17,16 @@ use typst_html::{HtmlDocument, HtmlOptions};
 use typst_kit::diagnostics::DiagnosticWorld;
 use typst_kit::timer::Timer;
 use typst_layout::{Page, PagedDocument};
-use typst_pdf::{PdfOptions, PdfStandards, Timestamp};
+use typst_pdf::{
+    PdfEncryption, PdfOptions, PdfOutputIntent, PdfPermissions, PdfStandards, Timestamp,
+};
 use typst_render::RenderOptions;
 use typst_svg::SvgOptions;
 use typst_utils::Scalar;
 
 use crate::args::{
     CompileArgs, CompileCommand, DepsFormat, DiagnosticFormat, Input, Output,
-    OutputFormat, PdfStandard, WatchCommand,
+    OutputFormat, PdfPermission, PdfStandard, WatchCommand,
 };
 use crate::deps::write_deps;
 use crate::watch::Status;
@@ -74,6 +76,10 @@ pub struct CompileConfig {
     pub pdf_standards: PdfStandards,
     /// Whether to write PDF (accessibility) tags.
     pub tagged: bool,
+    /// How to encrypt the PDF, if at all.
+    pub pdf_encryption: Option<PdfEncryption>,
+    /// The printing condition a PDF/X file is prepared for.
+    pub pdf_output_intent: Option<PdfOutputIntent>,
     /// A destination to write a list of dependencies to.
     pub deps: Option<Output>,
     /// The format to use for dependencies.
@@ -181,6 +187,60 @@ impl CompileConfig {
             &args.pdf_standard.iter().copied().map(Into::into).collect::<Vec<_>>(),
         )?;
 
+        let encrypt =
+            args.pdf_user_password.is_some() || args.pdf_owner_password.is_some();
+        if !encrypt && !args.pdf_deny.is_empty() {
+            bail!(
+                "cannot deny permissions without encrypting the PDF";
+                hint: "add --pdf-user-password or --pdf-owner-password";
+            );
+        }
+
+        let pdf_encryption = if encrypt {
+            let mut permissions = PdfPermissions::default();
+            for permission in &args.pdf_deny {
+                match permission {
+                    PdfPermission::Print => permissions.print = false,
+                    PdfPermission::HighQualityPrint => {
+                        permissions.print_high_quality = false
+                    }
+                    PdfPermission::Modify => permissions.modify = false,
+                    PdfPermission::Copy => permissions.copy = false,
+                    PdfPermission::Annotate => permissions.annotate = false,
+                    PdfPermission::FillForms => permissions.fill_forms = false,
+                    PdfPermission::Assemble => permissions.assemble = false,
+                }
+            }
+
+            let encryption = PdfEncryption {
+                user_password: args.pdf_user_password.as_deref().unwrap_or("").into(),
+                owner_password: args.pdf_owner_password.as_deref().map(Into::into),
+                permissions,
+            };
+            encryption.validate(&pdf_standards)?;
+            Some(encryption)
+        } else {
+            None
+        };
+
+        let pdf_output_intent = match &args.pdf_output_profile {
+            Some(path) => {
+                let profile = std::fs::read(path).map_err(|err| {
+                    eco_format!(
+                        "failed to read output profile {} ({err})",
+                        path.display()
+                    )
+                })?;
+                let intent = PdfOutputIntent {
+                    profile: Bytes::new(profile),
+                    condition: args.pdf_output_condition.as_deref().map(Into::into),
+                };
+                intent.validate(&pdf_standards)?;
+                Some(intent)
+            }
+            None => None,
+        };
+
         #[cfg(feature = "http-server")]
         let server = if let Some(command) = watch
             && !command.server.no_serve
@@ -231,6 +291,8 @@ impl CompileConfig {
             pages,
             pdf_standards,
             tagged,
+            pdf_encryption,
+            pdf_output_intent,
             creation_timestamp: args
                 .world
                 .creation_timestamp
@@ -377,6 +439,11 @@ fn export_paged(
 
 /// Export to a PDF.
 fn export_pdf(document: &PagedDocument, config: &CompileConfig) -> SourceResult<()> {
+    // Unlike image export, PDF export doesn't use the `ExportCache`. Reusing
+    // the content streams of unchanged pages would need support from krilla:
+    // It serializes the whole document at once and the glyph codes in each
+    // page's content stream depend on the font subsets, which are built from
+    // all pages. Thus, a change on one page can change the bytes of others.
     let options = pdf_options(config);
     let buffer = typst_pdf::pdf(document, &options)?;
     config
@@ -533,7 +600,7 @@ fn export_image(
         .collect::<StrResult<Vec<Output>>>()
 }
 
-mod output_template {
+pub mod output_template {
     const INDEXABLE: [&str; 3] = ["{p}", "{0p}", "{n}"];
 
     pub fn has_indexable_template(output: &str) -> bool {
@@ -598,9 +665,25 @@ fn html_options(config: &CompileConfig) -> HtmlOptions {
 
 /// Creates options for PDF export.
 fn pdf_options(config: &CompileConfig) -> PdfOptions {
+    PdfOptions {
+        ident: Smart::Auto,
+        creator: Smart::Auto,
+        timestamp: pdf_timestamp(config.creation_timestamp),
+        page_ranges: config.pages.clone(),
+        standards: config.pdf_standards.clone(),
+        tagged: config.tagged,
+        pretty: config.pretty,
+        encryption: config.pdf_encryption.clone(),
+        signature: None,
+        output_intent: config.pdf_output_intent.clone(),
+    }
+}
+
+/// Determines the timestamp to write into a PDF.
+pub fn pdf_timestamp(creation_timestamp: Option<DateTime<Utc>>) -> Option<Timestamp> {
     // If the timestamp is provided through the CLI, use UTC suffix,
     // else, use the current local time and timezone.
-    let timestamp = match config.creation_timestamp {
+    match creation_timestamp {
         Some(timestamp) => convert_datetime(timestamp).map(Timestamp::new_utc),
         None => {
             let local_datetime = chrono::Local::now();
@@ -611,16 +694,6 @@ fn pdf_options(config: &CompileConfig) -> PdfOptions {
                 )
             })
         }
-    };
-
-    PdfOptions {
-        ident: Smart::Auto,
-        creator: Smart::Auto,
-        timestamp,
-        page_ranges: config.pages.clone(),
-        standards: config.pdf_standards.clone(),
-        tagged: config.tagged,
-        pretty: config.pretty,
     }
 }
 
@@ -728,6 +801,8 @@ pub fn print_diagnostics(
         match format {
             DiagnosticFormat::Human => typst_kit::diagnostics::DiagnosticFormat::Human,
             DiagnosticFormat::Short => typst_kit::diagnostics::DiagnosticFormat::Short,
+            DiagnosticFormat::Json => typst_kit::diagnostics::DiagnosticFormat::Json,
+            DiagnosticFormat::Sarif => typst_kit::diagnostics::DiagnosticFormat::Sarif,
         },
     )
 }
@@ -752,6 +827,9 @@ impl From<PdfStandard> for typst_pdf::PdfStandard {
             PdfStandard::A_4f => typst_pdf::PdfStandard::A_4f,
             PdfStandard::A_4e => typst_pdf::PdfStandard::A_4e,
             PdfStandard::UA_1 => typst_pdf::PdfStandard::Ua_1,
+            PdfStandard::X_1a => typst_pdf::PdfStandard::X_1a,
+            PdfStandard::X_3 => typst_pdf::PdfStandard::X_3,
+            PdfStandard::X_4 => typst_pdf::PdfStandard::X_4,
         }
     }
 }
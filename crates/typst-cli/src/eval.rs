@@ -13,6 +13,7 @@ use typst::syntax::{
     FileId, RangeMapper, RootedPath, Source, Span, SyntaxMode, VirtualPath, VirtualRoot,
 };
 use typst::text::{Font, FontBook};
+use typst::visualize::ImageStore;
 use typst::{World, engine::Sink, introspection::Introspector};
 use typst_bundle::Bundle;
 use typst_eval::eval_string;
@@ -213,4 +214,8 @@ impl World for ExpressionWorld {
     fn today(&self, offset: Option<Duration>) -> Option<Datetime> {
         self.world.today(offset)
     }
+
+    fn image_store(&self) -> Option<&dyn ImageStore> {
+        self.world.image_store()
+    }
 }
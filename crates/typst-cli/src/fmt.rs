@@ -0,0 +1,143 @@
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use ecow::eco_format;
+use typst::diag::{HintedStrResult, StrResult, bail};
+use typst::syntax::{FormatConfig, FormatError, format};
+
+use crate::args::{FmtCommand, Input};
+
+/// Execute a formatting command.
+pub fn fmt(command: &FmtCommand) -> HintedStrResult<()> {
+    let config = FormatConfig {
+        indent: command.indent,
+        max_width: command.max_width,
+    };
+
+    let mut unformatted = 0;
+    let mut failed = 0;
+    for input in &command.input {
+        match input {
+            Input::Stdin => {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|err| eco_format!("failed to read from stdin ({err})"))?;
+
+                let Some(formatted) = format_text(input, &text, &config, &mut failed)
+                else {
+                    continue;
+                };
+
+                if command.check {
+                    if formatted != text {
+                        println!("{input}");
+                        unformatted += 1;
+                    }
+                } else {
+                    std::io::stdout().lock().write_all(formatted.as_bytes()).map_err(
+                        |err| eco_format!("failed to write to stdout ({err})"),
+                    )?;
+                }
+            }
+            Input::Path(path) => {
+                let mut paths = vec![];
+                collect(path, &mut paths)?;
+                for path in paths {
+                    let text = std::fs::read_to_string(&path).map_err(|err| {
+                        eco_format!("failed to read {} ({err})", path.display())
+                    })?;
+
+                    let input = Input::Path(path.clone());
+                    let Some(formatted) =
+                        format_text(&input, &text, &config, &mut failed)
+                    else {
+                        continue;
+                    };
+
+                    if formatted == text {
+                        continue;
+                    }
+
+                    if command.check {
+                        println!("{}", path.display());
+                        unformatted += 1;
+                    } else {
+                        std::fs::write(&path, formatted).map_err(|err| {
+                            eco_format!("failed to write {} ({err})", path.display())
+                        })?;
+                    }
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        let noun = if failed == 1 { "file" } else { "files" };
+        bail!("failed to format {failed} {noun}");
+    }
+
+    if unformatted > 0 {
+        let noun = if unformatted == 1 { "file is" } else { "files are" };
+        bail!(
+            "{unformatted} {noun} not formatted";
+            hint: "run `typst fmt` without `--check` to format them"
+        );
+    }
+
+    Ok(())
+}
+
+/// Format the text of a single input.
+///
+/// If the input cannot be formatted, reports the error, counts the failure,
+/// and returns `None` so that the remaining inputs are still formatted.
+fn format_text(
+    input: &Input,
+    text: &str,
+    config: &FormatConfig,
+    failed: &mut usize,
+) -> Option<String> {
+    let err = match format(text, config) {
+        Ok(formatted) => return Some(formatted),
+        Err(err) => err,
+    };
+
+    crate::print_error(&eco_format!("failed to format {input} ({err})")).unwrap();
+    if err == FormatError::Erroneous {
+        crate::print_hint("run `typst compile` to see the syntax errors").unwrap();
+    }
+
+    *failed += 1;
+    None
+}
+
+/// Collect the Typst files at a path, searching directories recursively.
+pub fn collect(path: &Path, paths: &mut Vec<PathBuf>) -> StrResult<()> {
+    if !path.is_dir() {
+        paths.push(path.into());
+        return Ok(());
+    }
+
+    let entries = std::fs::read_dir(path)
+        .map_err(|err| eco_format!("failed to read {} ({err})", path.display()))?;
+
+    let mut children = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| !name.starts_with('.'))
+        })
+        .collect::<Vec<_>>();
+    children.sort();
+
+    for child in children {
+        if child.is_dir() || child.extension().is_some_and(|ext| ext == "typ") {
+            collect(&child, paths)?;
+        }
+    }
+
+    Ok(())
+}
@@ -5,13 +5,14 @@ use std::path::Path;
 use typst::text::{
     AxisValue, FontAxis, FontInfo, FontStretch, FontVariant, FontWeight, StandardAxes,
 };
+use typst_kit::cache::DiskCache;
 use typst_kit::fonts::{self, FontPath, FontStore};
 
 use crate::args::{FontArgs, FontsCommand};
 
 /// Execute a font listing command.
 pub fn fonts(command: &FontsCommand) {
-    let fonts = discover_fonts(&command.font);
+    let fonts = discover_fonts(&command.font, None);
 
     for (family, indices) in fonts.book().families() {
         println!("{family}");
@@ -34,12 +35,17 @@ pub fn fonts(command: &FontsCommand) {
 }
 
 /// Discovers the fonts as specified by the CLI flags.
+///
+/// If a cache is given, font metadata is reused from and stored in it.
 #[typst_macros::time(name = "discover fonts")]
-pub fn discover_fonts(args: &FontArgs) -> FontStore {
+pub fn discover_fonts(args: &FontArgs, cache: Option<&DiskCache>) -> FontStore {
     let mut fonts = FontStore::new();
 
     if !args.ignore_system_fonts {
-        fonts.extend(fonts::system());
+        match cache {
+            Some(cache) => fonts.extend(fonts::system_cached(cache)),
+            None => fonts.extend(fonts::system()),
+        }
     }
 
     #[cfg(feature = "embedded-fonts")]
@@ -48,7 +54,10 @@ pub fn discover_fonts(args: &FontArgs) -> FontStore {
     }
 
     for path in &args.font_paths {
-        fonts.extend(fonts::scan(path));
+        match cache {
+            Some(cache) => fonts.extend(fonts::scan_cached(path, cache)),
+            None => fonts.extend(fonts::scan(path)),
+        }
     }
 
     fonts
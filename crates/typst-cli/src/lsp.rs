@@ -0,0 +1,866 @@
+use std::collections::HashSet;
+use std::io::{self, BufRead, Read, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+
+use ecow::{EcoString, eco_format};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use typst::WorldExt;
+use typst::diag::{HintedStrResult, Severity, SourceDiagnostic, StrResult, Warned};
+use typst::syntax::{FileId, Side, Source};
+use typst_ide::{
+    Completion, CompletionKind, Definition, DocumentSymbol, SymbolKind, TextEdit,
+    TokenModifiers, TokenType, Tooltip,
+};
+use typst_layout::PagedDocument;
+use url::Url;
+
+use crate::args::{Input, LspCommand};
+use crate::world::SystemWorld;
+
+/// The JSON-RPC error code for a message that isn't valid JSON.
+pub const PARSE_ERROR: i64 = -32700;
+
+/// The JSON-RPC error code for a message that isn't a valid request.
+pub const INVALID_REQUEST: i64 = -32600;
+
+/// The JSON-RPC error code for an unknown method.
+pub const METHOD_NOT_FOUND: i64 = -32601;
+
+/// The JSON-RPC error code for malformed parameters.
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// The LSP error code for a valid request that could not be fulfilled.
+pub const REQUEST_FAILED: i64 = -32803;
+
+/// The largest message body we accept from a client, in bytes.
+const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Execute the language server command.
+pub fn lsp(command: &'static LspCommand) -> HintedStrResult<()> {
+    let input = command.main.clone().map(Input::Path);
+    let world = SystemWorld::new(input.as_ref(), &command.world, &command.process)
+        .map_err(|err| eco_format!("{err}"))?;
+
+    let mut server = Server {
+        pinned: command.main.is_some(),
+        world,
+        document: None,
+        diagnostics: vec![],
+        published: HashSet::new(),
+        out: io::stdout().lock(),
+    };
+
+    // A single bad message or failed response must not take down the whole
+    // server. Only when reading from the client fails, we stop.
+    let mut input = io::stdin().lock();
+    while let Some(message) = read_message(&mut input)? {
+        let result = match message {
+            Ok(message) => server.handle(message),
+            Err(err) => server.send(&respond(Value::Null, Err(err))).map(|_| true),
+        };
+
+        match result {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(err) => _ = crate::print_error(&err),
+        }
+    }
+
+    Ok(())
+}
+
+/// The state of a running language server.
+struct Server<W> {
+    /// Whether the main file was fixed on the command line.
+    pinned: bool,
+    /// The world that serves sources, files, and fonts.
+    world: SystemWorld,
+    /// The most recently successfully compiled document.
+    document: Option<PagedDocument>,
+    /// The diagnostics of the most recent compilation.
+    diagnostics: Vec<SourceDiagnostic>,
+    /// Files for which diagnostics were last published.
+    published: HashSet<FileId>,
+    /// Where to write messages to the client.
+    out: W,
+}
+
+impl<W: Write> Server<W> {
+    /// Handle a single message from the client.
+    ///
+    /// Returns whether the server should keep running.
+    fn handle(&mut self, message: Value) -> StrResult<bool> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // We never send requests, so we only expect responses without a
+            // method. Anything else is not a valid message.
+            if message.get("result").is_none() && message.get("error").is_none() {
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                let err = (INVALID_REQUEST, "message is not a valid request".into());
+                self.send(&respond(id, Err(err)))?;
+            }
+            return Ok(true);
+        };
+
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let Some(id) = message.get("id").cloned() else {
+            return self.notify(method, params);
+        };
+
+        let result = match method {
+            "initialize" => Ok(capabilities()),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/completion" => parse(params).map(|p| self.completion(p)),
+            "textDocument/hover" => parse(params).map(|p| self.hover(p)),
+            "textDocument/signatureHelp" => parse(params).map(|p| self.signature_help(p)),
+            "textDocument/definition" => parse(params).map(|p| self.definition(p)),
+            "textDocument/references" => parse(params).map(|p| self.references(p)),
+            "textDocument/rename" => parse(params).and_then(|p| self.rename(p)),
+            "textDocument/semanticTokens/full" => {
+                parse(params).map(|p| self.semantic_tokens(p))
+            }
+            "textDocument/documentSymbol" => {
+                parse(params).map(|p| self.document_symbols(p))
+            }
+            "textDocument/codeAction" => parse(params).map(|p| self.code_actions(p)),
+            _ => Err((METHOD_NOT_FOUND, eco_format!("unknown method `{method}`"))),
+        };
+
+        self.send(&respond(id, result))?;
+        Ok(true)
+    }
+
+    /// Handle a notification, which does not receive a response.
+    fn notify(&mut self, method: &str, params: Value) -> StrResult<bool> {
+        match method {
+            "exit" => return Ok(false),
+            "textDocument/didOpen" => {
+                let Ok(params) = parse::<DidOpenParams>(params) else { return Ok(true) };
+                let Some(id) = self.file_id(&params.text_document.uri) else {
+                    return Ok(true);
+                };
+                self.world.shadow(id, params.text_document.text);
+                if !self.pinned {
+                    self.world.set_main(id);
+                }
+            }
+            "textDocument/didChange" => {
+                let Ok(params) = parse::<DidChangeParams>(params) else {
+                    return Ok(true);
+                };
+                let Some(id) = self.file_id(&params.text_document.uri) else {
+                    return Ok(true);
+                };
+                for change in params.content_changes {
+                    match change.range {
+                        Some(range) => {
+                            let Some(source) = self.world.shadowed(id) else { break };
+                            let Some(replace) = to_byte_range(source, range) else {
+                                continue;
+                            };
+                            self.world.edit_shadow(id, replace, &change.text);
+                        }
+                        None => self.world.shadow(id, change.text),
+                    }
+                }
+            }
+            // Saving may create a file on disk and closing may reveal that an
+            // unsaved buffer has no file on disk, so the set of files changes.
+            "textDocument/didSave" => self.world.invalidate_files(),
+            "textDocument/didClose" => {
+                let Ok(params) = parse::<DidCloseParams>(params) else { return Ok(true) };
+                if let Some(id) = self.file_id(&params.text_document.uri) {
+                    self.world.unshadow(id);
+                }
+                self.world.invalidate_files();
+            }
+            "workspace/didChangeWatchedFiles" => self.world.invalidate_files(),
+            _ => return Ok(true),
+        }
+
+        self.compile()?;
+        Ok(true)
+    }
+
+    /// Recompile the main file and publish the resulting diagnostics.
+    fn compile(&mut self) -> StrResult<()> {
+        self.world.reset();
+
+        let Warned { output, warnings } = typst::compile::<PagedDocument>(&self.world);
+        self.diagnostics = warnings.into_iter().collect();
+        match output {
+            Ok(document) => self.document = Some(document),
+            Err(errors) => self.diagnostics.extend(errors),
+        }
+
+        comemo::evict(10);
+        self.publish()
+    }
+
+    /// Send the diagnostics to the client, grouped by file.
+    fn publish(&mut self) -> StrResult<()> {
+        let main = self.world.main();
+        let mut files: Vec<(FileId, Vec<Value>)> =
+            self.published.drain().map(|id| (id, vec![])).collect();
+
+        for diagnostic in &self.diagnostics {
+            // Attach diagnostics without a location to the main file.
+            let id = diagnostic.span.id().unwrap_or(main);
+            let Ok(source) = self.world.source(id) else { continue };
+            let range = self.world.range(diagnostic.span).unwrap_or(0..0);
+
+            let mut message = diagnostic.message.to_string();
+            for hint in &diagnostic.hints {
+                message.push_str("\nhint: ");
+                message.push_str(&hint.v);
+            }
+
+            let value = json!({
+                "range": to_lsp_range(&source, range),
+                "severity": match diagnostic.severity {
+                    Severity::Error => 1,
+                    Severity::Warning => 2,
+                },
+                "source": "typst",
+                "message": message,
+            });
+
+            match files.iter_mut().find(|(other, _)| *other == id) {
+                Some((_, values)) => values.push(value),
+                None => files.push((id, vec![value])),
+            }
+        }
+
+        for (id, values) in files {
+            let Some(uri) = self.uri(id) else { continue };
+            if !values.is_empty() {
+                self.published.insert(id);
+            }
+            self.send(&json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": { "uri": uri, "diagnostics": values },
+            }))?;
+        }
+
+        Ok(())
+    }
+
+    /// Provide completions at a position.
+    fn completion(&self, params: CompletionParams) -> Value {
+        let Some((source, cursor)) = self.locate(&params.position) else {
+            return Value::Null;
+        };
+
+        // A trigger kind of 1 means that completion was explicitly invoked.
+        let explicit = params.context.is_none_or(|context| context.trigger_kind == 1);
+        let Some((from, completions)) = typst_ide::autocomplete(
+            &self.world,
+            self.document.as_ref(),
+            &source,
+            cursor,
+            explicit,
+        ) else {
+            return Value::Null;
+        };
+
+        let range = to_lsp_range(&source, from..cursor);
+        let items: Vec<Value> = completions
+            .iter()
+            .map(|completion| completion_item(completion, &range))
+            .collect();
+
+        json!({ "isIncomplete": false, "items": items })
+    }
+
+    /// Provide a hover tooltip at a position.
+    fn hover(&self, params: PositionParams) -> Value {
+        let Some((source, cursor)) = self.locate(&params) else { return Value::Null };
+        let Some(tooltip) = typst_ide::tooltip(
+            &self.world,
+            self.document.as_ref(),
+            &source,
+            cursor,
+            Side::After,
+        ) else {
+            return Value::Null;
+        };
+
+        let value = match tooltip {
+            Tooltip::Text(text) => text,
+            Tooltip::Code(code) => eco_format!("```typc\n{code}\n```"),
+        };
+
+        json!({ "contents": { "kind": "markdown", "value": value } })
+    }
+
+    /// Provide the signature of the function whose arguments are at a
+    /// position.
+    fn signature_help(&self, params: PositionParams) -> Value {
+        let Some((source, cursor)) = self.locate(&params) else { return Value::Null };
+        let Some(help) = typst_ide::signature_help(
+            &self.world,
+            self.document.as_ref(),
+            &source,
+            cursor,
+        ) else {
+            return Value::Null;
+        };
+
+        // Parameter ranges are given as UTF-16 offsets into the label.
+        let utf16 = |byte: usize| help.label[..byte].encode_utf16().count();
+        let parameters: Vec<Value> = help
+            .params
+            .iter()
+            .map(|param| {
+                json!({
+                    "label": [utf16(param.range.start), utf16(param.range.end)],
+                    "documentation": param.docs,
+                })
+            })
+            .collect();
+
+        json!({
+            "signatures": [{
+                "label": help.label,
+                "documentation": help.docs,
+                "parameters": parameters,
+            }],
+            "activeSignature": 0,
+            "activeParameter": help.active,
+        })
+    }
+
+    /// Find the definition of the item at a position.
+    fn definition(&self, params: PositionParams) -> Value {
+        let Some((source, cursor)) = self.locate(&params) else { return Value::Null };
+        let Some(definition) = typst_ide::definition(
+            &self.world,
+            self.document.as_ref(),
+            &source,
+            cursor,
+            Side::After,
+        ) else {
+            return Value::Null;
+        };
+
+        let (id, range) = match definition {
+            Definition::Span(span) => {
+                let (Some(id), Some(range)) = (span.id(), self.world.range(span)) else {
+                    return Value::Null;
+                };
+                (id, range)
+            }
+            Definition::File(id) => (id, 0..0),
+            Definition::Std(_) => return Value::Null,
+        };
+
+        let (Some(uri), Ok(source)) = (self.uri(id), self.world.source(id)) else {
+            return Value::Null;
+        };
+
+        json!({ "uri": uri, "range": to_lsp_range(&source, range) })
+    }
+
+    /// Find all references to the item at a position.
+    fn references(&self, params: ReferenceParams) -> Value {
+        let Some((source, cursor)) = self.locate(&params.position) else {
+            return Value::Null;
+        };
+
+        let locations = typst_ide::references(&self.world, &source, cursor)
+            .into_iter()
+            .filter(|reference| {
+                params.context.include_declaration || !reference.declaration
+            })
+            .filter_map(|reference| {
+                let uri = self.uri(reference.id)?;
+                let source = self.world.source(reference.id).ok()?;
+                Some(json!({
+                    "uri": uri,
+                    "range": to_lsp_range(&source, reference.range),
+                }))
+            })
+            .collect::<Vec<_>>();
+
+        Value::Array(locations)
+    }
+
+    /// Rename the item at a position and all references to it.
+    fn rename(&self, params: RenameParams) -> Result<Value, (i64, EcoString)> {
+        let Some((source, cursor)) = self.locate(&params.position) else {
+            return Ok(Value::Null);
+        };
+
+        let edits = typst_ide::rename(&self.world, &source, cursor, &params.new_name)
+            .map_err(|message| (REQUEST_FAILED, message))?;
+
+        Ok(self.workspace_edit(edits))
+    }
+
+    /// Provide the semantic tokens of a whole document.
+    fn semantic_tokens(&self, params: DocumentParams) -> Value {
+        let Some(source) = self.source(&params.text_document) else { return Value::Null };
+        let data: Vec<u32> = typst_ide::semantic_tokens(&source)
+            .into_iter()
+            .flat_map(|token| {
+                [
+                    token.delta_line,
+                    token.delta_start,
+                    token.length,
+                    token.ty as u32,
+                    token.modifiers.bits(),
+                ]
+            })
+            .collect();
+        json!({ "data": data })
+    }
+
+    /// Provide the outline of a document.
+    fn document_symbols(&self, params: DocumentParams) -> Value {
+        let Some(source) = self.source(&params.text_document) else { return Value::Null };
+        let symbols = typst_ide::document_symbols(&source);
+        Value::Array(
+            symbols
+                .iter()
+                .map(|symbol| document_symbol(&source, symbol))
+                .collect(),
+        )
+    }
+
+    /// Provide quick fixes for the diagnostics in a range of a document.
+    fn code_actions(&self, params: CodeActionParams) -> Value {
+        let Some(source) = self.source(&params.text_document) else { return Value::Null };
+        let Some(range) = to_byte_range(&source, params.range) else {
+            return Value::Null;
+        };
+        let actions =
+            typst_ide::code_actions(&self.world, &source, range, &self.diagnostics);
+        Value::Array(
+            actions
+                .into_iter()
+                .map(|action| {
+                    json!({
+                        "title": action.title,
+                        "kind": "quickfix",
+                        "edit": self.workspace_edit(action.edits),
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Group text edits by file into an LSP workspace edit.
+    fn workspace_edit(&self, edits: Vec<TextEdit>) -> Value {
+        let mut changes = serde_json::Map::new();
+        for edit in edits {
+            let (Some(uri), Ok(source)) = (self.uri(edit.id), self.world.source(edit.id))
+            else {
+                continue;
+            };
+            let Value::Array(list) = changes.entry(uri).or_insert_with(|| json!([]))
+            else {
+                continue;
+            };
+            list.push(json!({
+                "range": to_lsp_range(&source, edit.range),
+                "newText": edit.text,
+            }));
+        }
+        json!({ "changes": changes })
+    }
+
+    /// Resolve a text document to its source.
+    fn source(&self, document: &TextDocumentIdentifier) -> Option<Source> {
+        let id = self.file_id(&document.uri)?;
+        self.world.source(id).ok()
+    }
+
+    /// Resolve a text document position to a source and byte offset.
+    fn locate(&self, params: &PositionParams) -> Option<(Source, usize)> {
+        let source = self.source(&params.text_document)?;
+        let cursor = to_byte(&source, &params.position)?;
+        Some((source, cursor))
+    }
+
+    /// Determine the file id for a document URI.
+    fn file_id(&self, uri: &str) -> Option<FileId> {
+        let path = Url::parse(uri).ok()?.to_file_path().ok()?;
+        let path = path.canonicalize().unwrap_or(path);
+        self.world.id(&path)
+    }
+
+    /// Determine the document URI for a file id.
+    fn uri(&self, id: FileId) -> Option<String> {
+        let path: PathBuf = self.world.path(id).ok()?;
+        Url::from_file_path(path).ok().map(Into::into)
+    }
+
+    /// Write a message to the client.
+    fn send(&mut self, message: &Value) -> StrResult<()> {
+        write_message(&mut self.out, message)
+    }
+}
+
+/// The capabilities announced to the client upon initialization.
+fn capabilities() -> Value {
+    json!({
+        "capabilities": {
+            "positionEncoding": "utf-16",
+            // Incremental synchronization, so that we can reparse efficiently.
+            "textDocumentSync": { "openClose": true, "change": 2, "save": true },
+            "completionProvider": {
+                "triggerCharacters": ["#", ".", "@", "<", "(", ",", ":", "/", "\"", "$"],
+            },
+            "hoverProvider": true,
+            "signatureHelpProvider": { "triggerCharacters": ["(", ","] },
+            "definitionProvider": true,
+            "referencesProvider": true,
+            "renameProvider": true,
+            "semanticTokensProvider": {
+                "legend": {
+                    "tokenTypes": TokenType::LIST
+                        .iter()
+                        .map(|ty| ty.name())
+                        .collect::<Vec<_>>(),
+                    "tokenModifiers": TokenModifiers::NAMES,
+                },
+                "full": true,
+            },
+            "documentSymbolProvider": true,
+            "codeActionProvider": { "codeActionKinds": ["quickfix"] },
+        },
+        "serverInfo": {
+            "name": "typst",
+            "version": typst_utils::version().raw(),
+        },
+    })
+}
+
+/// Convert a completion into an LSP completion item.
+fn completion_item(completion: &Completion, range: &Value) -> Value {
+    let kind = match completion.kind {
+        CompletionKind::Syntax => 15,
+        CompletionKind::Func => 3,
+        CompletionKind::Type => 7,
+        CompletionKind::Param => 5,
+        CompletionKind::Constant => 21,
+        CompletionKind::Path => 17,
+        CompletionKind::Package => 9,
+        CompletionKind::Label => 18,
+        CompletionKind::Font => 12,
+        CompletionKind::Symbol(_) => 1,
+    };
+
+    let apply = completion.apply.as_ref().unwrap_or(&completion.label);
+    json!({
+        "label": completion.label,
+        "kind": kind,
+        "detail": completion.detail,
+        "insertTextFormat": 2,
+        "textEdit": { "range": range, "newText": snippet(apply) },
+    })
+}
+
+/// Convert a symbol of the outline into an LSP document symbol.
+fn document_symbol(source: &Source, symbol: &DocumentSymbol) -> Value {
+    let kind = match symbol.kind {
+        SymbolKind::Heading(_) => 15,
+        SymbolKind::Variable => 13,
+        SymbolKind::Function => 12,
+        SymbolKind::Label => 20,
+    };
+
+    json!({
+        "name": symbol.name,
+        "kind": kind,
+        "range": to_lsp_range(source, symbol.range.clone()),
+        "selectionRange": to_lsp_range(source, symbol.selection.clone()),
+        "children": symbol
+            .children
+            .iter()
+            .map(|child| document_symbol(source, child))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Convert Typst's snippet syntax to the one of LSP.
+///
+/// Typst uses `${name}` for named placeholders and `${}` for plain tab stops.
+/// LSP needs numbered tab stops and requires escaping of other dollar signs.
+fn snippet(apply: &str) -> String {
+    let mut out = String::new();
+    let mut rest = apply;
+    let mut index = 0;
+    while let Some(c) = rest.chars().next() {
+        if let Some(inner) = rest.strip_prefix("${")
+            && let Some(end) = inner.find('}')
+        {
+            index += 1;
+            let name = &inner[..end];
+            // Typst also accepts LSP-style numbered placeholders.
+            let name = name
+                .split_once(':')
+                .filter(|(n, _)| n.parse::<usize>().is_ok())
+                .map_or(name, |(_, name)| name);
+            if name.is_empty() {
+                out.push_str(&format!("${index}"));
+            } else {
+                out.push_str(&format!("${{{index}:{name}}}"));
+            }
+            rest = &inner[end + 1..];
+            continue;
+        }
+
+        if matches!(c, '$' | '}' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+/// Convert an LSP position to a byte offset in the source.
+fn to_byte(source: &Source, position: &Position) -> Option<usize> {
+    let lines = source.lines();
+    let line = lines.line_to_range(position.line)?;
+    let utf16 = lines.byte_to_utf16(line.start)? + position.character;
+    Some(lines.utf16_to_byte(utf16).unwrap_or(line.end).min(line.end))
+}
+
+/// Convert an LSP range to a byte range in the source.
+fn to_byte_range(source: &Source, range: LspRange) -> Option<Range<usize>> {
+    let start = to_byte(source, &range.start)?;
+    let end = to_byte(source, &range.end)?;
+    Some(start..end.max(start))
+}
+
+/// Convert a byte offset in the source to an LSP position.
+fn to_lsp_position(source: &Source, byte: usize) -> Value {
+    let lines = source.lines();
+    let byte = byte.min(lines.len_bytes());
+    let line = lines.byte_to_line(byte).unwrap_or(0);
+    let start = lines.line_to_byte(line).unwrap_or(0);
+    let character = lines
+        .byte_to_utf16(byte)
+        .zip(lines.byte_to_utf16(start))
+        .map_or(0, |(end, start)| end.saturating_sub(start));
+    json!({ "line": line, "character": character })
+}
+
+/// Convert a byte range in the source to an LSP range.
+fn to_lsp_range(source: &Source, range: Range<usize>) -> Value {
+    json!({
+        "start": to_lsp_position(source, range.start),
+        "end": to_lsp_position(source, range.end),
+    })
+}
+
+/// Deserialize the parameters of a message.
+pub fn parse<T: DeserializeOwned>(params: Value) -> Result<T, (i64, EcoString)> {
+    serde_json::from_value(params)
+        .map_err(|err| (INVALID_PARAMS, eco_format!("invalid parameters ({err})")))
+}
+
+/// Build the response to a request.
+pub fn respond(id: Value, result: Result<Value, (i64, EcoString)>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }),
+    }
+}
+
+/// Write a single message with a `Content-Length` header.
+pub fn write_message(out: &mut impl Write, message: &Value) -> StrResult<()> {
+    let body = message.to_string();
+    write!(out, "Content-Length: {}\r\n\r\n{body}", body.len())
+        .and_then(|_| out.flush())
+        .map_err(|err| eco_format!("failed to write to client ({err})"))
+}
+
+/// Read a single message with its `Content-Length` header.
+///
+/// Returns `None` when the client closed the connection. A message that can't
+/// be understood results in the JSON-RPC error to reply with, while the
+/// connection stays usable.
+pub fn read_message(
+    input: &mut impl BufRead,
+) -> StrResult<Option<Result<Value, (i64, EcoString)>>> {
+    let mut length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = input
+            .read_line(&mut line)
+            .map_err(|err| eco_format!("failed to read from client ({err})"))?;
+        if read == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let Some(length) = length else {
+        return Ok(Some(Err((
+            INVALID_REQUEST,
+            "message is missing a valid `Content-Length` header".into(),
+        ))));
+    };
+
+    // Skip oversized messages instead of allocating whatever the client asks
+    // for, so that the connection stays usable.
+    if length > MAX_MESSAGE_SIZE {
+        io::copy(&mut input.by_ref().take(length as u64), &mut io::sink())
+            .map_err(|err| eco_format!("failed to read from client ({err})"))?;
+        return Ok(Some(Err((
+            INVALID_REQUEST,
+            eco_format!("message is larger than {MAX_MESSAGE_SIZE} bytes"),
+        ))));
+    }
+
+    let mut buf = vec![0; length];
+    input
+        .read_exact(&mut buf)
+        .map_err(|err| eco_format!("failed to read from client ({err})"))?;
+
+    Ok(Some(
+        serde_json::from_slice(&buf).map_err(|err| {
+            (PARSE_ERROR, eco_format!("received malformed message ({err})"))
+        }),
+    ))
+}
+
+/// Identifies a text document.
+#[derive(Deserialize)]
+struct TextDocumentIdentifier {
+    uri: String,
+}
+
+/// A text document with its full contents.
+#[derive(Deserialize)]
+struct TextDocumentItem {
+    uri: String,
+    text: String,
+}
+
+/// A zero-based line and UTF-16 column.
+#[derive(Deserialize)]
+struct Position {
+    line: usize,
+    character: usize,
+}
+
+/// A range between two positions.
+#[derive(Deserialize)]
+struct LspRange {
+    start: Position,
+    end: Position,
+}
+
+/// Parameters of a `textDocument/didOpen` notification.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidOpenParams {
+    text_document: TextDocumentItem,
+}
+
+/// Parameters of a `textDocument/didChange` notification.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidChangeParams {
+    text_document: TextDocumentIdentifier,
+    content_changes: Vec<ContentChange>,
+}
+
+/// A single change in a `textDocument/didChange` notification. Without a
+/// range, the text replaces the whole document.
+#[derive(Deserialize)]
+struct ContentChange {
+    range: Option<LspRange>,
+    text: String,
+}
+
+/// Parameters of a `textDocument/didClose` notification.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidCloseParams {
+    text_document: TextDocumentIdentifier,
+}
+
+/// Parameters of requests that target a position in a document.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionParams {
+    text_document: TextDocumentIdentifier,
+    position: Position,
+}
+
+/// Parameters of requests that target a whole document.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentParams {
+    text_document: TextDocumentIdentifier,
+}
+
+/// Parameters of a `textDocument/codeAction` request.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CodeActionParams {
+    text_document: TextDocumentIdentifier,
+    range: LspRange,
+}
+
+/// Parameters of a `textDocument/references` request.
+#[derive(Deserialize)]
+struct ReferenceParams {
+    #[serde(flatten)]
+    position: PositionParams,
+    context: ReferenceContext,
+}
+
+/// Which references to include in the response.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReferenceContext {
+    include_declaration: bool,
+}
+
+/// Parameters of a `textDocument/rename` request.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RenameParams {
+    #[serde(flatten)]
+    position: PositionParams,
+    new_name: String,
+}
+
+/// Parameters of a `textDocument/completion` request.
+#[derive(Deserialize)]
+struct CompletionParams {
+    #[serde(flatten)]
+    position: PositionParams,
+    context: Option<CompletionContext>,
+}
+
+/// How a completion was triggered.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletionContext {
+    trigger_kind: u8,
+}
@@ -4,13 +4,17 @@ mod completions;
 mod deps;
 mod download;
 mod eval;
+mod fmt;
 mod fonts;
 mod greet;
 mod info;
 mod init;
+mod lsp;
 mod packages;
 mod query;
+mod serve;
 mod terminal;
+mod test;
 #[cfg(feature = "self-update")]
 mod update;
 mod watch;
@@ -74,6 +78,10 @@ fn dispatch() -> HintedStrResult<()> {
         Command::Query(command) => crate::query::query(command)?,
         Command::Eval(command) => crate::eval::eval(command)?,
         Command::Fonts(command) => crate::fonts::fonts(command),
+        Command::Lsp(command) => crate::lsp::lsp(command)?,
+        Command::ServeCompile(command) => crate::serve::serve(command)?,
+        Command::Fmt(command) => crate::fmt::fmt(command)?,
+        Command::Test(command) => crate::test::test(command)?,
         Command::Update(command) => crate::update::update(command)?,
         Command::Completions(command) => crate::completions::completions(command),
         Command::Info(command) => crate::info::info(command)?,
@@ -0,0 +1,427 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ecow::{EcoString, eco_format};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use typst::diag::{At, HintedStrResult, SourceResult, StrResult, Warned};
+use typst::foundations::{Dict, IntoValue, Smart};
+use typst::layout::PageRanges;
+use typst::syntax::Span;
+use typst_html::{HtmlDocument, HtmlOptions};
+use typst_kit::datetime::Time;
+use typst_layout::PagedDocument;
+use typst_pdf::{PdfOptions, PdfStandards};
+use typst_render::RenderOptions;
+use typst_svg::SvgOptions;
+use typst_utils::Scalar;
+
+use crate::args::{Pages, ProcessArgs, ServeCompileCommand};
+use crate::compile::{output_template, pdf_timestamp};
+use crate::lsp::{
+    INVALID_PARAMS, METHOD_NOT_FOUND, REQUEST_FAILED, parse, read_message, respond,
+    write_message,
+};
+use crate::world::{SystemWorld, build_library};
+
+/// Execute a compile server command.
+pub fn serve(command: &'static ServeCompileCommand) -> HintedStrResult<()> {
+    let world = SystemWorld::new(None, &command.world, &command.process)
+        .map_err(|err| eco_format!("{err}"))?;
+
+    let mut server = Server {
+        world,
+        process: &command.process,
+        inputs: &command.world.inputs,
+        creation_timestamp: command.world.creation_timestamp,
+    };
+
+    match &command.socket {
+        Some(path) => server.listen(path),
+        None => {
+            server.connection(&mut io::stdin().lock(), &mut io::stdout().lock())?;
+            Ok(())
+        }
+    }
+}
+
+/// The state of a running compile server.
+struct Server {
+    /// The world that serves sources, files, and fonts. It is kept around
+    /// between requests so that its caches stay warm.
+    world: SystemWorld,
+    /// The arguments that configure the standard library.
+    process: &'static ProcessArgs,
+    /// The default `sys.inputs` from the command line.
+    inputs: &'static [(String, String)],
+    /// The default creation timestamp from the command line.
+    creation_timestamp: Option<i64>,
+}
+
+impl Server {
+    /// Serve clients that connect to a Unix domain socket, one at a time.
+    #[cfg(unix)]
+    fn listen(&mut self, path: &Path) -> HintedStrResult<()> {
+        use std::io::BufReader;
+        use std::os::unix::fs::FileTypeExt;
+        use std::os::unix::net::UnixListener;
+
+        // A socket left behind by a server that didn't shut down cleanly would
+        // make binding fail.
+        if std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket())
+        {
+            std::fs::remove_file(path).map_err(|err| {
+                eco_format!("failed to remove stale socket {} ({err})", path.display())
+            })?;
+        }
+
+        let listener = UnixListener::bind(path).map_err(|err| {
+            eco_format!("failed to listen on {} ({err})", path.display())
+        })?;
+
+        let result = (|| -> HintedStrResult<()> {
+            for stream in listener.incoming() {
+                let stream = stream
+                    .map_err(|err| eco_format!("failed to accept connection ({err})"))?;
+                let mut reader = BufReader::new(&stream);
+                let mut writer = &stream;
+                // A failing client must not take down the server.
+                match self.connection(&mut reader, &mut writer) {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    Err(err) => {
+                        crate::print_error(&eco_format!("dropped client ({err})")).ok();
+                    }
+                }
+            }
+            Ok(())
+        })();
+
+        std::fs::remove_file(path).ok();
+        result
+    }
+
+    /// Serve clients that connect to a Unix domain socket, one at a time.
+    #[cfg(not(unix))]
+    fn listen(&mut self, _: &Path) -> HintedStrResult<()> {
+        typst::diag::bail!(
+            "Unix domain sockets are not supported on this platform";
+            hint: "omit `--socket` to communicate over stdio"
+        )
+    }
+
+    /// Handle messages from one client until it disconnects.
+    ///
+    /// Returns whether the server should keep running.
+    fn connection(
+        &mut self,
+        input: &mut impl BufRead,
+        out: &mut impl Write,
+    ) -> StrResult<bool> {
+        while let Some(message) = read_message(input)? {
+            let message = match message {
+                Ok(message) => message,
+                Err(err) => {
+                    write_message(out, &respond(Value::Null, Err(err)))?;
+                    continue;
+                }
+            };
+
+            let Some(method) = message.get("method").and_then(Value::as_str) else {
+                continue;
+            };
+
+            if method == "exit" {
+                return Ok(false);
+            }
+
+            // Notifications don't receive a response.
+            let Some(id) = message.get("id").cloned() else { continue };
+
+            let params = message.get("params").cloned().unwrap_or(Value::Null);
+            let result = match method {
+                "compile" => parse(params).and_then(|p| self.compile(p)),
+                "shutdown" => Ok(Value::Null),
+                _ => Err((METHOD_NOT_FOUND, eco_format!("unknown method `{method}`"))),
+            };
+
+            write_message(out, &respond(id, result))?;
+        }
+
+        Ok(true)
+    }
+
+    /// Compile a document and export it.
+    fn compile(&mut self, params: CompileParams) -> Result<Value, (i64, EcoString)> {
+        let pages = params
+            .pages
+            .as_deref()
+            .map(parse_pages)
+            .transpose()
+            .map_err(|err| (INVALID_PARAMS, err))?;
+
+        let timestamp = params.creation_timestamp.or(self.creation_timestamp);
+        let creation_timestamp = timestamp
+            .map(|time| {
+                DateTime::from_timestamp(time, 0)
+                    .ok_or_else(|| EcoString::from("creation timestamp is out of range"))
+            })
+            .transpose()
+            .map_err(|err| (INVALID_PARAMS, err))?;
+
+        let path = self.world.workdir().join(&params.input);
+        let path = path.canonicalize().map_err(|err| {
+            (REQUEST_FAILED, eco_format!("failed to read {} ({err})", path.display()))
+        })?;
+        let Some(id) = self.world.id(&path) else {
+            return Err((
+                REQUEST_FAILED,
+                eco_format!(
+                    "input file {} is outside of the project root",
+                    path.display()
+                ),
+            ));
+        };
+
+        // The request's inputs take precedence over the command line's.
+        let inputs: Dict = self
+            .inputs
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .chain(params.inputs.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .map(|(k, v)| (k.into(), v.into_value()))
+            .collect();
+
+        self.world.set_library(build_library(inputs, self.process));
+        self.world.set_main(id);
+        self.world.reset();
+        self.world.set_time(match timestamp {
+            Some(time) => {
+                Time::fixed_timestamp(time).map_err(|err| (INVALID_PARAMS, err))?
+            }
+            None => Time::system(),
+        });
+
+        let config = ExportConfig {
+            pages,
+            ppi: params.ppi,
+            pretty: params.pretty,
+            creation_timestamp,
+        };
+
+        let Warned { output, warnings } = match params.format {
+            Format::Pdf | Format::Png | Format::Svg => {
+                let Warned { output, warnings } =
+                    typst::compile::<PagedDocument>(&self.world);
+                let output = output
+                    .and_then(|document| export_paged(&document, params.format, &config));
+                Warned { output, warnings }
+            }
+            Format::Html => {
+                let Warned { output, warnings } =
+                    typst::compile::<HtmlDocument>(&self.world);
+                let output = output.and_then(|document| {
+                    let html = typst_html::html(
+                        &document,
+                        &HtmlOptions { pretty: config.pretty },
+                    )?;
+                    Ok(vec![Artifact { page: None, data: html.into_bytes() }])
+                });
+                Warned { output, warnings }
+            }
+        };
+
+        // Keep the caches warm, but don't let them grow indefinitely.
+        comemo::evict(10);
+
+        let (artifacts, errors) = match output {
+            Ok(artifacts) => (artifacts, vec![]),
+            Err(errors) => (vec![], errors.into_iter().collect::<Vec<_>>()),
+        };
+
+        let artifacts = match &params.output {
+            Some(output) => write_artifacts(output, &artifacts)
+                .map_err(|err| (REQUEST_FAILED, err))?,
+            None => artifacts
+                .iter()
+                .map(|artifact| {
+                    json!({
+                        "page": artifact.page,
+                        "data": base64::engine::general_purpose::STANDARD
+                            .encode(&artifact.data),
+                    })
+                })
+                .collect(),
+        };
+
+        let diagnostics = typst_kit::diagnostics::to_json(
+            &self.world,
+            errors.iter().chain(warnings.iter()),
+        );
+
+        Ok(json!({
+            "success": errors.is_empty(),
+            "artifacts": artifacts,
+            "diagnostics": diagnostics,
+        }))
+    }
+}
+
+/// Settings for exporting a compiled document.
+struct ExportConfig {
+    /// Which pages to export.
+    pages: Option<PageRanges>,
+    /// The PPI (pixels per inch) to use for PNG export.
+    ppi: f64,
+    /// Whether to make the output pretty.
+    pretty: bool,
+    /// The document's creation date.
+    creation_timestamp: Option<DateTime<Utc>>,
+}
+
+/// An exported file.
+struct Artifact {
+    /// The one-based page number, for formats that export pages separately.
+    page: Option<usize>,
+    /// The file's contents.
+    data: Vec<u8>,
+}
+
+/// Export a paged document in memory.
+fn export_paged(
+    document: &PagedDocument,
+    format: Format,
+    config: &ExportConfig,
+) -> SourceResult<Vec<Artifact>> {
+    if format == Format::Pdf {
+        let options = PdfOptions {
+            ident: Smart::Auto,
+            creator: Smart::Auto,
+            timestamp: pdf_timestamp(config.creation_timestamp),
+            page_ranges: config.pages.clone(),
+            standards: PdfStandards::default(),
+            tagged: config.pages.is_none(),
+            pretty: config.pretty,
+            encryption: None,
+            signature: None,
+            output_intent: None,
+        };
+        let data = typst_pdf::pdf(document, &options)?;
+        return Ok(vec![Artifact { page: None, data }]);
+    }
+
+    let pages = document.pages().iter().enumerate().filter(|(i, _)| {
+        config
+            .pages
+            .as_ref()
+            .is_none_or(|ranges| ranges.includes_page_index(*i))
+    });
+
+    let mut artifacts = vec![];
+    for (i, page) in pages {
+        let data = if format == Format::Png {
+            let options = RenderOptions {
+                pixel_per_pt: Scalar::new(config.ppi / 72.0),
+                render_bleed: false,
+            };
+            typst_render::render(page, &options)
+                .encode_png()
+                .map_err(|err| eco_format!("failed to encode PNG file ({err})"))
+                .at(Span::detached())?
+        } else {
+            let options = SvgOptions { render_bleed: false, pretty: config.pretty };
+            typst_svg::svg(page, &options).into_bytes()
+        };
+        artifacts.push(Artifact { page: Some(i + 1), data });
+    }
+
+    Ok(artifacts)
+}
+
+/// Write artifacts to disk and describe where they were written.
+///
+/// Multiple pages require a page number template in the output path, like
+/// for `typst compile`.
+fn write_artifacts(output: &Path, artifacts: &[Artifact]) -> StrResult<Vec<Value>> {
+    let template = output.to_str().unwrap_or_default();
+    let numbered = output_template::has_indexable_template(template);
+    if artifacts.len() > 1 && !numbered {
+        return Err("cannot export multiple images without a page number template \
+                    ({p}, {0p}) in the output path"
+            .into());
+    }
+
+    let total = artifacts.len();
+    artifacts
+        .iter()
+        .map(|artifact| {
+            let path = match artifact.page {
+                Some(page) if numbered => {
+                    PathBuf::from(output_template::format(template, page, total))
+                }
+                _ => output.to_path_buf(),
+            };
+            std::fs::write(&path, &artifact.data).map_err(|err| {
+                eco_format!("failed to write {} ({err})", path.display())
+            })?;
+            Ok(json!({ "page": artifact.page, "path": path }))
+        })
+        .collect()
+}
+
+/// Parse comma-separated page ranges like `1,3-5`.
+fn parse_pages(pages: &str) -> StrResult<PageRanges> {
+    pages
+        .split(',')
+        .map(|part| Pages::from_str(part.trim()).map(|pages| pages.0))
+        .collect::<Result<Vec<_>, _>>()
+        .map(PageRanges::new)
+        .map_err(Into::into)
+}
+
+/// Parameters of a `compile` request.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompileParams {
+    /// The main file, relative to the working directory.
+    input: PathBuf,
+    /// The string key-value pairs visible through `sys.inputs`, in addition
+    /// to those from the command line.
+    #[serde(default)]
+    inputs: BTreeMap<String, String>,
+    /// The format to export to.
+    #[serde(default)]
+    format: Format,
+    /// Which pages to export.
+    pages: Option<String>,
+    /// The PPI (pixels per inch) to use for PNG export.
+    #[serde(default = "default_ppi")]
+    ppi: f64,
+    /// Whether to make the output pretty.
+    #[serde(default)]
+    pretty: bool,
+    /// The document's creation date as a UNIX timestamp.
+    creation_timestamp: Option<i64>,
+    /// Where to write the artifacts instead of returning them.
+    output: Option<PathBuf>,
+}
+
+/// The default PPI for PNG export.
+fn default_ppi() -> f64 {
+    144.0
+}
+
+/// A format that the compile server can export to.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Format {
+    #[default]
+    Pdf,
+    Png,
+    Svg,
+    Html,
+}
@@ -0,0 +1,376 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use codespan_reporting::term;
+use ecow::{EcoString, eco_format};
+use tiny_skia as sk;
+use typst::diag::{HintedStrResult, StrResult, Warned, bail};
+use typst::layout::{Frame, FrameItem};
+use typst::syntax::{ast, parse};
+use typst_layout::PagedDocument;
+use typst_render::RenderOptions;
+use typst_utils::Scalar;
+
+use crate::args::TestCommand;
+use crate::compile::print_diagnostics;
+use crate::terminal;
+use crate::world::SystemWorld;
+
+/// Execute a test command.
+pub fn test(command: &'static TestCommand) -> HintedStrResult<()> {
+    let mut world = SystemWorld::new(None, &command.world, &command.process)
+        .map_err(|err| eco_format!("{err}"))?;
+
+    let dir = match &command.dir {
+        Some(dir) => dir.clone(),
+        None => world.root().join("tests"),
+    };
+    if !dir.is_dir() {
+        bail!(
+            "test directory {} does not exist", dir.display();
+            hint: "pass the directory containing the tests as an argument"
+        );
+    }
+    let dir = dir
+        .canonicalize()
+        .map_err(|err| eco_format!("failed to read {} ({err})", dir.display()))?;
+
+    let mut paths = vec![];
+    crate::fmt::collect(&dir, &mut paths)?;
+
+    // Files that other files import or include are helpers, not tests.
+    let mut helpers = HashSet::new();
+    for path in &paths {
+        imports(world.root(), path, &mut helpers);
+    }
+
+    let mut tests = vec![];
+    for path in paths {
+        if helpers.contains(&path) {
+            continue;
+        }
+
+        let Ok(name) = path.strip_prefix(&dir).map(|name| name.with_extension("")) else {
+            continue;
+        };
+        let display = name.display().to_string();
+        if command.filter.is_empty()
+            || command
+                .filter
+                .iter()
+                .any(|pattern| display.contains(pattern.as_str()))
+        {
+            tests.push(Test { path, name });
+        }
+    }
+
+    if tests.is_empty() {
+        bail!("found no tests in {}", dir.display());
+    }
+
+    // Without a filter, every test runs again, so the differences of tests
+    // that were renamed or deleted are stale, too.
+    if command.filter.is_empty() {
+        remove_dir(&dir.join("diff"))?;
+    }
+
+    let mut failed = 0;
+    let mut outdated = false;
+    for test in &tests {
+        let outcome = run(&mut world, command, &dir, test)?;
+        print_outcome(test, &outcome)
+            .map_err(|err| eco_format!("failed to print test result ({err})"))?;
+        match outcome {
+            Outcome::Passed | Outcome::Updated => {}
+            Outcome::Errored => failed += 1,
+            Outcome::Unreferenced | Outcome::Mismatched(_) => {
+                failed += 1;
+                outdated = true;
+            }
+        }
+    }
+
+    let total = tests.len();
+    if outdated {
+        bail!(
+            "{failed} of {total} tests failed";
+            hint: "to accept the current output as the reference, run `typst test --update`"
+        );
+    } else if failed > 0 {
+        bail!("{failed} of {total} tests failed");
+    }
+
+    Ok(())
+}
+
+/// A test file.
+struct Test {
+    /// The path of the file.
+    path: PathBuf,
+    /// The path relative to the test directory, without extension.
+    name: PathBuf,
+}
+
+/// The result of running a test.
+enum Outcome {
+    /// The output matched the references, or the test has no visible output
+    /// and no references.
+    Passed,
+    /// The references were created or replaced.
+    Updated,
+    /// Compilation failed, e.g. because of a failing assertion.
+    Errored,
+    /// The test has visible output, but no references.
+    Unreferenced,
+    /// The output differs from the references for the given reason.
+    Mismatched(EcoString),
+}
+
+/// Compile a test and compare its pages to the references.
+fn run(
+    world: &mut SystemWorld,
+    command: &TestCommand,
+    dir: &Path,
+    test: &Test,
+) -> HintedStrResult<Outcome> {
+    let Some(id) = world.id(&test.path) else {
+        bail!(
+            "test {} is outside of the project root", test.path.display();
+            hint: "set the project root with `--root`"
+        );
+    };
+
+    let refs = dir.join("ref").join(&test.name);
+    let diffs = dir.join("diff").join(&test.name);
+    remove_dir(&diffs)?;
+
+    world.set_main(id);
+    world.reset();
+
+    let Warned { output, warnings } = typst::compile::<PagedDocument>(&*world);
+    let diagnostic_format = command.process.diagnostic_format;
+    let document = match output {
+        Ok(document) => document,
+        Err(errors) => {
+            print_diagnostics(&*world, &errors, &warnings, diagnostic_format)
+                .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+            return Ok(Outcome::Errored);
+        }
+    };
+
+    if !warnings.is_empty() {
+        print_diagnostics(&*world, &[], &warnings, diagnostic_format)
+            .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+    }
+
+    let visible = document
+        .pages()
+        .iter()
+        .any(|page| !page.fill.is_auto() || !is_empty_frame(&page.frame));
+    if !visible && !refs.exists() {
+        return Ok(Outcome::Passed);
+    }
+
+    let options = RenderOptions {
+        pixel_per_pt: Scalar::new(command.ppi / 72.0),
+        render_bleed: false,
+    };
+    let pixmaps: Vec<sk::Pixmap> = document
+        .pages()
+        .iter()
+        .map(|page| typst_render::render(page, &options))
+        .collect();
+
+    let mut differing = vec![];
+    let mut references = vec![];
+    for (i, pixmap) in pixmaps.iter().enumerate() {
+        let reference = std::fs::read(page_path(&refs, i))
+            .ok()
+            .and_then(|data| sk::Pixmap::decode_png(&data).ok());
+        if !reference
+            .as_ref()
+            .is_some_and(|reference| approx_equal(reference, pixmap, command.tolerance))
+        {
+            differing.push(i);
+        }
+        references.push(reference);
+    }
+
+    let extra = page_path(&refs, pixmaps.len()).exists();
+    if differing.is_empty() && !extra {
+        return Ok(Outcome::Passed);
+    }
+
+    if command.update {
+        remove_dir(&refs)?;
+        if visible {
+            for (i, pixmap) in pixmaps.iter().enumerate() {
+                write(&page_path(&refs, i), &encode(pixmap)?)?;
+            }
+        }
+        return Ok(Outcome::Updated);
+    }
+
+    if !refs.exists() {
+        return Ok(Outcome::Unreferenced);
+    }
+
+    for &i in &differing {
+        let live = &pixmaps[i];
+        write(&page_path(&diffs, i), &encode(live)?)?;
+        if let Some(reference) = &references[i]
+            && let Some(diff) = diff_image(reference, live, command.tolerance)
+        {
+            write(&diffs.join(format!("{}.diff.png", i + 1)), &encode(&diff)?)?;
+        }
+    }
+
+    let reason = match differing.as_slice() {
+        [] => eco_format!("there are references for more than {} pages", pixmaps.len()),
+        [i] => eco_format!("page {} differs from its reference", i + 1),
+        _ => {
+            let pages: Vec<String> =
+                differing.iter().map(|i| (i + 1).to_string()).collect();
+            eco_format!("pages {} differ from their references", pages.join(", "))
+        }
+    };
+
+    Ok(Outcome::Mismatched(reason))
+}
+
+/// Collect the files that a Typst file imports or includes by a path.
+///
+/// Only string literals are considered. Files that don't exist are skipped.
+fn imports(root: &Path, path: &Path, found: &mut HashSet<PathBuf>) {
+    let Ok(text) = std::fs::read_to_string(path) else { return };
+    let mut queue = vec![parse(&text)];
+    while let Some(node) = queue.pop() {
+        let source = if let Some(import) = node.cast::<ast::ModuleImport>() {
+            import.source()
+        } else if let Some(include) = node.cast::<ast::ModuleInclude>() {
+            include.source()
+        } else {
+            queue.extend(node.children().cloned());
+            continue;
+        };
+
+        let ast::Expr::Str(string) = source else { continue };
+        let target = string.get();
+        let target = match target.strip_prefix('/') {
+            Some(rest) => root.join(rest),
+            None if target.starts_with('@') => continue,
+            None => path.parent().unwrap_or(root).join(target.as_str()),
+        };
+
+        if let Ok(target) = target.canonicalize() {
+            found.insert(target);
+        }
+    }
+}
+
+/// Print the outcome of a test.
+fn print_outcome(test: &Test, outcome: &Outcome) -> io::Result<()> {
+    let styles = term::Styles::default();
+    let (status, color, details) = match outcome {
+        Outcome::Passed => ("pass", &styles.header_note, None),
+        Outcome::Updated => ("updated", &styles.header_help, None),
+        Outcome::Errored => ("fail", &styles.header_error, Some("compilation failed")),
+        Outcome::Unreferenced => ("fail", &styles.header_error, Some("no references")),
+        Outcome::Mismatched(reason) => {
+            ("fail", &styles.header_error, Some(reason.as_str()))
+        }
+    };
+
+    let mut out = terminal::out();
+    out.set_color(color)?;
+    write!(out, "{status:>7}")?;
+    out.reset()?;
+    write!(out, " {}", test.name.display())?;
+    if let Some(details) = details {
+        write!(out, " ({details})")?;
+    }
+    writeln!(out)
+}
+
+/// Whether a frame contains nothing visible.
+fn is_empty_frame(frame: &Frame) -> bool {
+    frame.items().all(|(_, item)| match item {
+        FrameItem::Group(group) => is_empty_frame(&group.frame),
+        FrameItem::Tag(_) => true,
+        _ => false,
+    })
+}
+
+/// Whether two images have the same size and all their channels differ by at
+/// most the tolerance.
+fn approx_equal(a: &sk::Pixmap, b: &sk::Pixmap, tolerance: u8) -> bool {
+    a.width() == b.width()
+        && a.height() == b.height()
+        && a.data()
+            .iter()
+            .zip(b.data())
+            .all(|(&a, &b)| a.abs_diff(b) <= tolerance)
+}
+
+/// Highlight the pixels that differ between two images of the same size in
+/// red on top of a faded version of the reference.
+fn diff_image(
+    reference: &sk::Pixmap,
+    live: &sk::Pixmap,
+    tolerance: u8,
+) -> Option<sk::Pixmap> {
+    if reference.width() != live.width() || reference.height() != live.height() {
+        return None;
+    }
+
+    let mut diff = sk::Pixmap::new(live.width(), live.height())?;
+    for ((out, a), b) in diff
+        .data_mut()
+        .chunks_exact_mut(4)
+        .zip(reference.data().chunks_exact(4))
+        .zip(live.data().chunks_exact(4))
+    {
+        if a.iter().zip(b).all(|(&a, &b)| a.abs_diff(b) <= tolerance) {
+            let luma = (u16::from(a[0]) + u16::from(a[1]) + u16::from(a[2])) / 3;
+            let faded = (255 - (255 - luma) / 4) as u8;
+            out.copy_from_slice(&[faded, faded, faded, 255]);
+        } else {
+            out.copy_from_slice(&[255, 0, 0, 255]);
+        }
+    }
+
+    Some(diff)
+}
+
+/// The path of the image for the page with the given zero-based index.
+fn page_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{}.png", index + 1))
+}
+
+/// Encode an image as PNG.
+fn encode(pixmap: &sk::Pixmap) -> StrResult<Vec<u8>> {
+    pixmap
+        .encode_png()
+        .map_err(|err| eco_format!("failed to encode PNG file ({err})"))
+}
+
+/// Write a file, creating its parent directories if necessary.
+fn write(path: &Path, data: &[u8]) -> StrResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|err| {
+            eco_format!("failed to create directory {} ({err})", parent.display())
+        })?;
+    }
+    std::fs::write(path, data)
+        .map_err(|err| eco_format!("failed to write {} ({err})", path.display()))
+}
+
+/// Remove a directory and its contents if it exists.
+fn remove_dir(path: &Path) -> StrResult<()> {
+    match std::fs::remove_dir_all(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => bail!("failed to remove {} ({err})", path.display()),
+    }
+}
@@ -1,8 +1,10 @@
+use std::collections::HashMap;
 use std::error;
 use std::fmt;
 use std::io::{self, Read};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
-use std::sync::LazyLock;
+use std::sync::{LazyLock, OnceLock};
 
 use ecow::{EcoString, eco_format};
 use typst::diag::{FileError, FileResult};
@@ -12,7 +14,10 @@ use typst::syntax::{
 };
 use typst::text::{Font, FontBook};
 use typst::utils::LazyHash;
+use typst::visualize::ImageStore;
 use typst::{Library, LibraryExt, World};
+use typst_ide::IdeWorld;
+use typst_kit::cache::DiskCache;
 use typst_kit::datetime::Time;
 use typst_kit::diagnostics::DiagnosticWorld;
 use typst_kit::files::{FileLoader, FileStore, FsRoot};
@@ -31,6 +36,14 @@ pub struct SystemWorld {
     fonts: LazyLock<FontStore, Box<dyn Fn() -> FontStore + Send + Sync>>,
     /// Maps file ids to source files and buffers.
     files: FileStore<SystemFiles>,
+    /// Persists expensive intermediate results across runs, if enabled.
+    cache: Option<DiskCache>,
+    /// Sources that take precedence over the file system, e.g. unsaved buffers
+    /// of an editor connected through the language server.
+    shadows: HashMap<FileId, Source>,
+    /// The Typst files in the project root, discovered upon first use by the
+    /// IDE features and cleared by [`invalidate_files`](Self::invalidate_files).
+    project_sources: OnceLock<Vec<FileId>>,
     /// The current datetime if requested. This is stored here to ensure it is
     /// always the same within one compilation.
     /// Reset between compilations if not [`Time::Fixed`].
@@ -53,19 +66,13 @@ impl SystemWorld {
                 .ok();
         }
 
-        let library = {
-            // Convert the input pairs to a dictionary.
-            let inputs: Dict = world_args
-                .inputs
-                .iter()
-                .map(|(k, v)| (k.as_str().into(), v.as_str().into_value()))
-                .collect();
-
-            let features =
-                process_args.features.iter().copied().map(Into::into).collect();
-
-            Library::builder().with_inputs(inputs).with_features(features).build()
-        };
+        // Convert the input pairs to a dictionary.
+        let inputs: Dict = world_args
+            .inputs
+            .iter()
+            .map(|(k, v)| (k.as_str().into(), v.as_str().into_value()))
+            .collect();
+        let library = build_library(inputs, process_args);
 
         let now = match world_args.creation_timestamp {
             Some(time) => Time::fixed_timestamp(time)
@@ -73,13 +80,29 @@ impl SystemWorld {
             None => Time::system(),
         };
 
+        // Bound the cache's size once, before it is used.
+        let cache = world_args.cache_dir.as_deref().map(|dir| {
+            let cache = DiskCache::new(dir).with_limit(world_args.cache_limit << 20);
+            cache.prune().ok();
+            cache
+        });
+
+        let mut files = FileStore::new(SystemFiles::new(input, world_args)?);
+        if let Some(cache) = &cache {
+            files = files.with_cache(cache.clone());
+        }
+
+        let font_cache = cache.clone();
         Ok(Self {
             workdir: std::env::current_dir().ok(),
             library: LazyHash::new(library),
-            fonts: LazyLock::new(Box::new(|| {
-                crate::fonts::discover_fonts(&world_args.font)
+            fonts: LazyLock::new(Box::new(move || {
+                crate::fonts::discover_fonts(&world_args.font, font_cache.as_ref())
             })),
-            files: FileStore::new(SystemFiles::new(input, world_args)?),
+            files,
+            cache,
+            shadows: HashMap::new(),
+            project_sources: OnceLock::new(),
             now,
         })
     }
@@ -106,6 +129,78 @@ impl SystemWorld {
         self.now.reset();
     }
 
+    /// Resolves the file system path of the given file `id`.
+    pub fn path(&self, id: FileId) -> FileResult<PathBuf> {
+        self.files.loader().resolve(id)
+    }
+
+    /// Determines the file id for a file system path within the project root.
+    pub fn id(&self, path: &Path) -> Option<FileId> {
+        let vpath = VirtualPath::virtualize(self.root(), path).ok()?;
+        Some(RootedPath::new(VirtualRoot::Project, vpath).intern())
+    }
+
+    /// Replaces the standard library, e.g. to change `sys.inputs`.
+    pub fn set_library(&mut self, library: Library) {
+        self.library = LazyHash::new(library);
+    }
+
+    /// Replaces the source of the current date, e.g. to fix it for a
+    /// reproducible build.
+    pub fn set_time(&mut self, now: Time) {
+        self.now = now;
+    }
+
+    /// Changes the main file of the world.
+    pub fn set_main(&mut self, id: FileId) {
+        self.files.loader_mut().main = id;
+    }
+
+    /// Shadows the file with the given `id` with the given text, regardless
+    /// of its contents on disk.
+    pub fn shadow(&mut self, id: FileId, text: String) {
+        match self.shadows.get_mut(&id) {
+            Some(source) => {
+                source.replace(&text);
+            }
+            None => {
+                self.shadows.insert(id, Source::new(id, text));
+            }
+        }
+    }
+
+    /// Applies an edit to a shadowed file, reparsing incrementally.
+    ///
+    /// Returns the shadowed source after the edit or `None` if the file is not
+    /// shadowed.
+    pub fn edit_shadow(
+        &mut self,
+        id: FileId,
+        replace: Range<usize>,
+        with: &str,
+    ) -> Option<&Source> {
+        let source = self.shadows.get_mut(&id)?;
+        source.edit(replace, with);
+        Some(source)
+    }
+
+    /// Removes the shadow of a file, so that it is read from disk again.
+    pub fn unshadow(&mut self, id: FileId) {
+        self.shadows.remove(&id);
+    }
+
+    /// Forgets the discovered Typst files in the project root, so that they
+    /// are discovered again when they are needed next. Should be called when
+    /// files were created or deleted.
+    pub fn invalidate_files(&mut self) {
+        self.project_sources = OnceLock::new();
+    }
+
+    /// Returns the shadowed source for the given file `id`, if any.
+    pub fn shadowed(&self, id: FileId) -> Option<&Source> {
+        self.shadows.get(&id)
+    }
+
     /// Forcibly scan fonts instead of doing it lazily upon the first access.
     ///
     /// Does nothing if the fonts were already scanned.
@@ -128,11 +223,17 @@ impl World for SystemWorld {
     }
 
     fn source(&self, id: FileId) -> FileResult<Source> {
-        self.files.source(id)
+        match self.shadows.get(&id) {
+            Some(source) => Ok(source.clone()),
+            None => self.files.source(id),
+        }
     }
 
     fn file(&self, id: FileId) -> FileResult<Bytes> {
-        self.files.file(id)
+        match self.shadows.get(&id) {
+            Some(source) => Ok(Bytes::from_string(source.clone())),
+            None => self.files.file(id),
+        }
     }
 
     fn font(&self, index: usize) -> Option<Font> {
@@ -142,6 +243,10 @@ impl World for SystemWorld {
     fn today(&self, offset: Option<Duration>) -> Option<Datetime> {
         self.now.today(offset)
     }
+
+    fn image_store(&self) -> Option<&dyn ImageStore> {
+        self.cache.as_ref().map(|cache| cache as &dyn ImageStore)
+    }
 }
 
 impl DiagnosticWorld for SystemWorld {
@@ -164,6 +269,69 @@ impl DiagnosticWorld for SystemWorld {
     }
 }
 
+impl IdeWorld for SystemWorld {
+    fn upcast(&self) -> &dyn World {
+        self
+    }
+
+    fn files(&self) -> Vec<FileId> {
+        let mut files: Vec<FileId> = self.shadows.keys().copied().collect();
+        let sources = self.project_sources.get_or_init(|| discover_sources(self));
+        for &id in sources {
+            if !files.contains(&id) {
+                files.push(id);
+            }
+        }
+        files
+    }
+}
+
+/// Directories that typically hold build output or dependencies rather than
+/// project files.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target"];
+
+/// Walks the project root to find all Typst files in it. Hidden entries like
+/// `.git` and the [`SKIPPED_DIRS`] are not entered.
+fn discover_sources(world: &SystemWorld) -> Vec<FileId> {
+    let mut files = vec![];
+    let mut dirs = vec![world.root().to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') {
+                continue;
+            }
+
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(ty) if ty.is_dir() => {
+                    if !SKIPPED_DIRS.contains(&name.as_ref()) {
+                        dirs.push(path);
+                    }
+                }
+                Ok(ty) if ty.is_file() => {
+                    if path.extension().is_some_and(|ext| ext == "typ")
+                        && let Some(id) = world.id(&path)
+                    {
+                        files.push(id);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    files
+}
+
+/// Builds the standard library with the given `sys.inputs` and the features
+/// enabled on the command line.
+pub fn build_library(inputs: Dict, process_args: &ProcessArgs) -> Library {
+    let features = process_args.features.iter().copied().map(Into::into).collect();
+    Library::builder().with_inputs(inputs).with_features(features).build()
+}
+
 /// Static `FileId` allocated for stdin. This is to ensure that stdin can live
 /// in the project root without colliding with any real on-disk file.
 static STDIN_ID: LazyLock<FileId> = LazyLock::new(|| {
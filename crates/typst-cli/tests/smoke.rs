@@ -1,7 +1,8 @@
 use std::collections::HashSet;
 use std::fmt::{self, Debug, Display, Formatter};
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
 
 use tempfile::TempDir;
 use typst::foundations::Bytes;
@@ -41,6 +42,44 @@ fn test_compile_pdf_version() {
         .must_contain(format!("/Creator(Typst {version})").as_bytes());
 }
 
+#[test]
+fn test_compile_pdf_encrypted() {
+    let project = tempfs();
+    let hello = project.write("hello.typ", "Hi");
+    let compile = |output: &str| {
+        exec()
+            .arg("compile")
+            .arg("--creation-timestamp=0")
+            .arg("--pdf-user-password=secret")
+            .arg("--pdf-deny=copy")
+            .arg(&hello)
+            .arg(project.resolve(output))
+            .must_succeed();
+        let pdf = project.read(output);
+        pdf.must_contain("/Encrypt");
+        pdf.0
+    };
+
+    // The file key is random, so the output differs between runs.
+    assert_ne!(compile("a.pdf"), compile("b.pdf"));
+
+    exec()
+        .arg("compile")
+        .arg("--pdf-owner-password=")
+        .arg(&hello)
+        .must_fail()
+        .stderr
+        .must_contain("the owner password must not be empty");
+
+    exec()
+        .arg("compile")
+        .arg("--pdf-deny=copy")
+        .arg(&hello)
+        .must_fail()
+        .stderr
+        .must_contain("cannot deny permissions without encrypting the PDF");
+}
+
 #[test]
 fn test_eval() {
     let output = exec().arg("eval").arg("1+2").must_succeed();
@@ -101,6 +140,34 @@ fn test_fonts_path() {
     assert_eq!(found, expected);
 }
 
+#[test]
+fn test_cache_dir() {
+    let project = tempfs();
+    let cache = tempfs();
+    project.write("tiger.jpg", typst_dev_assets::get_by_name("tiger.jpg").unwrap());
+    project.write("monkey.svg", typst_dev_assets::get_by_name("monkey.svg").unwrap());
+    project.write("main.typ", "#image(\"tiger.jpg\")\n#image(\"monkey.svg\")");
+
+    let compile = || {
+        exec()
+            .arg("compile")
+            .arg("--ignore-system-fonts")
+            .arg("--creation-timestamp=0")
+            .arg("--cache-dir")
+            .arg(cache.path())
+            .arg(project.resolve("main.typ"))
+            .must_succeed();
+    };
+
+    compile();
+    let version = format!("v{}", env!("CARGO_PKG_VERSION"));
+    assert!(cache.resolve(&version).join("image").is_dir());
+    let first = project.read("main.pdf").0;
+
+    compile();
+    assert_eq!(project.read("main.pdf").0, first);
+}
+
 #[test]
 fn test_info() {
     let output = exec().arg("info").must_succeed();
@@ -138,6 +205,34 @@ fn test_path_unresolved() {
         .must_contain("#include \"other.typ\"");
 }
 
+#[test]
+fn test_diagnostic_format_json() {
+    let project = tempfs();
+    let main = project.write("main.typ", "#let f() = x\n#f()");
+    let output = exec()
+        .arg("compile")
+        .arg("--diagnostic-format=json")
+        .arg(&main)
+        .must_fail();
+    output
+        .stderr
+        .must_contain(r#""severity":"error","message":"unknown variable `x`""#)
+        .must_contain(r#""range":{"start":11,"end":12}"#)
+        .must_contain(r#""start":{"line":1,"column":12}"#)
+        .must_contain(r#""message":"while calling `f`""#);
+
+    let output = exec()
+        .arg("compile")
+        .arg("--diagnostic-format=sarif")
+        .arg(&main)
+        .must_fail();
+    output
+        .stderr
+        .must_contain(r#""version": "2.1.0""#)
+        .must_contain(r#""level": "error""#)
+        .must_contain(r#""startLine": 1"#);
+}
+
 #[test]
 fn test_path_project_root() {
     let project = tempfs();
@@ -274,6 +369,264 @@ fn test_target_available() {
     exec().arg("compile").arg(&main).must_succeed();
 }
 
+#[test]
+fn test_lsp() {
+    let project = tempfs();
+    let main = project.write("main.typ", "");
+    let uri = format!("file://{}", main.canonicalize().unwrap().display());
+    let messages = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#.to_string(),
+        format!(
+            r##"{{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{{"textDocument":{{"uri":"{uri}","text":"#let x = 1\n#y"}}}}}}"##
+        ),
+        format!(
+            r#"{{"jsonrpc":"2.0","id":2,"method":"textDocument/hover","params":{{"textDocument":{{"uri":"{uri}"}},"position":{{"line":0,"character":5}}}}}}"#
+        ),
+        r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#.to_string(),
+        r#"{"jsonrpc":"2.0","method":"exit"}"#.to_string(),
+    ];
+
+    let mut child = exec()
+        .arg("lsp")
+        .arg("--root")
+        .arg(project.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in &messages {
+        write!(stdin, "Content-Length: {}\r\n\r\n{message}", message.len()).unwrap();
+    }
+    drop(stdin);
+
+    let output = TestOutput::from(child.wait_with_output().unwrap());
+    output
+        .stdout
+        .must_contain("\"hoverProvider\":true")
+        .must_contain("textDocument/publishDiagnostics")
+        .must_contain("unknown variable `y`")
+        .must_contain("\"id\":3");
+}
+
+#[test]
+fn test_lsp_malformed() {
+    let project = tempfs();
+    let mut child = exec()
+        .arg("lsp")
+        .arg("--root")
+        .arg(project.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    write!(stdin, "Content-Type: text/plain\r\n\r\n").unwrap();
+    for message in [
+        r#"{"jsonrpc":"2.0","id":1,"#,
+        r#"{"jsonrpc":"2.0","id":2}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#,
+    ] {
+        write!(stdin, "Content-Length: {}\r\n\r\n{message}", message.len()).unwrap();
+    }
+    drop(stdin);
+
+    let output = TestOutput::from(child.wait_with_output().unwrap());
+    output
+        .stdout
+        .must_contain("\"code\":-32600")
+        .must_contain("\"code\":-32700")
+        .must_contain("\"id\":2")
+        .must_contain("\"id\":3");
+}
+
+#[test]
+fn test_serve_compile() {
+    let project = tempfs();
+    project.write("main.typ", "#set page(width: 50pt, height: 20pt)\n#sys.inputs.name");
+    project.write("broken.typ", "#unknown");
+    let messages = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"compile","params":{"input":"main.typ","inputs":{"name":"Ada"}}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"compile","params":{"input":"main.typ","format":"svg","inputs":{"name":"Bob"}}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"compile","params":{"input":"broken.typ"}}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"compile","params":{"input":"main.typ","format":"png","output":"out.png"}}"#,
+        r#"{"jsonrpc":"2.0","method":"exit"}"#,
+    ];
+
+    let mut child = exec()
+        .arg("serve-compile")
+        .arg("--root")
+        .arg(project.path())
+        .current_dir(project.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in &messages {
+        write!(stdin, "Content-Length: {}\r\n\r\n{message}", message.len()).unwrap();
+    }
+    drop(stdin);
+
+    let output = TestOutput::from(child.wait_with_output().unwrap());
+    output
+        .stdout
+        // Base64 for `%PDF`.
+        .must_contain("\"data\":\"JVBER")
+        .must_contain("\"page\":1")
+        .must_contain("\"success\":false")
+        .must_contain("unknown variable `unknown`")
+        .must_contain("\"id\":4");
+    assert!(project.path().join("out.png").exists());
+}
+
+#[test]
+fn test_serve_compile_defaults() {
+    let project = tempfs();
+    project.write(
+        "main.typ",
+        "#sys.inputs.greeting #sys.inputs.name #datetime.today().year()",
+    );
+    let message = r#"{"jsonrpc":"2.0","id":1,"method":"compile","params":{"input":"main.typ","format":"html","inputs":{"name":"Ada"},"creationTimestamp":0,"output":"out.html"}}"#;
+
+    let mut child = exec()
+        .arg("serve-compile")
+        .arg("--features=html")
+        .arg("--input=greeting=Hi")
+        .arg("--input=name=Bob")
+        .arg("--root")
+        .arg(project.path())
+        .current_dir(project.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    write!(stdin, "Content-Length: {}\r\n\r\n{message}", message.len()).unwrap();
+    drop(stdin);
+
+    let output = TestOutput::from(child.wait_with_output().unwrap());
+    output.stdout.must_contain("\"success\":true");
+    project.read("out.html").must_contain("Hi Ada 1970");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_serve_compile_socket() {
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::time::Duration;
+
+    let project = tempfs();
+    let socket = project.resolve("typst.sock");
+
+    // Leave a stale socket behind.
+    drop(UnixListener::bind(&socket).unwrap());
+
+    let child = exec()
+        .arg("serve-compile")
+        .arg("--socket")
+        .arg(&socket)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let connect = || {
+        for _ in 0..100 {
+            if let Ok(stream) = UnixStream::connect(&socket) {
+                return stream;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        panic!("failed to connect to server");
+    };
+
+    // A client that disconnects mid-message must not stop the server.
+    let mut stream = connect();
+    write!(stream, "Content-Length: 100\r\n\r\n{{").unwrap();
+    drop(stream);
+
+    let message = r#"{"jsonrpc":"2.0","method":"exit"}"#;
+    let mut stream = connect();
+    write!(stream, "Content-Length: {}\r\n\r\n{message}", message.len()).unwrap();
+
+    let output = TestOutput::from(child.wait_with_output().unwrap());
+    output.stderr.must_contain("dropped client");
+    assert!(!socket.exists());
+}
+
+#[test]
+fn test_fmt() {
+    let project = tempfs();
+    let main = project.write("main.typ", "#let x=(1,2)\n");
+    let output = exec().arg("fmt").arg("--check").arg(project.path()).must_fail();
+    output.stderr.must_contain("1 file is not formatted");
+
+    exec().arg("fmt").arg(&main).must_succeed();
+    project.read("main.typ").must_match_lines(["#let x = (1, 2)"]);
+    exec().arg("fmt").arg("--check").arg(&main).must_succeed();
+}
+
+#[test]
+fn test_fmt_erroneous() {
+    let project = tempfs();
+    project.write("a.typ", "#f(\n");
+    project.write("b.typ", "#let x=1\r\n");
+    let output = exec().arg("fmt").arg(project.path()).must_fail();
+    output.stderr.must_contain("a.typ (source contains syntax errors)");
+    output.stderr.must_contain("failed to format 1 file");
+    project.read("b.typ").must_contain("#let x = 1\r\n");
+}
+
+#[test]
+fn test_test() {
+    let project = tempfs();
+    project
+        .write("tests/assert.typ", "#import \"utils.typ\": two\n#assert.eq(1 + 1, two)");
+    project.write("tests/utils.typ", "#let two = 2\n#two");
+    project.write("tests/hello.typ", "#set page(width: 50pt, height: 20pt)\nHi");
+
+    let output = exec().arg("test").arg("--root").arg(project.path()).must_fail();
+    assert!(!output.stderr.contains("utils"));
+    output
+        .stderr
+        .must_contain("pass assert")
+        .must_contain("fail hello (no references)")
+        .must_contain("error: 1 of 2 tests failed");
+
+    exec()
+        .arg("test")
+        .arg("--update")
+        .arg("--root")
+        .arg(project.path())
+        .must_succeed()
+        .stderr
+        .must_contain("updated hello");
+    assert!(project.path().join("tests/ref/hello/1.png").exists());
+    assert!(!project.path().join("tests/ref/assert").exists());
+
+    project.write("tests/hello.typ", "#set page(width: 50pt, height: 20pt)\nHo");
+    exec().arg("test").arg("--root").arg(project.path()).must_fail();
+    assert!(project.path().join("tests/diff/hello/1.png").exists());
+    assert!(project.path().join("tests/diff/hello/1.diff.png").exists());
+
+    project.write("tests/hello.typ", "#set page(width: 50pt, height: 20pt)\n#ho");
+    exec().arg("test").arg("--root").arg(project.path()).must_fail();
+    assert!(!project.path().join("tests/diff/hello").exists());
+
+    project.write("tests/hello.typ", "#set page(width: 50pt, height: 20pt)\nHi");
+    exec().arg("test").arg("--root").arg(project.path()).must_succeed();
+    assert!(!project.path().join("tests/diff/hello").exists());
+}
+
 /// Executes a command with the Typst CLI.
 fn exec() -> Command {
     Command::new(env!("CARGO_BIN_EXE_typst"))
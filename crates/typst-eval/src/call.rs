@@ -737,7 +737,10 @@ pub fn eval_closure(
     }
 
     // Ensure all arguments have been used.
-    args.finish()?;
+    args.finish_with_params(params.children().filter_map(|param| match param {
+        ast::Param::Named(named) => Some(named.name().get().clone()),
+        _ => None,
+    }))?;
 
     // Handle control flow.
     let output = body.eval(&mut vm)?;
@@ -232,7 +232,10 @@ impl HtmlIntrospectorBuilder {
                         self.frame_link_targets.insert(*loc);
                     }
                 }
-                FrameItem::Text(..) | FrameItem::Shape(..) | FrameItem::Image(..) => {}
+                FrameItem::Text(..)
+                | FrameItem::Shape(..)
+                | FrameItem::Image(..)
+                | FrameItem::Annotation(..) => {}
             }
         }
     }
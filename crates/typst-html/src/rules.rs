@@ -21,13 +21,14 @@ use typst_library::model::{
     Destination, DirectLinkElem, DividerElem, EarlyLinkResolver, EmphElem, EnumElem,
     FigureCaption, FigureElem, FootnoteContainer, FootnoteElem, FootnoteEntry,
     FootnoteMarker, HeadingElem, LinkElem, LinkTarget, ListElem, OutlineElem,
-    OutlineEntry, OutlineNode, ParElem, ParbreakElem, QuoteElem, RefElem, StrongElem,
-    TableCell, TableElem, TermsElem, TitleElem, Works,
+    OutlineEntry, OutlineNode, ParElem, ParbreakElem, QuoteElem, RefElem, SidenoteElem,
+    SidenoteEntry, StrongElem, TableCell, TableElem, TermsElem, TitleElem, Works,
 };
 use typst_library::routines::Arenas;
 use typst_library::text::{
-    HighlightElem, LinebreakElem, OverlineElem, RawElem, RawLine, SmallcapsElem,
-    SpaceElem, StrikeElem, SubElem, SuperElem, UnderlineElem,
+    HighlightElem, LinebreakElem, OverlineElem, RawElem, RawLine, RubyElem,
+    RubyPlacement, SmallcapsElem, SpaceElem, StrikeElem, SubElem, SuperElem,
+    UnderlineElem,
 };
 use typst_library::visualize::{Color, ImageElem};
 use typst_syntax::Span;
@@ -58,6 +59,8 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Html, FOOTNOTE_MARKER_RULE);
     rules.register(Html, FOOTNOTE_CONTAINER_RULE);
     rules.register(Html, FOOTNOTE_ENTRY_RULE);
+    rules.register(Html, SIDENOTE_RULE);
+    rules.register(Html, SIDENOTE_ENTRY_RULE);
     rules.register(Html, OUTLINE_RULE);
     rules.register(Html, OUTLINE_ENTRY_RULE);
     rules.register(Html, REF_RULE);
@@ -78,6 +81,7 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Html, SMALLCAPS_RULE);
     rules.register(Html, RAW_RULE);
     rules.register(Html, RAW_LINE_RULE);
+    rules.register(Html, RUBY_RULE);
 
     // Visualize.
     rules.register(Html, IMAGE_RULE);
@@ -422,6 +426,41 @@ const FOOTNOTE_ENTRY_RULE: ShowFn<FootnoteEntry> = |elem, engine, styles| {
     Ok(prefix + body)
 };
 
+const SIDENOTE_RULE: ShowFn<SidenoteElem> = |elem, engine, styles| {
+    let span = elem.span();
+    let loc = elem.location().unwrap();
+
+    // The sidenote number that links to the sidenote entry.
+    let link = elem.realize(engine, styles)?;
+    let sup = SuperElem::new(link)
+        .pack()
+        .styled(HtmlElem::role.set(Some("doc-noteref".into())))
+        .spanned(span);
+
+    // Unlike footnotes, sidenotes stay next to the text they refer to, so
+    // `doc-footnote` is the appropriate ARIA role here. As the note is part of
+    // a paragraph, it is a `span`, which can be moved into the margin with CSS.
+    let entry = HtmlElem::new(tag::span)
+        .with_attr(attr::role, "doc-footnote")
+        .with_body(Some(SidenoteEntry::new(elem.clone()).pack().spanned(span)))
+        .pack()
+        .located(loc.variant(1))
+        .spanned(span);
+
+    Ok(HElem::hole().clone() + sup + entry)
+};
+
+const SIDENOTE_ENTRY_RULE: ShowFn<SidenoteEntry> = |elem, engine, styles| {
+    let (sup, body) = elem.realize(engine, styles)?;
+
+    // The prefix is a link back to the sidenote number.
+    let prefix = sup
+        .styled(HtmlElem::role.set(Some("doc-backlink".into())))
+        .spanned(elem.span());
+
+    Ok(prefix + body)
+};
+
 const OUTLINE_RULE: ShowFn<OutlineElem> = |elem, engine, styles| {
     fn convert_list(list: Vec<OutlineNode>) -> Content {
         // The Digital Publishing ARIA spec also proposed to add
@@ -770,6 +809,25 @@ pub fn html_span_filled(content: Content, color: Color) -> Content {
 
 const RAW_LINE_RULE: ShowFn<RawLine> = |elem, _, _| Ok(elem.body.clone());
 
+const RUBY_RULE: ShowFn<RubyElem> = |elem, _, styles| {
+    let rt = |body| HtmlElem::new(tag::rt).with_body(Some(body)).pack();
+
+    // Grouped annotations span the whole base text. Otherwise, each piece of
+    // the base text is directly followed by its own annotation.
+    let body = if elem.placement.get(styles) == Smart::Custom(RubyPlacement::Group) {
+        let annotation = Content::sequence(elem.annotation.0.iter().cloned());
+        elem.base.clone() + rt(annotation)
+    } else {
+        Content::sequence(
+            elem.pairs()?
+                .into_iter()
+                .flat_map(|(base, annotation)| [base, rt(annotation)]),
+        )
+    };
+
+    Ok(HtmlElem::new(tag::ruby).with_body(Some(body)).pack())
+};
+
 // Also check `PATCHED_IMAGE_RULE` in `docs/src/main.rs` when editing this.
 const IMAGE_RULE: ShowFn<ImageElem> = |elem, engine, styles| {
     let image = elem.decode(engine, styles)?;
@@ -0,0 +1,231 @@
+use std::ops::Range;
+
+use ecow::{EcoString, eco_format};
+use typst::WorldExt;
+use typst::diag::{Fix, FixKind, SourceDiagnostic};
+use typst::syntax::ast::{self, AstNode};
+use typst::syntax::{DiagSpan, LinkedNode, Source, SyntaxKind};
+
+use crate::utils::visit;
+use crate::{IdeWorld, TextEdit, analyze_import};
+
+/// A change to the code that resolves a diagnostic, which can be offered as a
+/// quick fix.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CodeAction {
+    /// A short description of the action, e.g. ``replace with `fill` ``.
+    pub title: EcoString,
+    /// The edits to apply. They may span multiple files.
+    pub edits: Vec<TextEdit>,
+}
+
+/// Collect quick fixes for the diagnostics that overlap with the given byte
+/// range in the source.
+///
+/// This includes the [fixes](SourceDiagnostic::fixes) that the diagnostics
+/// carry, as well as fixes that need further analysis, like importing an
+/// unknown variable from a module that is already imported with a list of
+/// items.
+pub fn code_actions(
+    world: &dyn IdeWorld,
+    source: &Source,
+    range: Range<usize>,
+    diagnostics: &[SourceDiagnostic],
+) -> Vec<CodeAction> {
+    let mut actions = vec![];
+    for diag in diagnostics {
+        if diag.span.id() != Some(source.id()) {
+            continue;
+        }
+
+        let Some(diag_range) = world.range(diag.span) else { continue };
+        if diag_range.end < range.start || range.end < diag_range.start {
+            continue;
+        }
+
+        for fix in &diag.fixes {
+            match fix.edits.as_slice() {
+                [edit] if edit.kind == FixKind::Import => {
+                    import_actions(world, source, &edit.text, edit.span, &mut actions)
+                }
+                _ => actions.extend(convert(world, fix)),
+            }
+        }
+    }
+    actions
+}
+
+/// Turn a fix into a code action.
+///
+/// Returns `None` if any of the fix's spans doesn't point into a file or if
+/// the fix needs further analysis.
+fn convert(world: &dyn IdeWorld, fix: &Fix) -> Option<CodeAction> {
+    let edits = fix
+        .edits
+        .iter()
+        .map(|edit| {
+            let range = world.range(edit.span)?;
+            let range = match edit.kind {
+                FixKind::Replace => range,
+                FixKind::InsertBefore => range.start..range.start,
+                FixKind::InsertAfter => range.end..range.end,
+                FixKind::Import => return None,
+            };
+            Some(TextEdit {
+                id: edit.span.id()?,
+                range,
+                text: edit.text.clone(),
+            })
+        })
+        .collect::<Option<_>>()?;
+    Some(CodeAction { title: fix.title.clone(), edits })
+}
+
+/// Resolve an [import fix](FixKind::Import) by offering to import the name
+/// from the modules that are imported with a list of items before the span.
+fn import_actions(
+    world: &dyn IdeWorld,
+    source: &Source,
+    name: &str,
+    span: DiagSpan,
+    actions: &mut Vec<CodeAction>,
+) {
+    if span.id() != Some(source.id()) {
+        return;
+    }
+
+    let Some(range) = world.range(span) else { return };
+    let root = LinkedNode::new(source.root());
+    visit(&root, &mut |node| {
+        if node.range().end > range.start {
+            return;
+        }
+
+        let Some(import) = node.cast::<ast::ModuleImport>() else { return };
+        let Some(ast::Imports::Items(_)) = import.imports() else { return };
+        let Some(module) = node.find(import.source().span()) else { return };
+        let Some(value) = analyze_import(world, &module) else { return };
+        if value.scope().and_then(|scope| scope.get(name)).is_none() {
+            return;
+        }
+
+        let Some(last) = node
+            .children()
+            .find(|child| child.kind() == SyntaxKind::ImportItems)
+            .and_then(|items| {
+                items.children().rfind(|child| {
+                    matches!(
+                        child.kind(),
+                        SyntaxKind::ImportItemPath | SyntaxKind::RenamedImportItem
+                    )
+                })
+            })
+        else {
+            return;
+        };
+
+        let end = last.range().end;
+        actions.push(CodeAction {
+            title: eco_format!("import `{name}` from {}", module.get().full_text()),
+            edits: vec![TextEdit {
+                id: source.id(),
+                range: end..end,
+                text: eco_format!(", {name}"),
+            }],
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Borrow;
+    use std::ops::Range;
+
+    use typst::diag::Warned;
+    use typst_layout::PagedDocument;
+
+    use super::{CodeAction, code_actions};
+    use crate::tests::{FilePos, TestWorld, WorldLike};
+
+    type Response = Vec<CodeAction>;
+
+    trait ResponseExt {
+        /// Assert that there are no actions.
+        fn must_be_empty(&self) -> &Self;
+        /// Assert that there is an action with the given title and that it
+        /// makes a single edit at the given range with the given text.
+        fn must_include(&self, title: &str, range: Range<usize>, text: &str) -> &Self;
+    }
+
+    impl ResponseExt for Response {
+        #[track_caller]
+        fn must_be_empty(&self) -> &Self {
+            assert_eq!(*self, vec![]);
+            self
+        }
+
+        #[track_caller]
+        fn must_include(&self, title: &str, range: Range<usize>, text: &str) -> &Self {
+            let action = self
+                .iter()
+                .find(|action| action.title == title)
+                .unwrap_or_else(|| panic!("{title:?} not in {self:?}"));
+            assert_eq!(action.edits.len(), 1);
+            assert_eq!(action.edits[0].range, range);
+            assert_eq!(action.edits[0].text, text);
+            self
+        }
+    }
+
+    #[track_caller]
+    fn test(world: impl WorldLike, pos: impl FilePos) -> Response {
+        let world = world.acquire();
+        let world = world.borrow();
+        let (source, cursor) = pos.resolve(world);
+        let Warned { output, mut warnings } = typst::compile::<PagedDocument>(world);
+        warnings.extend(output.err().into_iter().flatten());
+        code_actions(world, &source, cursor..cursor, &warnings)
+    }
+
+    #[test]
+    fn test_code_actions_misspelled_param() {
+        test("#rect(fil: red)", 7).must_include("replace with `fill`", 6..9, "fill");
+        test("#let f(width: 1) = width\n#f(widt: 2)", -5).must_include(
+            "replace with `width`",
+            28..32,
+            "width",
+        );
+        test("#rect(foo: red)", 7).must_be_empty();
+    }
+
+    #[test]
+    fn test_code_actions_context() {
+        test("#counter(page).get()", 5).must_include(
+            "wrap in `context`",
+            1..1,
+            "context ",
+        );
+        test("#counter(page).get()\n\nText", -1).must_be_empty();
+    }
+
+    #[test]
+    fn test_code_actions_unknown_variable() {
+        test("#let a = 1\n#(a-b)", -3).must_include(
+            "add spaces around the minus sign",
+            13..16,
+            "a - b",
+        );
+        test("$true$", 2).must_include("add a hash", 1..1, "#");
+    }
+
+    #[test]
+    fn test_code_actions_import() {
+        let world = TestWorld::new("#import \"other.typ\": this\n#that")
+            .with_source("other.typ", "#let this = 1; #let that = 2");
+        test(&world, -2).must_include(
+            "import `that` from \"other.typ\"",
+            25..25,
+            ", that",
+        );
+    }
+}
@@ -1,19 +1,29 @@
 //! Capabilities for Typst IDE support.
 
+mod actions;
 mod analyze;
 mod complete;
 mod definition;
 mod docs;
 mod jump;
 mod matchers;
+mod references;
+mod signature;
+mod symbols;
+mod tokens;
 mod tooltip;
 mod utils;
 
+pub use self::actions::{CodeAction, code_actions};
 pub use self::analyze::{analyze_expr, analyze_import, analyze_labels};
 pub use self::complete::{Completion, CompletionKind, autocomplete};
 pub use self::definition::{Definition, definition};
 pub use self::jump::{Jump, jump_from_click, jump_from_click_in_frame, jump_from_cursor};
 pub use self::matchers::{DerefTarget, NamedItem, deref_target, named_items};
+pub use self::references::{Reference, TextEdit, references, rename};
+pub use self::signature::{SignatureHelp, SignatureParam, signature_help};
+pub use self::symbols::{DocumentSymbol, SymbolKind, document_symbols};
+pub use self::tokens::{SemanticToken, TokenModifiers, TokenType, semantic_tokens};
 pub use self::tooltip::{Tooltip, tooltip};
 
 use ecow::EcoString;
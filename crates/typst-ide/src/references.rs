@@ -0,0 +1,345 @@
+use std::ops::Range;
+
+use ecow::EcoString;
+use rustc_hash::FxHashSet;
+use typst::diag::{StrResult, bail};
+use typst::syntax::{
+    FileId, LinkedNode, Side, Source, Span, SyntaxKind, ast, is_ident,
+    is_valid_label_literal_id,
+};
+
+use crate::utils::{declarations, visit};
+use crate::{IdeWorld, NamedItem, named_items};
+
+/// A place in a source file that refers to an item.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Reference {
+    /// The file the reference is in.
+    pub id: FileId,
+    /// The byte range of the item's name in the file.
+    ///
+    /// For labels and references, this excludes the angle brackets and the
+    /// `@` marker.
+    pub range: Range<usize>,
+    /// Whether this is where the item is declared (its binding or label)
+    /// rather than a use of it.
+    pub declaration: bool,
+}
+
+/// A single replacement in a source file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TextEdit {
+    /// The file to edit.
+    pub id: FileId,
+    /// The byte range to replace.
+    pub range: Range<usize>,
+    /// The replacement text.
+    pub text: EcoString,
+}
+
+/// Find all references to the item under the cursor, including its
+/// declaration.
+///
+/// Supports `let` bindings, function parameters, imported names, and labels
+/// along with references to them. All files in [`IdeWorld::files`] are
+/// searched in addition to the given source.
+pub fn references(
+    world: &dyn IdeWorld,
+    source: &Source,
+    cursor: usize,
+) -> Vec<Reference> {
+    let Some(target) = Target::at(world, source, cursor) else { return vec![] };
+
+    let mut ids = vec![source.id()];
+    for id in world.files() {
+        if id.vpath().extension() == Some("typ") && !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    let mut refs = vec![];
+    for id in ids {
+        let Ok(source) = world.source(id) else { continue };
+        target.collect(world, &source, &mut refs);
+    }
+
+    refs
+}
+
+/// Rename the item under the cursor and all references to it.
+///
+/// Returns the edits to apply to the project's files.
+pub fn rename(
+    world: &dyn IdeWorld,
+    source: &Source,
+    cursor: usize,
+    new_name: &str,
+) -> StrResult<Vec<TextEdit>> {
+    let Some(target) = Target::at(world, source, cursor) else {
+        bail!("there is nothing to rename at the cursor");
+    };
+
+    match target {
+        Target::Binding { .. } if !is_binding_name(new_name) => {
+            bail!("`{new_name}` is not a valid identifier");
+        }
+        Target::Label(_) if !is_valid_label_literal_id(new_name) => {
+            bail!("`{new_name}` is not a valid label");
+        }
+        _ => {}
+    }
+
+    Ok(references(world, source, cursor)
+        .into_iter()
+        .map(|reference| TextEdit {
+            id: reference.id,
+            range: reference.range,
+            text: new_name.into(),
+        })
+        .collect())
+}
+
+/// The item whose references we are looking for.
+enum Target {
+    /// A binding, identified by the span of its declaration.
+    Binding { name: EcoString, declaration: Span },
+    /// A label with the given name.
+    Label(EcoString),
+}
+
+impl Target {
+    /// Determine the item under the cursor.
+    fn at(world: &dyn IdeWorld, source: &Source, cursor: usize) -> Option<Self> {
+        let root = LinkedNode::new(source.root());
+        let leaf = [Side::After, Side::Before]
+            .into_iter()
+            .filter_map(|side| root.leaf_at(cursor, side))
+            .find(|leaf| {
+                matches!(
+                    leaf.kind(),
+                    SyntaxKind::Ident
+                        | SyntaxKind::MathIdent
+                        | SyntaxKind::Label
+                        | SyntaxKind::RefMarker
+                )
+            })?;
+
+        match leaf.kind() {
+            SyntaxKind::Label => {
+                Some(Self::Label(leaf.cast::<ast::Label>()?.get().into()))
+            }
+            SyntaxKind::RefMarker => {
+                Some(Self::Label(leaf.parent()?.cast::<ast::Ref>()?.target().into()))
+            }
+            _ => {
+                let declarations = declarations(source.root());
+                let declaration = resolve(world, &leaf, &declarations)?;
+                Some(Self::Binding { name: leaf.leaf_text().clone(), declaration })
+            }
+        }
+    }
+
+    /// Collect all references to this item in the given source.
+    fn collect(&self, world: &dyn IdeWorld, source: &Source, refs: &mut Vec<Reference>) {
+        let id = source.id();
+        let declarations = match self {
+            Self::Binding { .. } => declarations(source.root()),
+            Self::Label(_) => FxHashSet::default(),
+        };
+
+        visit(&LinkedNode::new(source.root()), &mut |node| match self {
+            Self::Binding { name, declaration } => {
+                if matches!(node.kind(), SyntaxKind::Ident | SyntaxKind::MathIdent)
+                    && node.leaf_text() == name
+                    && resolve(world, node, &declarations) == Some(*declaration)
+                {
+                    refs.push(Reference {
+                        id,
+                        range: node.range(),
+                        declaration: node.span() == *declaration,
+                    });
+                }
+            }
+            Self::Label(name) => {
+                if let Some(label) = node.cast::<ast::Label>()
+                    && label.get() == name
+                {
+                    let range = node.range();
+                    refs.push(Reference {
+                        id,
+                        range: range.start + 1..range.end - 1,
+                        declaration: true,
+                    });
+                } else if node.kind() == SyntaxKind::RefMarker
+                    && node.leaf_text().trim_start_matches('@') == name
+                {
+                    let range = node.range();
+                    refs.push(Reference {
+                        id,
+                        range: range.start + 1..range.end,
+                        declaration: false,
+                    });
+                }
+            }
+        });
+    }
+}
+
+/// Determine the span of the declaration an identifier refers to.
+///
+/// Returns `None` for identifiers that don't refer to a binding, like field
+/// names and argument names.
+fn resolve(
+    world: &dyn IdeWorld,
+    node: &LinkedNode,
+    declarations: &FxHashSet<Span>,
+) -> Option<Span> {
+    if declarations.contains(&node.span()) {
+        return Some(node.span());
+    }
+
+    let parent = node.parent()?;
+    match parent.kind() {
+        // The field in `a.b`.
+        SyntaxKind::FieldAccess | SyntaxKind::MathFieldAccess if node.index() > 0 => {
+            return None;
+        }
+        // The name in `f(a: b)` or `(a: b)`.
+        SyntaxKind::Named if node.index() == 0 => return None,
+        _ => {}
+    }
+
+    let name = node.leaf_text();
+    named_items(world, node.clone(), |item: NamedItem| {
+        (item.name() == name).then(|| item.span())
+    })
+    .filter(|span| !span.is_detached())
+}
+
+/// Whether the string is usable as the name of a binding.
+fn is_binding_name(name: &str) -> bool {
+    // Keywords are valid identifiers, but can't be used as names.
+    is_ident(name)
+        && typst::syntax::parse_code(name)
+            .children()
+            .next()
+            .is_some_and(|node| node.kind() == SyntaxKind::Ident)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Borrow;
+    use std::ops::Range;
+
+    use super::{Reference, references, rename};
+    use crate::tests::{FilePos, TestWorld, WorldLike};
+
+    type Response = Vec<Reference>;
+
+    trait ResponseExt {
+        fn must_be(&self, expected: &[(&str, Range<usize>)]) -> &Self;
+        fn must_declare(&self, path: &str, range: Range<usize>) -> &Self;
+    }
+
+    impl ResponseExt for Response {
+        #[track_caller]
+        fn must_be(&self, expected: &[(&str, Range<usize>)]) -> &Self {
+            let mut found = self
+                .iter()
+                .map(|r| (r.id.vpath().get_without_slash(), r.range.clone()))
+                .collect::<Vec<_>>();
+            found.sort_by_key(|(path, range)| (*path, range.start));
+            assert_eq!(found, expected);
+            self
+        }
+
+        #[track_caller]
+        fn must_declare(&self, path: &str, range: Range<usize>) -> &Self {
+            assert!(
+                self.iter().any(|r| r.declaration
+                    && r.id.vpath().get_without_slash() == path
+                    && r.range == range),
+                "{path}:{range:?} is not a declaration in {self:?}",
+            );
+            self
+        }
+    }
+
+    #[track_caller]
+    fn test(world: impl WorldLike, pos: impl FilePos) -> Response {
+        let world = world.acquire();
+        let world = world.borrow();
+        let (source, cursor) = pos.resolve(world);
+        references(world, &source, cursor)
+    }
+
+    #[test]
+    fn test_references_let() {
+        test("#let x = 1; #x #(x + 1)", 6)
+            .must_be(&[("main.typ", 5..6), ("main.typ", 13..14), ("main.typ", 17..18)])
+            .must_declare("main.typ", 5..6);
+        test("#let x = 1; #x", -1).must_be(&[("main.typ", 5..6), ("main.typ", 13..14)]);
+    }
+
+    #[test]
+    fn test_references_shadowing() {
+        let text = "#let f(a) = a; #let a = 2; #a";
+        test(text, 8).must_be(&[("main.typ", 7..8), ("main.typ", 12..13)]);
+        test(text, -1).must_be(&[("main.typ", 20..21), ("main.typ", 28..29)]);
+    }
+
+    #[test]
+    fn test_references_excludes_fields_and_args() {
+        test("#let a = (a: 1); #a.a #f(a: a)", 6).must_be(&[
+            ("main.typ", 5..6),
+            ("main.typ", 18..19),
+            ("main.typ", 28..29),
+        ]);
+    }
+
+    #[test]
+    fn test_references_cross_file() {
+        let world = TestWorld::new("#import \"other.typ\": x; #x")
+            .with_source("other.typ", "#let x = 1; #x");
+        test(&world, -1)
+            .must_be(&[
+                ("main.typ", 21..22),
+                ("main.typ", 25..26),
+                ("other.typ", 5..6),
+                ("other.typ", 13..14),
+            ])
+            .must_declare("other.typ", 5..6);
+    }
+
+    #[test]
+    fn test_references_label() {
+        let world = TestWorld::new("= Intro <intro>\nSee @intro.\n#include \"ch.typ\"")
+            .with_source("ch.typ", "As in @intro[the intro].");
+        test(&world, 10)
+            .must_be(&[("ch.typ", 7..12), ("main.typ", 9..14), ("main.typ", 21..26)])
+            .must_declare("main.typ", 9..14);
+        test(&world, ("ch.typ", 8)).must_be(&[
+            ("ch.typ", 7..12),
+            ("main.typ", 9..14),
+            ("main.typ", 21..26),
+        ]);
+    }
+
+    #[test]
+    fn test_rename() {
+        let world = TestWorld::new("#let x = 1; #x");
+        let source = world.main.clone();
+        let edits = rename(&world, &source, 13, "y").unwrap();
+        assert_eq!(edits.len(), 2);
+        assert!(edits.iter().all(|edit| edit.text == "y"));
+        assert_eq!(
+            rename(&world, &source, 13, "let").unwrap_err(),
+            "`let` is not a valid identifier"
+        );
+        assert_eq!(
+            rename(&world, &source, 13, "1x").unwrap_err(),
+            "`1x` is not a valid identifier"
+        );
+        assert!(rename(&world, &source, 0, "y").is_err());
+    }
+}
@@ -0,0 +1,347 @@
+use std::fmt::Write;
+use std::ops::Range;
+
+use ecow::EcoString;
+use typst::foundations::{AsOutput, CastInfo, ParamInfo, Repr, Value};
+use typst::syntax::ast::{self, AstNode};
+use typst::syntax::{LinkedNode, Side, Source, SyntaxKind};
+
+use crate::IdeWorld;
+use crate::analyze::analyze_expr_with_fallback;
+use crate::docs::{find_param_docs, find_value_docs};
+
+/// The signature of a function that is being called, with the parameter the
+/// cursor is at.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SignatureHelp {
+    /// The rendered signature, e.g. `repeat(gap: length = 0pt, body: content)`.
+    pub label: EcoString,
+    /// A short summary of the function's documentation.
+    pub docs: Option<EcoString>,
+    /// The parameters of the function.
+    pub params: Vec<SignatureParam>,
+    /// The index of the parameter the cursor is at, if any.
+    pub active: Option<usize>,
+}
+
+/// A parameter in a [`SignatureHelp`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SignatureParam {
+    /// The byte range of the parameter in the signature's label.
+    pub range: Range<usize>,
+    /// A short summary of the parameter's documentation.
+    pub docs: Option<EcoString>,
+}
+
+/// The maximum length of a default value to include in a signature.
+const MAX_DEFAULT_LEN: usize = 40;
+
+/// Describe the signature of the function whose arguments the cursor is in.
+///
+/// Works for calls and set rules of native functions, closures, element
+/// functions, and plugin functions. Passing a `document` (from a previous
+/// compilation) is optional and currently doesn't affect the result, but
+/// keeps the signature in line with the other IDE functions.
+pub fn signature_help(
+    world: &dyn IdeWorld,
+    _document: Option<impl AsOutput>,
+    source: &Source,
+    cursor: usize,
+) -> Option<SignatureHelp> {
+    let root = LinkedNode::new(source.root());
+    let leaf = root
+        .leaf_at(cursor, Side::Before)
+        .or_else(|| root.leaf_at(cursor, Side::After))?;
+
+    // Find the innermost argument list the cursor is in. We don't show the
+    // signature inside of nested blocks.
+    let mut args = leaf;
+    while !matches!(args.kind(), SyntaxKind::Args | SyntaxKind::MathArgs) {
+        if matches!(
+            args.kind(),
+            SyntaxKind::ContentBlock | SyntaxKind::CodeBlock | SyntaxKind::Closure
+        ) {
+            return None;
+        }
+        args = args.parent()?.clone();
+    }
+
+    if !in_parens(&args, cursor) {
+        return None;
+    }
+
+    let call = args.parent()?;
+    let (callee, set) = match call.cast::<ast::Expr>()? {
+        ast::Expr::FuncCall(call) => (call.callee(), false),
+        ast::Expr::MathCall(call) => (call.callee(), false),
+        ast::Expr::SetRule(set) => (set.target(), true),
+        _ => return None,
+    };
+    let callee = call.find(callee.span())?;
+    let value = analyze_expr_with_fallback(world, &callee)?;
+    let Value::Func(func) = &value else { return None };
+
+    let method = is_method_call(world, &callee);
+    let params: Vec<ParamInfo> = func
+        .params()
+        .filter(|param| !set || param.settable())
+        .filter(|param| !method || param.name() != Some("self"))
+        .collect();
+
+    let active = active_param(&args, cursor, &params);
+    let name = func.name().map_or_else(|| callee.get().full_text(), Into::into);
+
+    let mut label = EcoString::new();
+    let mut infos = vec![];
+    label.push_str(&name);
+    label.push('(');
+    for (i, param) in params.iter().enumerate() {
+        if i > 0 {
+            label.push_str(", ");
+        }
+        let start = label.len();
+        write_param(&mut label, param);
+        infos.push(SignatureParam {
+            range: start..label.len(),
+            docs: find_param_docs(world, param).map(|docs| docs.summary()),
+        });
+    }
+    label.push(')');
+
+    if let Some(returns) = func.returns() {
+        write!(label, " -> {}", describe(returns)).unwrap();
+    }
+
+    Some(SignatureHelp {
+        label,
+        docs: find_value_docs(world, &value).map(|docs| docs.summary()),
+        params: infos,
+        active,
+    })
+}
+
+/// Whether the callee is a method called on a value, in which case the
+/// value is passed as the `self` parameter.
+fn is_method_call(world: &dyn IdeWorld, callee: &LinkedNode) -> bool {
+    let Some(access) = callee.cast::<ast::FieldAccess>() else { return false };
+    let Some(target) = callee.find(access.target().span()) else { return false };
+    !matches!(
+        analyze_expr_with_fallback(world, &target),
+        Some(Value::Type(_) | Value::Module(_) | Value::Func(_)) | None
+    )
+}
+
+/// Whether the cursor is between the parentheses of an argument list.
+fn in_parens(args: &LinkedNode, cursor: usize) -> bool {
+    let mut children = args.children();
+    let Some(open) = children.find(|child| child.kind() == SyntaxKind::LeftParen) else {
+        return false;
+    };
+    open.range().end <= cursor
+        && children
+            .find(|child| child.kind() == SyntaxKind::RightParen)
+            .is_none_or(|close| cursor <= close.offset())
+}
+
+/// Determine which parameter the argument at the cursor belongs to.
+fn active_param(args: &LinkedNode, cursor: usize, params: &[ParamInfo]) -> Option<usize> {
+    // Find the argument the cursor is at and count the positional arguments
+    // before it.
+    let mut positional = 0;
+    let mut current = None;
+    for child in args.children() {
+        match child.kind() {
+            SyntaxKind::RightParen => break,
+            SyntaxKind::Comma if child.range().end <= cursor => {
+                if let Some(ast::Arg::Pos(_)) = current.take() {
+                    positional += 1;
+                }
+            }
+            _ if child.offset() > cursor => break,
+            _ => {
+                if let Some(arg) = child.cast::<ast::Arg>() {
+                    current = Some(arg);
+                }
+            }
+        }
+    }
+
+    match current {
+        Some(ast::Arg::Named(named)) => params
+            .iter()
+            .position(|param| param.name() == Some(named.name().as_str())),
+        Some(ast::Arg::Spread(_)) => None,
+        Some(ast::Arg::Pos(_)) | None => {
+            let mut index = positional;
+            for (i, param) in params.iter().enumerate() {
+                if !param.positional() {
+                    continue;
+                }
+                if param.variadic() || index == 0 {
+                    return Some(i);
+                }
+                index -= 1;
+            }
+            None
+        }
+    }
+}
+
+/// Render a parameter for a signature's label.
+fn write_param(label: &mut EcoString, param: &ParamInfo) {
+    if param.variadic() {
+        label.push_str("..");
+    }
+
+    label.push_str(param.name().unwrap_or("_"));
+
+    match param {
+        ParamInfo::Native(info) => {
+            write!(label, ": {}", describe(&info.input)).unwrap();
+        }
+        ParamInfo::Plugin => label.push_str(": bytes"),
+        ParamInfo::Closure(_) => {}
+    }
+
+    if !param.required()
+        && param.named()
+        && let Some(default) = param.default()
+        && let repr = default.repr()
+        && repr.len() <= MAX_DEFAULT_LEN
+    {
+        write!(label, " = {repr}").unwrap();
+    }
+}
+
+/// Describe the values a parameter accepts or a function returns.
+fn describe(info: &CastInfo) -> EcoString {
+    let mut parts: Vec<EcoString> = vec![];
+    info.walk(|info| {
+        let part = match info {
+            CastInfo::Any => "any".into(),
+            CastInfo::Value(value, _) => value.repr(),
+            CastInfo::Type(ty) => ty.short_name().into(),
+            CastInfo::Union(_) => return,
+        };
+        if !parts.contains(&part) {
+            parts.push(part);
+        }
+    });
+    parts.join(" | ").into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Borrow;
+
+    use typst_layout::PagedDocument;
+
+    use super::{SignatureHelp, signature_help};
+    use crate::tests::{FilePos, TestWorld, WorldLike};
+
+    type Response = Option<SignatureHelp>;
+
+    trait ResponseExt {
+        /// Assert that there is no signature.
+        fn must_be_none(&self) -> &Self;
+        /// Assert that the signature's label starts with the given text.
+        fn must_start_with(&self, prefix: &str) -> &Self;
+        /// Assert that the active parameter is the given one.
+        fn must_be_active(&self, param: &str) -> &Self;
+        /// Assert that no parameter is active.
+        fn must_have_no_active(&self) -> &Self;
+    }
+
+    impl ResponseExt for Response {
+        #[track_caller]
+        fn must_be_none(&self) -> &Self {
+            assert_eq!(*self, None);
+            self
+        }
+
+        #[track_caller]
+        fn must_start_with(&self, prefix: &str) -> &Self {
+            let help = self.as_ref().expect("expected a signature");
+            assert!(
+                help.label.starts_with(prefix),
+                "{:?} does not start with {prefix:?}",
+                help.label,
+            );
+            self
+        }
+
+        #[track_caller]
+        fn must_be_active(&self, param: &str) -> &Self {
+            let help = self.as_ref().expect("expected a signature");
+            let active = help.active.expect("expected an active parameter");
+            let label = &help.label[help.params[active].range.clone()];
+            assert!(
+                label.trim_start_matches("..").starts_with(param),
+                "active parameter is {label:?}, expected {param:?}",
+            );
+            self
+        }
+
+        #[track_caller]
+        fn must_have_no_active(&self) -> &Self {
+            let help = self.as_ref().expect("expected a signature");
+            assert_eq!(help.active, None);
+            self
+        }
+    }
+
+    #[track_caller]
+    fn test(world: impl WorldLike, pos: impl FilePos) -> Response {
+        let world = world.acquire();
+        let world = world.borrow();
+        let (source, cursor) = pos.resolve(world);
+        let doc = typst::compile::<PagedDocument>(world).output.ok();
+        signature_help(world, doc.as_ref(), &source, cursor)
+    }
+
+    #[test]
+    fn test_signature_help_native() {
+        test("#rgb(1, 2)", 5).must_start_with("rgb(").must_be_active("red");
+        test("#rgb(1, 2)", 8).must_be_active("green");
+        test("#lorem()", 7).must_be_active("words");
+        test("#lorem()", 1).must_be_none();
+        test("#lorem()", -1).must_be_none();
+    }
+
+    #[test]
+    fn test_signature_help_element() {
+        test("#heading(level: 2)[Hi]", 10)
+            .must_start_with("heading(")
+            .must_be_active("level");
+        test("#heading(level: 2, )", -3).must_be_active("body");
+        test("#set text(fill: red)", 12)
+            .must_start_with("text(")
+            .must_be_active("fill");
+        test("#heading[Hi]", 10).must_be_none();
+    }
+
+    #[test]
+    fn test_signature_help_closure() {
+        let world = TestWorld::new("#let f(a, b, c: 1, ..d) = none\n#f(1, 2, 3, c: 4)");
+        test(&world, -15).must_be_active("a");
+        test(&world, -12).must_be_active("b");
+        test(&world, -9).must_be_active("..d");
+        test(&world, -4).must_be_active("c");
+        test(&world, -4).must_start_with("f(a, b, c = 1, ..d)");
+    }
+
+    #[test]
+    fn test_signature_help_method() {
+        test("#\"a,b\".split(\",\")", 14)
+            .must_start_with("split(pattern")
+            .must_be_active("pattern");
+        test("#str.split(\"a,b\", \",\")", 12)
+            .must_start_with("split(self")
+            .must_be_active("self");
+    }
+
+    #[test]
+    fn test_signature_help_spread() {
+        test("#let args = (1, 2)\n#rgb(..args)", -3).must_have_no_active();
+    }
+}
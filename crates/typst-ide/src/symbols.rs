@@ -0,0 +1,202 @@
+use std::num::NonZeroUsize;
+use std::ops::Range;
+
+use ecow::EcoString;
+use typst::syntax::ast::{self, AstNode};
+use typst::syntax::{LinkedNode, Source, SyntaxKind};
+
+/// An item in the outline of a source file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct DocumentSymbol {
+    /// The name of the item.
+    pub name: EcoString,
+    /// What kind of item this is.
+    pub kind: SymbolKind,
+    /// The byte range of the whole item.
+    ///
+    /// For headings, this spans the whole section up to the next heading of
+    /// the same or a higher level.
+    pub range: Range<usize>,
+    /// The byte range of the item's name.
+    pub selection: Range<usize>,
+    /// The items nested in this one. Only headings have children.
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// The kind of a [`DocumentSymbol`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SymbolKind {
+    /// A section heading with the given depth.
+    Heading(NonZeroUsize),
+    /// A `let` binding of a value other than a function.
+    Variable,
+    /// A `let` binding of a function.
+    Function,
+    /// A label.
+    Label,
+}
+
+/// Build the outline of a source file.
+///
+/// The outline contains headings, labels, and the `let` bindings that aren't
+/// local to a function. Items are nested into the section they appear in.
+pub fn document_symbols(source: &Source) -> Vec<DocumentSymbol> {
+    let mut outline = Outline { sections: vec![], symbols: vec![] };
+    outline.node(&LinkedNode::new(source.root()), false);
+    outline.close(1, source.text().len());
+    outline.symbols
+}
+
+/// Collects the symbols of a source file.
+struct Outline {
+    /// The currently open sections with their depth, innermost last.
+    sections: Vec<(usize, DocumentSymbol)>,
+    /// The finished top-level symbols.
+    symbols: Vec<DocumentSymbol>,
+}
+
+impl Outline {
+    /// Collect the symbols in a node and its descendants.
+    fn node(&mut self, node: &LinkedNode, local: bool) {
+        if let Some(heading) = node.cast::<ast::Heading>() {
+            let body = heading.body().to_untyped();
+            let name = body.full_text();
+            let name: EcoString =
+                name.split_whitespace().collect::<Vec<_>>().join(" ").into();
+            if !name.is_empty() {
+                let depth = heading.depth().get();
+                let start = node.offset();
+                let selection = node
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::Markup)
+                    .map_or(node.range(), |child| child.range());
+                self.close(depth, start);
+                self.sections.push((
+                    depth,
+                    DocumentSymbol {
+                        name,
+                        kind: SymbolKind::Heading(heading.depth()),
+                        range: node.range(),
+                        selection,
+                        children: vec![],
+                    },
+                ));
+            }
+        } else if let Some(label) = node.cast::<ast::Label>() {
+            let range = node.range();
+            self.push(DocumentSymbol {
+                name: label.get().into(),
+                kind: SymbolKind::Label,
+                selection: range.start + 1..range.end - 1,
+                range,
+                children: vec![],
+            });
+        } else if let Some(binding) = node.cast::<ast::LetBinding>()
+            && !local
+        {
+            let kind = match binding.kind() {
+                ast::LetBindingKind::Closure(_) => SymbolKind::Function,
+                ast::LetBindingKind::Normal(_) => match binding.init() {
+                    Some(ast::Expr::Closure(_)) => SymbolKind::Function,
+                    _ => SymbolKind::Variable,
+                },
+            };
+            for ident in binding.kind().bindings() {
+                let Some(ident) = node.find(ident.span()) else { continue };
+                self.push(DocumentSymbol {
+                    name: ident.leaf_text().clone(),
+                    kind,
+                    range: node.range(),
+                    selection: ident.range(),
+                    children: vec![],
+                });
+            }
+        }
+
+        let local = local || node.kind() == SyntaxKind::Closure;
+        for child in node.children() {
+            self.node(&child, local);
+        }
+    }
+
+    /// Add a symbol to the innermost open section.
+    fn push(&mut self, symbol: DocumentSymbol) {
+        match self.sections.last_mut() {
+            Some((_, section)) => section.children.push(symbol),
+            None => self.symbols.push(symbol),
+        }
+    }
+
+    /// Close all open sections with at least the given depth, ending them at
+    /// the given offset.
+    fn close(&mut self, depth: usize, end: usize) {
+        while let Some((d, _)) = self.sections.last()
+            && *d >= depth
+        {
+            let (_, mut section) = self.sections.pop().unwrap();
+            section.range.end = end;
+            self.push(section);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use typst::syntax::Source;
+
+    use super::{DocumentSymbol, SymbolKind, document_symbols};
+
+    /// Flatten the outline into names, kinds, and nesting depths.
+    fn flatten(symbols: &[DocumentSymbol], level: usize, out: &mut Vec<String>) {
+        for symbol in symbols {
+            let kind = match symbol.kind {
+                SymbolKind::Heading(depth) => format!("h{depth}"),
+                SymbolKind::Variable => "var".into(),
+                SymbolKind::Function => "fn".into(),
+                SymbolKind::Label => "label".into(),
+            };
+            out.push(format!("{}{kind} {}", "  ".repeat(level), symbol.name));
+            flatten(&symbol.children, level + 1, out);
+        }
+    }
+
+    #[track_caller]
+    fn test(text: &str) -> Vec<String> {
+        let mut out = vec![];
+        flatten(&document_symbols(&Source::detached(text)), 0, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_document_symbols() {
+        let text = "#let (a, b) = (1, 2)\n\
+                    = Intro <intro>\n\
+                    #let f(x) = { let y = x; y }\n\
+                    == Details\n\
+                    #let g = x => x\n\
+                    = Outro\n";
+        assert_eq!(
+            test(text),
+            [
+                "var a",
+                "var b",
+                "h1 Intro",
+                "  label intro",
+                "  fn f",
+                "  h2 Details",
+                "    fn g",
+                "h1 Outro",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_document_symbols_ranges() {
+        let text = "= A\nText\n== B\n= C";
+        let symbols = document_symbols(&Source::detached(text));
+        assert_eq!(symbols[0].range, 0..14);
+        assert_eq!(symbols[0].selection, 2..3);
+        assert_eq!(symbols[0].children[0].range, 9..14);
+        assert_eq!(symbols[1].range, 14..17);
+    }
+}
@@ -0,0 +1,413 @@
+use std::ops::Range;
+
+use ecow::EcoString;
+use rustc_hash::FxHashSet;
+use typst::syntax::{LinkedNode, Source, Span, SyntaxKind, Tag, highlight, is_newline};
+
+use crate::utils::declarations;
+
+/// A semantic token, positioned relative to the previous token.
+///
+/// This follows the encoding of the Language Server Protocol: Columns and
+/// lengths are measured in UTF-16 code units.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SemanticToken {
+    /// The line of the token, relative to the line of the previous token.
+    pub delta_line: u32,
+    /// The column of the token. If the token is on the same line as the
+    /// previous one, this is relative to the previous token's column.
+    pub delta_start: u32,
+    /// The length of the token.
+    pub length: u32,
+    /// The kind of the token.
+    pub ty: TokenType,
+    /// Additional properties of the token.
+    pub modifiers: TokenModifiers,
+}
+
+/// The kind of a semantic token.
+///
+/// This mirrors the syntax highlighting [`Tag`]s, but folds the math-specific
+/// tags into their general counterparts (marking them with
+/// [`TokenModifiers::MATH`] instead) and adds plain variables.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TokenType {
+    /// A line or block comment.
+    Comment,
+    /// Punctuation in code or grouping parentheses in math.
+    Punctuation,
+    /// An escape sequence or shorthand.
+    Escape,
+    /// Strong markup.
+    Strong,
+    /// Emphasized markup.
+    Emph,
+    /// A hyperlink.
+    Link,
+    /// Raw text.
+    Raw,
+    /// A label.
+    Label,
+    /// A reference to a label.
+    Ref,
+    /// A section heading.
+    Heading,
+    /// A marker of a list, enumeration, or term list.
+    ListMarker,
+    /// A term in a term list.
+    ListTerm,
+    /// The delimiters of an equation.
+    Delimiter,
+    /// An operator in code or math.
+    Operator,
+    /// A keyword.
+    Keyword,
+    /// A numeric literal.
+    Number,
+    /// A string literal.
+    String,
+    /// A function or method name.
+    Function,
+    /// An interpolated variable in markup or math.
+    Interpolated,
+    /// A variable in code.
+    Variable,
+    /// A syntax error.
+    Error,
+}
+
+impl TokenType {
+    /// The list of all token types, in the same order as they are defined.
+    ///
+    /// Can be used as the counter-part to `ty as usize`.
+    pub const LIST: &'static [TokenType] = &[
+        Self::Comment,
+        Self::Punctuation,
+        Self::Escape,
+        Self::Strong,
+        Self::Emph,
+        Self::Link,
+        Self::Raw,
+        Self::Label,
+        Self::Ref,
+        Self::Heading,
+        Self::ListMarker,
+        Self::ListTerm,
+        Self::Delimiter,
+        Self::Operator,
+        Self::Keyword,
+        Self::Number,
+        Self::String,
+        Self::Function,
+        Self::Interpolated,
+        Self::Variable,
+        Self::Error,
+    ];
+
+    /// The name of the token type in a Language Server Protocol legend.
+    ///
+    /// Uses the predefined names where one fits.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Comment => "comment",
+            Self::Punctuation => "punctuation",
+            Self::Escape => "escape",
+            Self::Strong => "strong",
+            Self::Emph => "emph",
+            Self::Link => "link",
+            Self::Raw => "raw",
+            Self::Label => "label",
+            Self::Ref => "ref",
+            Self::Heading => "heading",
+            Self::ListMarker => "listMarker",
+            Self::ListTerm => "listTerm",
+            Self::Delimiter => "delimiter",
+            Self::Operator => "operator",
+            Self::Keyword => "keyword",
+            Self::Number => "number",
+            Self::String => "string",
+            Self::Function => "function",
+            Self::Interpolated => "interpolated",
+            Self::Variable => "variable",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl From<Tag> for TokenType {
+    fn from(tag: Tag) -> Self {
+        match tag {
+            Tag::Comment => Self::Comment,
+            Tag::Punctuation => Self::Punctuation,
+            Tag::Escape => Self::Escape,
+            Tag::Strong => Self::Strong,
+            Tag::Emph => Self::Emph,
+            Tag::Link => Self::Link,
+            Tag::Raw => Self::Raw,
+            Tag::Label => Self::Label,
+            Tag::Ref => Self::Ref,
+            Tag::Heading => Self::Heading,
+            Tag::ListMarker => Self::ListMarker,
+            Tag::ListTerm => Self::ListTerm,
+            Tag::MathDelimiter => Self::Delimiter,
+            Tag::MathOperator => Self::Operator,
+            Tag::MathGroupingParens => Self::Punctuation,
+            Tag::Keyword => Self::Keyword,
+            Tag::Operator => Self::Operator,
+            Tag::Number => Self::Number,
+            Tag::String => Self::String,
+            Tag::Function => Self::Function,
+            Tag::Interpolated => Self::Interpolated,
+            Tag::Error => Self::Error,
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// Additional properties of a semantic token.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct TokenModifiers: u32 {
+        /// The token introduces a binding or a label.
+        const DEFINITION = 1 << 0;
+        /// The token accesses a variable that can't be modified at this
+        /// point because it was captured by a closure.
+        const READONLY = 1 << 1;
+        /// The token is part of an equation.
+        const MATH = 1 << 2;
+    }
+}
+
+impl TokenModifiers {
+    /// The names of the modifiers in a Language Server Protocol legend, in
+    /// the order of their bits.
+    pub const NAMES: &'static [&'static str] = &["definition", "readonly", "math"];
+}
+
+/// Produce the semantic tokens of a source file.
+///
+/// The tokens are sorted, don't overlap, and never span multiple lines.
+pub fn semantic_tokens(source: &Source) -> Vec<SemanticToken> {
+    let mut tokenizer = Tokenizer {
+        source,
+        declarations: declarations(source.root()),
+        scopes: vec![],
+        tokens: vec![],
+        last: (0, 0),
+    };
+
+    let root = LinkedNode::new(source.root());
+    tokenizer.node(&root, None, TokenModifiers::empty());
+    tokenizer.tokens
+}
+
+/// Walks the syntax tree and collects semantic tokens.
+struct Tokenizer<'a> {
+    source: &'a Source,
+    /// The spans of all identifiers that introduce a binding.
+    declarations: FxHashSet<Span>,
+    /// The names declared within each of the closures around the current
+    /// node, innermost last.
+    scopes: Vec<FxHashSet<EcoString>>,
+    tokens: Vec<SemanticToken>,
+    /// The line and UTF-16 column of the previous token.
+    last: (usize, usize),
+}
+
+impl Tokenizer<'_> {
+    /// Tokenize a node and its descendants. Nodes without a highlighting tag
+    /// of their own inherit the token type of their parent.
+    fn node(
+        &mut self,
+        node: &LinkedNode,
+        inherited: Option<TokenType>,
+        mut modifiers: TokenModifiers,
+    ) {
+        let mut ty = highlight(node).map(TokenType::from).or(inherited);
+        match node.kind() {
+            SyntaxKind::Equation => modifiers |= TokenModifiers::MATH,
+            SyntaxKind::Label => modifiers |= TokenModifiers::DEFINITION,
+            SyntaxKind::Ident | SyntaxKind::MathIdent if is_binding(node) => {
+                ty = ty.or(Some(TokenType::Variable));
+                if self.declarations.contains(&node.span()) {
+                    modifiers |= TokenModifiers::DEFINITION;
+                } else if let Some(scope) = self.scopes.last()
+                    && !scope.contains(node.leaf_text())
+                {
+                    modifiers |= TokenModifiers::READONLY;
+                }
+            }
+            _ => {}
+        }
+
+        if node.children().len() == 0 {
+            if let Some(ty) = ty
+                && !matches!(node.kind(), SyntaxKind::Space | SyntaxKind::Parbreak)
+            {
+                self.leaf(node.range(), ty, modifiers);
+            }
+            return;
+        }
+
+        let closure = node.kind() == SyntaxKind::Closure;
+        if closure {
+            let mut names = FxHashSet::default();
+            self.declared(node, &mut names);
+            self.scopes.push(names);
+        }
+
+        for child in node.children() {
+            self.node(&child, ty, modifiers);
+        }
+
+        if closure {
+            self.scopes.pop();
+        }
+    }
+
+    /// Emit tokens for a leaf, splitting it at line breaks.
+    fn leaf(&mut self, range: Range<usize>, ty: TokenType, modifiers: TokenModifiers) {
+        let text = &self.source.text()[range.clone()];
+        let mut start = 0;
+        for (i, c) in text.char_indices().chain([(text.len(), '\n')]) {
+            if !is_newline(c) {
+                continue;
+            }
+            if !text[start..i].trim().is_empty() {
+                self.push(range.start + start..range.start + i, ty, modifiers);
+            }
+            start = i + c.len_utf8();
+        }
+    }
+
+    /// Emit a token for a single-line range.
+    fn push(&mut self, range: Range<usize>, ty: TokenType, modifiers: TokenModifiers) {
+        let lines = self.source.lines();
+        let (Some(line), Some(start), Some(end)) = (
+            lines.byte_to_line(range.start),
+            lines.byte_to_utf16(range.start),
+            lines.byte_to_utf16(range.end),
+        ) else {
+            return;
+        };
+
+        let line_start = lines
+            .line_to_byte(line)
+            .and_then(|byte| lines.byte_to_utf16(byte))
+            .unwrap_or(0);
+        let column = start - line_start;
+
+        let (last_line, last_column) = self.last;
+        let delta_line = line - last_line;
+        let delta_start = if delta_line == 0 { column - last_column } else { column };
+        self.last = (line, column);
+
+        self.tokens.push(SemanticToken {
+            delta_line: delta_line as u32,
+            delta_start: delta_start as u32,
+            length: (end - start) as u32,
+            ty,
+            modifiers,
+        });
+    }
+
+    /// Collect the names of all bindings declared within a node.
+    fn declared(&self, node: &LinkedNode, names: &mut FxHashSet<EcoString>) {
+        if self.declarations.contains(&node.span()) {
+            names.insert(node.leaf_text().clone());
+        }
+        for child in node.children() {
+            self.declared(&child, names);
+        }
+    }
+}
+
+/// Whether an identifier declares or refers to a binding, as opposed to
+/// being a field or argument name.
+fn is_binding(node: &LinkedNode) -> bool {
+    let Some(parent) = node.parent() else { return true };
+    match parent.kind() {
+        SyntaxKind::FieldAccess | SyntaxKind::MathFieldAccess => node.index() == 0,
+        SyntaxKind::Named if parent.parent_kind() != Some(SyntaxKind::Params) => {
+            node.index() > 0
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use typst::syntax::Source;
+
+    use super::{TokenModifiers, TokenType, semantic_tokens};
+
+    #[track_caller]
+    fn test(text: &str) -> Vec<(u32, u32, u32, TokenType, TokenModifiers)> {
+        semantic_tokens(&Source::detached(text))
+            .into_iter()
+            .map(|t| (t.delta_line, t.delta_start, t.length, t.ty, t.modifiers))
+            .collect()
+    }
+
+    #[test]
+    fn test_semantic_tokens_markup() {
+        let none = TokenModifiers::empty();
+        assert_eq!(
+            test("= Hi\n*a* <l>"),
+            [
+                (0, 0, 1, TokenType::Heading, none),
+                (0, 2, 2, TokenType::Heading, none),
+                (1, 0, 1, TokenType::Strong, none),
+                (0, 1, 1, TokenType::Strong, none),
+                (0, 1, 1, TokenType::Strong, none),
+                (0, 2, 3, TokenType::Label, TokenModifiers::DEFINITION),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_semantic_tokens_code() {
+        let none = TokenModifiers::empty();
+        let definition = TokenModifiers::DEFINITION;
+        assert_eq!(
+            test("#let f(x) = x + y"),
+            [
+                (0, 0, 1, TokenType::Keyword, none),
+                (0, 1, 3, TokenType::Keyword, none),
+                (0, 4, 1, TokenType::Function, definition),
+                (0, 1, 1, TokenType::Punctuation, none),
+                (0, 1, 1, TokenType::Variable, definition),
+                (0, 1, 1, TokenType::Punctuation, none),
+                (0, 2, 1, TokenType::Operator, none),
+                (0, 2, 1, TokenType::Variable, none),
+                (0, 2, 1, TokenType::Operator, none),
+                (0, 2, 1, TokenType::Variable, TokenModifiers::READONLY),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_semantic_tokens_math() {
+        let math = TokenModifiers::MATH;
+        assert_eq!(
+            test("$pi^2$"),
+            [
+                (0, 0, 1, TokenType::Delimiter, math),
+                (0, 1, 2, TokenType::Interpolated, math),
+                (0, 2, 1, TokenType::Operator, math),
+                (0, 2, 1, TokenType::Delimiter, math),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_semantic_tokens_multiline() {
+        let tokens = test("/* a\n  b */");
+        assert_eq!(
+            tokens,
+            [
+                (0, 0, 4, TokenType::Comment, TokenModifiers::empty()),
+                (1, 0, 6, TokenType::Comment, TokenModifiers::empty()),
+            ]
+        );
+    }
+}
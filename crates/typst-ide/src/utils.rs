@@ -4,10 +4,12 @@ use std::ops::ControlFlow;
 use comemo::Track;
 use ecow::EcoString;
 use indexmap::IndexMap;
+use rustc_hash::FxHashSet;
 use typst::engine::{Engine, Route, Sink, Traced};
 use typst::foundations::{Scope, SilentBindingGuard, Value};
 use typst::introspection::EmptyIntrospector;
-use typst::syntax::{LinkedNode, SyntaxMode};
+use typst::syntax::ast::{self, AstNode};
+use typst::syntax::{LinkedNode, Span, SyntaxMode, SyntaxNode};
 use typst::text::{
     AxisValue, FontAxis, FontFlags, FontInfo, FontStretch, FontStyle, FontWeight,
     StandardAxes,
@@ -181,6 +183,48 @@ pub fn globals<'a>(world: &'a dyn IdeWorld, leaf: &LinkedNode) -> &'a Scope {
     }
 }
 
+/// Collect the spans of all identifiers in the tree that introduce a new
+/// binding.
+pub fn declarations(root: &SyntaxNode) -> FxHashSet<Span> {
+    fn walk(node: &SyntaxNode, spans: &mut FxHashSet<Span>) {
+        let mut add = |idents: Vec<ast::Ident>| {
+            spans.extend(idents.into_iter().map(|ident| ident.span()));
+        };
+
+        if let Some(binding) = node.cast::<ast::LetBinding>() {
+            add(binding.kind().bindings());
+        } else if let Some(for_loop) = node.cast::<ast::ForLoop>() {
+            add(for_loop.pattern().bindings());
+        } else if let Some(closure) = node.cast::<ast::Closure>() {
+            for param in closure.params().children() {
+                match param {
+                    ast::Param::Pos(pattern) => add(pattern.bindings()),
+                    ast::Param::Named(named) => add(vec![named.name()]),
+                    ast::Param::Spread(spread) => {
+                        add(spread.sink_ident().into_iter().collect());
+                    }
+                }
+            }
+        }
+
+        for child in node.children() {
+            walk(child, spans);
+        }
+    }
+
+    let mut spans = FxHashSet::default();
+    walk(root, &mut spans);
+    spans
+}
+
+/// Call `f` for the node and all of its descendants.
+pub fn visit<'a>(node: &LinkedNode<'a>, f: &mut impl FnMut(&LinkedNode<'a>)) {
+    f(node);
+    for child in node.children() {
+        visit(&child, f);
+    }
+}
+
 /// Checks whether the given value or any of its constituent parts satisfy the
 /// predicate.
 pub fn check_value_recursively(
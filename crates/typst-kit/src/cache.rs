@@ -0,0 +1,317 @@
+//! A persistent cache that lets separate processes share expensive work.
+//!
+//! Within a single process, Typst relies on memoization to avoid repeated
+//! work. A [`DiskCache`] additionally persists some intermediate results in a
+//! directory, so that cold builds, e.g. in CI, can reuse them. Currently, this
+//! covers:
+//!
+//! - The metadata of fonts found during font discovery, via
+//!   [`fonts::system_cached`](crate::fonts::system_cached) and
+//!   [`fonts::scan_cached`](crate::fonts::scan_cached).
+//! - Decoded raster images, by returning the cache from
+//!   [`World::image_store`](typst_library::World::image_store).
+//! - The syntax trees of package sources, by passing the cache to
+//!   [`FileStore::with_cache`](crate::files::FileStore::with_cache).
+//!
+//! Entries are keyed by a hash of the content they were derived from, except
+//! for font metadata, which is keyed by the font file's path, size, and
+//! modification time to avoid reading the files. Cache directories can be
+//! shared between Typst versions because entries are namespaced by version.
+//! Failing to read or write an entry is never an error; the work is simply
+//! redone.
+//!
+//! The cache's size is bounded by [`prune`](DiskCache::prune), which evicts the
+//! least recently used entries.
+
+#![cfg(feature = "disk-cache")]
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use typst_library::visualize::ImageStore;
+use typst_syntax::{FileId, Source, SyntaxKind, SyntaxNode};
+
+/// A directory that stores cached data.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    /// The version-specific directory in which entries are stored.
+    dir: PathBuf,
+    /// The maximum total size of the entries in bytes.
+    limit: u64,
+}
+
+impl DiskCache {
+    /// The default maximum total size of the entries: 1 GiB.
+    pub const DEFAULT_LIMIT: u64 = 1 << 30;
+
+    /// Creates a cache that stores its entries in the given directory.
+    ///
+    /// The directory is created lazily once the first entry is written.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into().join(concat!("v", env!("CARGO_PKG_VERSION")));
+        Self { dir, limit: Self::DEFAULT_LIMIT }
+    }
+
+    /// Creates a cache in the system-dependent default location, if there is
+    /// one.
+    pub fn system() -> Option<Self> {
+        dirs::cache_dir().map(|dir| Self::new(dir.join("typst/build")))
+    }
+
+    /// Sets the maximum total size of the entries in bytes, which
+    /// [`prune`](Self::prune) enforces.
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// The directory in which the entries for this Typst version are stored.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Loads the raw entry of the given kind for the given key.
+    ///
+    /// Marks the entry as recently used, so that it survives pruning.
+    pub fn load(&self, kind: &str, key: u128) -> Option<Vec<u8>> {
+        let path = self.path(kind, key);
+        let data = fs::read(&path).ok()?;
+        if let Ok(file) = fs::File::options().write(true).open(&path) {
+            file.set_modified(SystemTime::now()).ok();
+        }
+        Some(data)
+    }
+
+    /// Stores a raw entry of the given kind for the given key.
+    ///
+    /// The entry is written to a temporary file first and then moved into
+    /// place, so that concurrent readers never observe partial entries.
+    pub fn store(&self, kind: &str, key: u128, data: &[u8]) {
+        let path = self.path(kind, key);
+        let write = || -> io::Result<()> {
+            let parent = path.parent().unwrap();
+            fs::create_dir_all(parent)?;
+            let temp = parent.join(format!(
+                ".{key:032x}.{}.{:x}",
+                std::process::id(),
+                fastrand::u64(..),
+            ));
+            fs::write(&temp, data)?;
+            fs::rename(&temp, &path).inspect_err(|_| {
+                fs::remove_file(&temp).ok();
+            })
+        };
+        write().ok();
+    }
+
+    /// Loads and deserializes the entry of the given kind for the given key.
+    ///
+    /// Returns `None` if there is no entry or if it cannot be deserialized,
+    /// e.g. because it was written by an incompatible version.
+    pub fn load_value<T: DeserializeOwned>(&self, kind: &str, key: u128) -> Option<T> {
+        let data = self.load(kind, key)?;
+        ciborium::from_reader(data.as_slice()).ok()
+    }
+
+    /// Serializes and stores a value as the entry of the given kind for the
+    /// given key.
+    pub fn store_value<T: Serialize>(&self, kind: &str, key: u128, value: &T) {
+        let mut data = vec![];
+        if ciborium::into_writer(value, &mut data).is_ok() {
+            self.store(kind, key, &data);
+        }
+    }
+
+    /// Creates a source file, reusing its syntax tree from the cache if the
+    /// same text was parsed before.
+    ///
+    /// Only syntax trees without errors and warnings are stored.
+    pub fn source(&self, id: FileId, text: String) -> Source {
+        let key = typst_utils::hash128(&text);
+        if let Some(tree) = self.load_value::<Tree>("syntax", key) {
+            let mut root = tree.build();
+            if root.renumber(id) {
+                return Source::with_root(id, text, root);
+            }
+        }
+
+        let source = Source::new(id, text);
+        let root = source.root();
+        if !root.diagnosis().either() {
+            self.store_value("syntax", key, &Tree::new(root));
+        }
+
+        source
+    }
+
+    /// Evicts the least recently used entries until the total size of the
+    /// entries is within the limit.
+    ///
+    /// Entries are only evicted when this is called, so the limit can be
+    /// exceeded temporarily while the cache is in use.
+    pub fn prune(&self) -> io::Result<()> {
+        let mut entries = vec![];
+        let mut total = 0;
+        let mut dirs = vec![self.dir.clone()];
+        while let Some(dir) = dirs.pop() {
+            let children = match fs::read_dir(&dir) {
+                Ok(children) => children,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+
+            // Entries may be removed concurrently by other processes.
+            for child in children.flatten() {
+                let Ok(meta) = child.metadata() else { continue };
+                if meta.is_dir() {
+                    dirs.push(child.path());
+                } else {
+                    let used = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    total += meta.len();
+                    entries.push((used, meta.len(), child.path()));
+                }
+            }
+        }
+
+        entries.sort();
+        for (_, len, path) in entries {
+            if total <= self.limit {
+                break;
+            }
+            match fs::remove_file(path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                _ => total -= len,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes all entries for this Typst version.
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        }
+    }
+
+    /// The path of the entry of the given kind for the given key.
+    fn path(&self, kind: &str, key: u128) -> PathBuf {
+        // Shard by the first byte to keep directories reasonably small.
+        let hex = format!("{key:032x}");
+        self.dir.join(kind).join(&hex[..2]).join(&hex[2..])
+    }
+}
+
+impl ImageStore for DiskCache {
+    fn load(&self, key: u128) -> Option<Vec<u8>> {
+        DiskCache::load(self, "image", key)
+    }
+
+    fn store(&self, key: u128, buf: &[u8]) {
+        DiskCache::store(self, "image", key, buf)
+    }
+}
+
+/// A syntax tree as it is persisted in the cache.
+#[derive(Serialize, Deserialize)]
+enum Tree {
+    Leaf(SyntaxKind, String),
+    Inner(SyntaxKind, Vec<Tree>),
+}
+
+impl Tree {
+    /// Captures an error-free syntax tree.
+    fn new(node: &SyntaxNode) -> Self {
+        if node.is_leaf() {
+            Self::Leaf(node.kind(), node.leaf_text().to_string())
+        } else {
+            Self::Inner(node.kind(), node.children().map(Self::new).collect())
+        }
+    }
+
+    /// Rebuilds the syntax tree.
+    fn build(self) -> SyntaxNode {
+        match self {
+            Self::Leaf(kind, text) => SyntaxNode::leaf(kind, text),
+            Self::Inner(kind, children) => {
+                SyntaxNode::inner(kind, children.into_iter().map(Self::build).collect())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use typst_syntax::{RootedPath, VirtualPath, VirtualRoot};
+
+    use super::DiskCache;
+
+    #[test]
+    fn test_disk_cache_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path());
+        assert_eq!(cache.load("test", 1), None);
+
+        cache.store("test", 1, b"hello");
+        assert_eq!(cache.load("test", 1).as_deref(), Some(b"hello".as_slice()));
+        assert_eq!(cache.load("test", 2), None);
+        assert_eq!(cache.load("other", 1), None);
+
+        cache.store_value("value", 3, &("a", 4));
+        assert_eq!(cache.load_value::<(String, i32)>("value", 3), Some(("a".into(), 4)));
+        assert_eq!(cache.load_value::<Vec<f64>>("value", 3), None);
+
+        cache.clear().unwrap();
+        assert_eq!(cache.load("test", 1), None);
+        cache.clear().unwrap();
+    }
+
+    #[test]
+    fn test_disk_cache_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path()).with_limit(8);
+        let touch = |key, secs| {
+            let file = File::options().write(true).open(cache.path("test", key)).unwrap();
+            file.set_modified(UNIX_EPOCH + Duration::from_secs(secs)).unwrap();
+        };
+
+        cache.store("test", 1, b"abcd");
+        cache.store("test", 2, b"efgh");
+        touch(1, 10);
+        touch(2, 20);
+        cache.prune().unwrap();
+        assert!(cache.load("test", 1).is_some());
+
+        // Loading the first entry made it more recently used than the second.
+        cache.store("test", 3, b"ijkl");
+        cache.prune().unwrap();
+        assert!(cache.load("test", 1).is_some());
+        assert_eq!(cache.load("test", 2), None);
+        assert!(cache.load("test", 3).is_some());
+    }
+
+    #[test]
+    fn test_disk_cache_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path());
+        let id =
+            RootedPath::new(VirtualRoot::Project, VirtualPath::new("a.typ").unwrap())
+                .intern();
+        let text = "= Heading\n#let f(x) = [*#x*]\n$ a + b $ ``` raw ```";
+
+        let first = cache.source(id, text.into());
+        let second = cache.source(id, text.into());
+        assert!(first.root().spanless_eq(second.root()));
+        assert_eq!(second.root().span(), first.root().span());
+        let range = second.find(second.root().span()).map(|node| node.range());
+        assert_eq!(range, Some(0..text.len()));
+    }
+}
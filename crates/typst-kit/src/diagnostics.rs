@@ -9,6 +9,8 @@ use std::ops::Range;
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::files::Files;
 use codespan_reporting::term;
+use serde::Serialize;
+use serde_json::json;
 use termcolor::{Color, ColorSpec, WriteColor};
 use typst_library::World;
 use typst_library::diag::{FileError, Severity, SourceDiagnostic, Tracepoint};
@@ -35,6 +37,16 @@ pub enum DiagnosticFormat {
     Human,
     /// Displays a short single-line diagnostic.
     Short,
+    /// Emits one JSON object per diagnostic and line.
+    ///
+    /// Each object has the fields `severity` (`"error"` or `"warning"`),
+    /// `message`, `span`, `hints` (each with a `message` and a `span`), and
+    /// `trace` (each with a `message` and a `span`). A span is either `null`
+    /// or has a `path`, a byte `range`, and a `start` and `end` position.
+    /// Lines and columns are one-based and columns count characters.
+    Json,
+    /// Emits a SARIF 2.1.0 log with all diagnostics.
+    Sarif,
 }
 
 /// Emits diagnostic messages to a writable, colorized output.
@@ -46,6 +58,12 @@ pub fn emit<'a>(
 ) -> Result<(), codespan_reporting::files::Error> {
     let mut files = WorldFiles { world, sources: HashMap::new() };
 
+    match format {
+        DiagnosticFormat::Human | DiagnosticFormat::Short => {}
+        DiagnosticFormat::Json => return emit_json(dest, &mut files, diagnostics),
+        DiagnosticFormat::Sarif => return emit_sarif(dest, &mut files, diagnostics),
+    }
+
     let mut config = term::Config { tab_width: 2, ..Default::default() };
     if format == DiagnosticFormat::Short {
         config.display_style = term::DisplayStyle::Short;
@@ -145,6 +163,201 @@ fn emit_trace(
     Ok(())
 }
 
+/// Converts diagnostics into JSON values.
+///
+/// The values have the same shape as the lines emitted with
+/// [`DiagnosticFormat::Json`].
+pub fn to_json<'a>(
+    world: &dyn DiagnosticWorld,
+    diagnostics: impl IntoIterator<Item = &'a SourceDiagnostic>,
+) -> Vec<serde_json::Value> {
+    let mut files = WorldFiles { world, sources: HashMap::new() };
+    diagnostics
+        .into_iter()
+        .filter_map(|diagnostic| {
+            serde_json::to_value(json_diagnostic(&mut files, diagnostic)).ok()
+        })
+        .collect()
+}
+
+/// Emits diagnostics as JSON lines.
+fn emit_json<'a>(
+    dest: &mut dyn WriteColor,
+    files: &mut WorldFiles,
+    diagnostics: impl IntoIterator<Item = &'a SourceDiagnostic>,
+) -> CodespanResult<()> {
+    for diagnostic in diagnostics {
+        let value = json_diagnostic(files, diagnostic);
+        serde_json::to_writer(&mut *dest, &value).map_err(io::Error::other)?;
+        writeln!(dest)?;
+    }
+
+    Ok(())
+}
+
+/// Resolves the spans of a diagnostic for JSON output.
+fn json_diagnostic<'a>(
+    files: &mut WorldFiles,
+    diagnostic: &'a SourceDiagnostic,
+) -> JsonDiagnostic<'a> {
+    JsonDiagnostic {
+        severity: severity_name(diagnostic.severity),
+        message: &diagnostic.message,
+        span: files.locate(diagnostic.span),
+        hints: diagnostic
+            .hints
+            .iter()
+            .map(|hint| JsonSpanned {
+                message: hint.v.to_string(),
+                span: files.locate(hint.span),
+            })
+            .collect(),
+        trace: diagnostic
+            .trace
+            .iter()
+            .map(|point| JsonSpanned {
+                message: point.v.to_string(),
+                span: files.locate(point.span),
+            })
+            .collect(),
+    }
+}
+
+/// Emits diagnostics as a SARIF log.
+fn emit_sarif<'a>(
+    dest: &mut dyn WriteColor,
+    files: &mut WorldFiles,
+    diagnostics: impl IntoIterator<Item = &'a SourceDiagnostic>,
+) -> CodespanResult<()> {
+    let mut results = vec![];
+    for diagnostic in diagnostics {
+        // SARIF has no notion of hints, so unlocated ones become part of the
+        // message and located ones become related locations.
+        let mut message = diagnostic.message.to_string();
+        let mut related = vec![];
+        for hint in &diagnostic.hints {
+            match files.locate(hint.span) {
+                Some(location) => related.push(json!({
+                    "id": related.len(),
+                    "physicalLocation": sarif_location(&location),
+                    "message": { "text": hint.v },
+                })),
+                None => {
+                    message.push_str("\nhint: ");
+                    message.push_str(&hint.v);
+                }
+            }
+        }
+
+        let frames: Vec<_> = diagnostic
+            .trace
+            .iter()
+            .filter_map(|point| {
+                let location = files.locate(point.span)?;
+                Some(json!({
+                    "location": {
+                        "physicalLocation": sarif_location(&location),
+                        "message": { "text": point.v.to_string() },
+                    },
+                }))
+            })
+            .collect();
+
+        let mut result = json!({
+            "level": severity_name(diagnostic.severity),
+            "message": { "text": message },
+            "locations": files
+                .locate(diagnostic.span)
+                .map(|location| json!({ "physicalLocation": sarif_location(&location) }))
+                .into_iter()
+                .collect::<Vec<_>>(),
+        });
+
+        if !related.is_empty() {
+            result["relatedLocations"] = related.into();
+        }
+
+        if !frames.is_empty() {
+            result["stacks"] = json!([{ "frames": frames }]);
+        }
+
+        results.push(result);
+    }
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "typst",
+                    "informationUri": "https://typst.app",
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    });
+
+    serde_json::to_writer_pretty(&mut *dest, &log).map_err(io::Error::other)?;
+    writeln!(dest)?;
+    Ok(())
+}
+
+/// The name of a severity in machine-readable output.
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+/// Converts a location into a SARIF physical location.
+fn sarif_location(location: &JsonLocation) -> serde_json::Value {
+    json!({
+        "artifactLocation": { "uri": location.path.replace('\\', "/") },
+        "region": {
+            "startLine": location.start.line,
+            "startColumn": location.start.column,
+            "endLine": location.end.line,
+            "endColumn": location.end.column,
+        },
+    })
+}
+
+/// A diagnostic in the JSON output.
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    severity: &'static str,
+    message: &'a str,
+    span: Option<JsonLocation>,
+    hints: Vec<JsonSpanned>,
+    trace: Vec<JsonSpanned>,
+}
+
+/// A hint or tracepoint in the JSON output.
+#[derive(Serialize)]
+struct JsonSpanned {
+    message: String,
+    span: Option<JsonLocation>,
+}
+
+/// A resolved span in the JSON output.
+#[derive(Serialize)]
+struct JsonLocation {
+    path: String,
+    range: Range<usize>,
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+/// A one-based line and column in the JSON output.
+#[derive(Serialize)]
+struct JsonPosition {
+    line: usize,
+    column: usize,
+}
+
 /// Provides file contents and metadata to `codespan-reporting`.
 struct WorldFiles<'a> {
     world: &'a dyn DiagnosticWorld,
@@ -167,6 +380,24 @@ impl WorldFiles<'_> {
         }
     }
 
+    /// Resolve a span to a path, a byte range, and line / column positions.
+    fn locate(&mut self, span: impl Into<DiagSpan>) -> Option<JsonLocation> {
+        let span = span.into();
+        let id = span.id()?;
+        let range = self.range(span)?;
+        let lines = self.lines(id).ok()?;
+        let position = |byte| {
+            let (line, column) = lines.byte_to_line_column(byte)?;
+            Some(JsonPosition { line: line + 1, column: column + 1 })
+        };
+        Some(JsonLocation {
+            path: self.world.name(id),
+            start: position(range.start)?,
+            end: position(range.end)?,
+            range,
+        })
+    }
+
     /// Lookup line metadata for a file by id. If a source file was remembered,
     /// it will be used. Otherwise, we load as a file as compute line metadata.
     fn lines(&self, id: FileId) -> CodespanResult<Lines<String>> {
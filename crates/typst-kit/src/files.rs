@@ -36,6 +36,8 @@ use {crate::packages::SystemPackages, typst_syntax::VirtualRoot};
 pub struct FileStore<L> {
     loader: L,
     slots: Mutex<FxHashMap<FileId, FileSlot>>,
+    #[cfg(feature = "disk-cache")]
+    cache: Option<crate::cache::DiskCache>,
 }
 
 impl<L> FileStore<L>
@@ -44,7 +46,22 @@ where
 {
     /// Creates a new file store that loads file data via the provided `loader`.
     pub fn new(loader: L) -> Self {
-        Self { loader, slots: Mutex::new(FxHashMap::default()) }
+        Self {
+            loader,
+            slots: Mutex::new(FxHashMap::default()),
+            #[cfg(feature = "disk-cache")]
+            cache: None,
+        }
+    }
+
+    /// Reuses the syntax trees of package sources from a persistent cache.
+    ///
+    /// Project sources are not cached because they typically change between
+    /// compilations and are reparsed incrementally anyway.
+    #[cfg(feature = "disk-cache")]
+    pub fn with_cache(mut self, cache: crate::cache::DiskCache) -> Self {
+        self.cache = Some(cache);
+        self
     }
 
     /// Returns a reference to the underlying loader.
@@ -67,7 +84,9 @@ where
     /// Can directly be used to implement
     /// [`World::source`](typst_library::World::source).
     pub fn source(&self, id: FileId) -> FileResult<Source> {
-        self.slot(id, |slot| slot.source(&self.loader, id))
+        self.slot(id, |slot| {
+            slot.source(&self.loader, id, &|id, text| self.create_source(id, text))
+        })
     }
 
     /// Retrieves the given file id as a raw file.
@@ -115,6 +134,18 @@ where
         }
     }
 
+    /// Creates a source file from scratch.
+    fn create_source(&self, id: FileId, text: String) -> Source {
+        #[cfg(feature = "disk-cache")]
+        if let Some(cache) = &self.cache
+            && matches!(id.root(), typst_syntax::VirtualRoot::Package(_))
+        {
+            return cache.source(id, text);
+        }
+
+        Source::new(id, text)
+    }
+
     /// Access the canonical slot for the given file id.
     fn slot<F, T>(&self, id: FileId, f: F) -> FileResult<T>
     where
@@ -186,8 +217,14 @@ impl FileSlot {
         }
     }
 
-    /// Retrieves the source for this slot.
-    fn source(&mut self, loader: &impl FileLoader, id: FileId) -> FileResult<Source> {
+    /// Retrieves the source for this slot, using `create` to create a source
+    /// from scratch.
+    fn source(
+        &mut self,
+        loader: &impl FileLoader,
+        id: FileId,
+        create: &dyn Fn(FileId, String) -> Source,
+    ) -> FileResult<Source> {
         // When we already have a source or error, this returns. Otherwise, it
         // loads or extracts the bytes and a potential stale source file.
         let (bytes, stale) = match self {
@@ -220,13 +257,13 @@ impl FileSlot {
         } else if let Some(rest) = without_bom {
             // If we had a BOM, we can't reuse the bytes for a string, so we
             // just create a source with a cloned string.
-            (str::from_utf8(rest).map(|text| Source::new(id, text.into())), bytes)
+            (str::from_utf8(rest).map(|text| create(id, text.into())), bytes)
         } else {
             // If we had no BOM, we attempt to reuse an existing `String` or
             // `Vec<u8>` within the `Bytes`, backing the `Bytes` with the
             // resulting `Source` instead. This way, we can transition from
             // a vector-backed file to a source without reallocating.
-            match bytes.into_string().map(|text| Source::new(id, text)) {
+            match bytes.into_string().map(|text| create(id, text)) {
                 Ok(source) => (Ok(source.clone()), Bytes::from_string(source)),
                 Err(err) => (Err(err.error), err.bytes),
             }
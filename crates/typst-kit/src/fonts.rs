@@ -147,12 +147,21 @@ pub fn embedded() -> impl Iterator<Item = (Font, FontInfo)> {
 #[cfg(feature = "scan-fonts")]
 pub fn system() -> impl Iterator<Item = (FontPath, FontInfo)> {
     let _scope = typst_timing::TimingScope::new("scan system fonts");
-    with_db(|db| {
-        db.load_system_fonts();
+    with_db(load_system_fonts, |_, _, parse| parse())
+}
 
-        // Add Adobe Fonts on Windows and macOS.
-        #[cfg(any(target_os = "windows", target_os = "macos"))]
-        load_adobe_fonts(db);
+/// Discovers system fonts, reusing font metadata from a persistent cache.
+///
+/// Behaves like [`system`], but the parsed [`FontInfo`] of each font is
+/// stored in the cache, keyed by the font file's path, size, and modification
+/// time. Later scans don't need to read unchanged files.
+#[cfg(all(feature = "scan-fonts", feature = "disk-cache"))]
+pub fn system_cached(
+    cache: &crate::cache::DiskCache,
+) -> impl Iterator<Item = (FontPath, FontInfo)> {
+    let _scope = typst_timing::TimingScope::new("scan system fonts");
+    with_db(load_system_fonts, |path, index, parse| {
+        cached_info(cache, path, index, parse)
     })
 }
 
@@ -162,13 +171,69 @@ pub fn system() -> impl Iterator<Item = (FontPath, FontInfo)> {
 #[cfg(feature = "scan-fonts")]
 pub fn scan(path: &std::path::Path) -> impl Iterator<Item = (FontPath, FontInfo)> {
     let _scope = typst_timing::TimingScope::new("scan system fonts");
-    with_db(move |db| db.load_fonts_dir(path))
+    with_db(move |db| db.load_fonts_dir(path), |_, _, parse| parse())
+}
+
+/// Scans for fonts in a directory, reusing font metadata from a persistent
+/// cache.
+///
+/// Behaves like [`scan`], but caches font metadata like [`system_cached`].
+#[cfg(all(feature = "scan-fonts", feature = "disk-cache"))]
+pub fn scan_cached(
+    path: &std::path::Path,
+    cache: &crate::cache::DiskCache,
+) -> impl Iterator<Item = (FontPath, FontInfo)> {
+    let _scope = typst_timing::TimingScope::new("scan system fonts");
+    with_db(
+        move |db| db.load_fonts_dir(path),
+        |path, index, parse| cached_info(cache, path, index, parse),
+    )
 }
 
-/// Discovers fonts via `fontdb`.
+/// Loads the system fonts into the database.
+#[cfg(feature = "scan-fonts")]
+fn load_system_fonts(db: &mut fontdb::Database) {
+    db.load_system_fonts();
+
+    // Add Adobe Fonts on Windows and macOS.
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    load_adobe_fonts(db);
+}
+
+/// Loads font metadata from the cache or parses it with `parse`.
+#[cfg(all(feature = "scan-fonts", feature = "disk-cache"))]
+fn cached_info(
+    cache: &crate::cache::DiskCache,
+    path: &std::path::Path,
+    index: u32,
+    parse: &dyn Fn() -> Option<FontInfo>,
+) -> Option<FontInfo> {
+    // Without a modification time, we can't tell whether the file changed.
+    let Some((len, modified)) = std::fs::metadata(path)
+        .ok()
+        .and_then(|meta| Some((meta.len(), meta.modified().ok()?)))
+    else {
+        return parse();
+    };
+
+    let key = typst_utils::hash128(&(path, index, len, modified));
+    if let Some(info) = cache.load_value::<Option<FontInfo>>("font-info", key) {
+        return info;
+    }
+
+    // Also cache failures, so that files that aren't fonts are skipped.
+    let info = parse();
+    cache.store_value("font-info", key, &info);
+    info
+}
+
+/// Discovers fonts via `fontdb`, using `info` to obtain the metadata of the
+/// face with the given path and index. It can fall back to `parse`, which
+/// extracts the metadata from the face's data.
 #[cfg(feature = "scan-fonts")]
 fn with_db(
     f: impl FnOnce(&mut fontdb::Database),
+    info: impl Fn(&std::path::Path, u32, &dyn Fn() -> Option<FontInfo>) -> Option<FontInfo>,
 ) -> impl Iterator<Item = (FontPath, FontInfo)> {
     let mut db = fontdb::Database::new();
     f(&mut db);
@@ -181,9 +246,11 @@ fn with_db(
                 fontdb::Source::Binary(_) => return None,
             };
 
-            let info = db
-                .with_face_data(face.id, FontInfo::new)
-                .expect("database must contain this font")?;
+            let parse = || {
+                db.with_face_data(face.id, FontInfo::new)
+                    .expect("database must contain this font")
+            };
+            let info = info(path, face.index, &parse)?;
 
             let path = FontPath { path: path.clone(), index: face.index };
 
@@ -19,6 +19,8 @@
 //!   [`packages::SystemPackages`].
 //! - `universe-packages`: Enables loading of packages from Typst Universe via
 //!   [`packages::UniversePackages`].
+//! - `disk-cache`: Enables persisting intermediate results across processes
+//!   via [`cache::DiskCache`].
 //! - `emit-diagnostics`: Enables emitting terminal-style diagnostics via
 //! - `datetime`: Enables obtaining the current date via [`datetime::Time::today`].
 //!   [`diagnostics::emit`].
@@ -39,6 +41,7 @@
         feature = "system-files",
         feature = "system-packages",
         feature = "universe-packages",
+        feature = "disk-cache",
         feature = "emit-diagnostics",
         feature = "datetime",
         feature = "system-downloader",
@@ -49,6 +52,7 @@
     allow(rustdoc::broken_intra_doc_links)
 )]
 
+pub mod cache;
 pub mod datetime;
 pub mod diagnostics;
 pub mod downloader;
@@ -0,0 +1,26 @@
+use typst_library::diag::SourceResult;
+use typst_library::engine::Engine;
+use typst_library::foundations::{Packed, StyleChain};
+use typst_library::introspection::Locator;
+use typst_library::layout::{Frame, FrameItem, InlineItem, Point, Size};
+use typst_library::pdf::AnnotationElem;
+use typst_library::text::TextElem;
+
+/// Lay out an annotation without a body.
+///
+/// The annotation itself takes up no space. Its interactive area is a square
+/// with the size of the font that sits on the baseline.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_annotation(
+    elem: &Packed<AnnotationElem>,
+    _: &mut Engine,
+    _: Locator,
+    styles: StyleChain,
+    _: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    let size = styles.resolve(TextElem::size);
+    let location = elem.location().unwrap();
+    let mut frame = Frame::soft(Size::zero());
+    frame.push(Point::with_y(-size), FrameItem::Annotation(location, Size::splat(size)));
+    Ok(vec![InlineItem::Frame(frame)])
+}
@@ -86,6 +86,9 @@ pub struct Page {
     /// The bleed amount to be added on each side of the page. The bleed is not
     /// included in frame.
     pub bleed: Sides<Abs>,
+    /// The area for printer's marks to be added around the bleed. Like the
+    /// bleed, it is not included in the frame.
+    pub marks: Sides<Abs>,
     /// How the page is filled.
     ///
     /// - When `None`, the background is transparent.
@@ -105,6 +108,12 @@ pub struct Page {
 }
 
 impl Page {
+    /// How far the printed sheet extends beyond the frame on each side, that
+    /// is, the bleed plus the area for printer's marks.
+    pub fn outset(&self) -> Sides<Abs> {
+        self.bleed.zip(self.marks).map(|(bleed, marks)| bleed + marks)
+    }
+
     /// Get the configured background or `None` if it is `Auto`.
     ///
     /// This is used in PDF export.
@@ -14,7 +14,7 @@ use typst_library::introspection::{
 use typst_library::layout::{
     Abs, AlignElem, Alignment, Axes, BlockElem, ColbreakElem, FixedAlignment, FlushElem,
     Fr, Fragment, Frame, FrameParent, Inherit, PagebreakElem, PlaceElem, PlacementScope,
-    Ratio, Region, Regions, Rel, Size, Sizing, Spacing, VElem,
+    PlacementWrap, Ratio, Region, Regions, Rel, Size, Sizing, Spacing, VElem,
 };
 use typst_library::model::ParElem;
 use typst_library::routines::Pair;
@@ -23,7 +23,7 @@ use typst_library::{Library, World};
 use typst_utils::{LazyHash, Protected, SliceExt};
 
 use super::{FlowMode, layout_multi_block, layout_single_block};
-use crate::inline::ParSituation;
+use crate::inline::{Exclusions, ParSituation};
 use crate::modifiers::layout_and_modify;
 
 /// Collects all elements of the flow into prepared children. These are much
@@ -160,14 +160,17 @@ impl<'a> Collector<'a, '_, '_> {
         elem: &'a Packed<ParElem>,
         styles: StyleChain<'a>,
     ) -> SourceResult<()> {
+        let locator = self.locator.next(&elem.span());
+        let situation = self.par_situation;
         let lines = crate::inline::layout_par(
             elem,
             self.engine,
-            self.locator.next(&elem.span()),
+            locator.relayout(),
             styles,
             self.base,
             self.expand,
-            self.par_situation,
+            situation,
+            &Exclusions::default(),
         )?
         .into_frames();
 
@@ -176,6 +179,29 @@ impl<'a> Collector<'a, '_, '_> {
 
         self.output.push(Child::Rel(spacing.into(), 4));
 
+        // Mark the start of the lines so that the paragraph can be laid out
+        // again if it needs to flow around wrapped elements.
+        if !lines.is_empty() {
+            // Empty lines don't tell us anything about the line height.
+            let line_height = lines
+                .iter()
+                .map(Frame::height)
+                .filter(|height| *height > Abs::zero())
+                .min()
+                .unwrap_or_default();
+            self.output.push(Child::Par(self.boxed(ParChild {
+                leading,
+                len: 2 * lines.len() - 1,
+                line_height,
+                elem,
+                styles,
+                locator,
+                situation,
+                base: self.base,
+                expand: self.expand,
+            })));
+        }
+
         self.lines(lines, leading, styles);
 
         self.output.push(Child::Rel(spacing.into(), 4));
@@ -186,46 +212,12 @@ impl<'a> Collector<'a, '_, '_> {
 
     /// Collect laid-out lines.
     fn lines(&mut self, lines: Vec<Frame>, leading: Abs, styles: StyleChain<'a>) {
-        let align = styles.resolve(AlignElem::alignment);
-        let costs = styles.get(TextElem::costs);
-
-        // Determine whether to prevent widow and orphans.
-        let len = lines.len();
-        let prevent_orphans =
-            costs.orphan() > Ratio::zero() && len >= 2 && !lines[1].is_empty();
-        let prevent_widows =
-            costs.widow() > Ratio::zero() && len >= 2 && !lines[len - 2].is_empty();
-        let prevent_all = len == 3 && prevent_orphans && prevent_widows;
-
-        // Store the heights of lines at the edges because we'll potentially
-        // need these later when `lines` is already moved.
-        let height_at = |i| lines.get(i).map(Frame::height).unwrap_or_default();
-        let front_1 = height_at(0);
-        let front_2 = height_at(1);
-        let back_2 = height_at(len.saturating_sub(2));
-        let back_1 = height_at(len.saturating_sub(1));
-
-        for (i, frame) in lines.into_iter().enumerate() {
+        for (i, line) in line_children(lines, leading, styles).into_iter().enumerate() {
             if i > 0 {
                 self.output.push(Child::Rel(leading.into(), 5));
             }
 
-            // To prevent widows and orphans, we require enough space for
-            // - all lines if it's just three
-            // - the first two lines if we're at the first line
-            // - the last two lines if we're at the second to last line
-            let need = if prevent_all && i == 0 {
-                front_1 + leading + front_2 + leading + back_1
-            } else if prevent_orphans && i == 0 {
-                front_1 + leading + front_2
-            } else if prevent_widows && i >= 2 && i + 2 == len {
-                back_2 + leading + back_1
-            } else {
-                frame.height()
-            };
-
-            self.output
-                .push(Child::Line(self.boxed(LineChild { frame, align, need })));
+            self.output.push(Child::Line(self.boxed(line)));
         }
     }
 
@@ -291,6 +283,23 @@ impl<'a> Collector<'a, '_, '_> {
         let align_y = alignment.map(|align| align.y().map(|y| y.resolve(styles)));
         let scope = elem.scope.get(styles);
         let float = elem.float.get(styles);
+        let wrap = elem.wrap.get(styles);
+
+        if wrap.is_some() {
+            if float {
+                bail!(
+                    elem.span(),
+                    "wrapping is only available for non-floating placement";
+                    hint: "you can disable floating placement with `place(float: false, ..)`";
+                );
+            } else if align_y != Smart::Custom(None) {
+                bail!(
+                    elem.span(),
+                    "wrapped placement cannot be aligned vertically";
+                    hint: "the element is placed at its position in the flow";
+                );
+            }
+        }
 
         match (float, align_y) {
             (true, Smart::Custom(None | Some(FixedAlignment::Center))) => bail!(
@@ -321,6 +330,7 @@ impl<'a> Collector<'a, '_, '_> {
             align_y,
             scope,
             float,
+            wrap,
             clearance,
             delta,
             elem,
@@ -351,6 +361,8 @@ pub enum Child<'a> {
     Rel(Rel<Abs>, u8),
     /// Fractional spacing with a specific weakness level.
     Fr(Fr, u8),
+    /// The start of a paragraph's lines.
+    Par(BumpBox<'a, ParChild<'a>>),
     /// An already layouted line of a paragraph.
     Line(BumpBox<'a, LineChild>),
     /// An unbreakable block.
@@ -365,8 +377,106 @@ pub enum Child<'a> {
     Break(bool),
 }
 
-/// A child that encapsulates a layouted line of a paragraph.
+/// A child that marks the start of a paragraph's lines. It allows laying out
+/// the paragraph again so that its lines flow around wrapped elements.
 #[derive(Debug)]
+pub struct ParChild<'a> {
+    pub leading: Abs,
+    /// The number of following children that make up the paragraph's lines,
+    /// including the leading between them.
+    pub len: usize,
+    /// The height of the paragraph's shortest line.
+    pub line_height: Abs,
+    elem: &'a Packed<ParElem>,
+    styles: StyleChain<'a>,
+    locator: Locator<'a>,
+    situation: ParSituation,
+    base: Size,
+    expand: bool,
+}
+
+impl ParChild<'_> {
+    /// Lays out the paragraph's lines such that they flow around the
+    /// exclusions.
+    pub fn layout(
+        &self,
+        engine: &mut Engine,
+        exclusions: &Exclusions,
+    ) -> SourceResult<Vec<LineChild>> {
+        let lines = crate::inline::layout_par(
+            self.elem,
+            engine,
+            self.locator.relayout(),
+            self.styles,
+            self.base,
+            self.expand,
+            self.situation,
+            exclusions,
+        )?
+        .into_frames();
+        Ok(line_children(lines, self.leading, self.styles))
+    }
+}
+
+/// The remaining lines of a paragraph that was laid out around wrapped
+/// elements.
+#[derive(Debug, Clone)]
+pub struct ParSpill {
+    pub lines: std::vec::IntoIter<LineChild>,
+    pub leading: Abs,
+    /// Whether the first line was already processed.
+    pub started: bool,
+}
+
+/// Turns the lines of a paragraph into line children.
+///
+/// Each line needs enough space for the lines grouped with it to prevent
+/// widows and orphans.
+fn line_children(lines: Vec<Frame>, leading: Abs, styles: StyleChain) -> Vec<LineChild> {
+    let align = styles.resolve(AlignElem::alignment);
+    let costs = styles.get(TextElem::costs);
+
+    // Determine whether to prevent widow and orphans.
+    let len = lines.len();
+    let prevent_orphans =
+        costs.orphan() > Ratio::zero() && len >= 2 && !lines[1].is_empty();
+    let prevent_widows =
+        costs.widow() > Ratio::zero() && len >= 2 && !lines[len - 2].is_empty();
+    let prevent_all = len == 3 && prevent_orphans && prevent_widows;
+
+    // Store the heights of lines at the edges because we'll potentially
+    // need these later when `lines` is already moved.
+    let height_at = |i| lines.get(i).map(Frame::height).unwrap_or_default();
+    let front_1 = height_at(0);
+    let front_2 = height_at(1);
+    let back_2 = height_at(len.saturating_sub(2));
+    let back_1 = height_at(len.saturating_sub(1));
+
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            // To prevent widows and orphans, we require enough space for
+            // - all lines if it's just three
+            // - the first two lines if we're at the first line
+            // - the last two lines if we're at the second to last line
+            let need = if prevent_all && i == 0 {
+                front_1 + leading + front_2 + leading + back_1
+            } else if prevent_orphans && i == 0 {
+                front_1 + leading + front_2
+            } else if prevent_widows && i >= 2 && i + 2 == len {
+                back_2 + leading + back_1
+            } else {
+                frame.height()
+            };
+
+            LineChild { frame, align, need }
+        })
+        .collect()
+}
+
+/// A child that encapsulates a layouted line of a paragraph.
+#[derive(Debug, Clone)]
 pub struct LineChild {
     pub frame: Frame,
     pub align: Axes<FixedAlignment>,
@@ -624,6 +734,7 @@ pub struct PlacedChild<'a> {
     pub align_y: Smart<Option<FixedAlignment>>,
     pub scope: PlacementScope,
     pub float: bool,
+    pub wrap: Option<PlacementWrap>,
     pub clearance: Abs,
     pub delta: Axes<Rel<Abs>>,
     elem: &'a Packed<PlaceElem>,
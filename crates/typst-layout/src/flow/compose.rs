@@ -13,6 +13,7 @@ use typst_library::layout::{
 };
 use typst_library::model::{
     FootnoteElem, FootnoteEntry, LineNumberingScope, Numbering, ParLineMarker,
+    SidenoteElem, SidenoteEntry,
 };
 use typst_library::pdf::ArtifactKind;
 use typst_syntax::Span;
@@ -21,6 +22,11 @@ use typst_utils::{NonZeroExt, Numeric};
 use super::{
     Config, FlowMode, FlowResult, LineNumberConfig, PlacedChild, Stop, Work, distribute,
 };
+use crate::inline::Exclusion;
+
+/// How close (in points) the search for a column balancing height gets to the
+/// full column height before it gives up on balancing.
+const BALANCING_PRECISION: f64 = 1.0;
 
 /// Composes the contents of a single page/region. A region can have multiple
 /// columns/subregions.
@@ -32,13 +38,16 @@ use super::{
 ///
 /// To lay out the in-flow contents of individual subregions, the composer
 /// invokes [distribution](distribute()).
+///
+/// For the root flow of a page, also returns the region's sidenotes, laid out
+/// into a frame for the outer page margin.
 pub fn compose(
     engine: &mut Engine,
     work: &mut Work,
     config: &Config,
     locator: Locator,
     regions: Regions,
-) -> SourceResult<Frame> {
+) -> SourceResult<(Frame, Option<Frame>)> {
     Composer {
         engine,
         config,
@@ -47,6 +56,7 @@ pub fn compose(
         page_insertions: Insertions::default(),
         column_insertions: Insertions::default(),
         column_balancing_height: None,
+        exclusions: vec![],
         work,
         footnote_spill: None,
         footnote_queue: vec![],
@@ -71,6 +81,10 @@ pub struct Composer<'a, 'b, 'x, 'y> {
     page_insertions: Insertions<'a, 'b>,
     column_insertions: Insertions<'a, 'b>,
     column_balancing_height: Option<Abs>,
+    /// The areas occupied by wrapped elements in the current column, which
+    /// paragraphs flow around. Vertical positions are relative to the top of
+    /// the column's in-flow content.
+    pub exclusions: Vec<Exclusion>,
     // These are here because they have to survive relayout (we could lose the
     // footnotes otherwise). For floats, we revisit them anyway, so it's okay to
     // use `work.floats` directly. This is not super clean; probably there's a
@@ -81,7 +95,11 @@ pub struct Composer<'a, 'b, 'x, 'y> {
 
 impl<'a, 'b> Composer<'a, 'b, '_, '_> {
     /// Lay out a container/page region, including container/page insertions.
-    fn page(mut self, locator: Locator, regions: Regions) -> SourceResult<Frame> {
+    fn page(
+        mut self,
+        locator: Locator,
+        regions: Regions,
+    ) -> SourceResult<(Frame, Option<Frame>)> {
         // This loop can restart region layout when requested to do so by a
         // `Stop`. This happens when there is a parent-scoped float.
         let checkpoint = self.work.clone();
@@ -103,7 +121,10 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
         };
         drop(checkpoint);
 
-        Ok(self.page_insertions.finalize(self.work, self.config, output, None))
+        let insertions = std::mem::take(&mut self.page_insertions);
+        let output = insertions.finalize(self.work, self.config, output, None);
+        let sidenotes = self.sidenotes(&output)?;
+        Ok((output, sidenotes))
     }
 
     /// Lay out the inner contents of a container/page.
@@ -151,10 +172,12 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
             }
 
             let width = frame.width();
-            let x = if self.config.columns.dir == Dir::LTR {
-                offset
-            } else {
-                regions.size.x - offset - width
+            // Like right-to-left text, vertical text has its columns from
+            // right to left.
+            let x = match self.config.columns.dir {
+                Dir::LTR => offset,
+                Dir::RTL | Dir::TTB => regions.size.x - offset - width,
+                Dir::BTT => unreachable!("text direction cannot be bottom-to-top"),
             };
             offset += width + self.config.columns.gutter;
 
@@ -170,11 +193,32 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
             inner.next();
         }
 
-        // Column balancing with re-layout
-        if self.config.columns.balanced && self.work.done() {
-            let height = total_used_height / self.config.columns.count as f64;
-            if self.column_balancing_height.is_none_or(|h| h < height) {
-                self.column_balancing_height = Some(height);
+        // Column balancing with re-layout. The balancing height only ever
+        // grows, so this terminates.
+        if self.config.columns.balanced {
+            if self.work.done() {
+                // Everything fit, so try to make the columns as high as the
+                // average column.
+                let height = total_used_height / self.config.columns.count as f64;
+                if self.column_balancing_height.is_none_or(|h| h < height) {
+                    self.column_balancing_height = Some(height);
+                    return Err(Stop::Relayout(PlacementScope::Parent));
+                }
+            } else if let Some(height) = self.column_balancing_height
+                && height < column_height
+                && column_height.is_finite()
+            {
+                // Everything fit without balancing, but the columns broke too
+                // early with this height, for example due to unbreakable
+                // blocks or widow and orphan prevention. Search for a height
+                // between this one and the full column height that works. At
+                // worst, we end up with the full height, which is equivalent
+                // to not balancing.
+                let mut next = (height + column_height) / 2.0;
+                if column_height - next < Abs::pt(BALANCING_PRECISION) {
+                    next = column_height;
+                }
+                self.column_balancing_height = Some(next);
                 return Err(Stop::Relayout(PlacementScope::Parent));
             }
         }
@@ -200,6 +244,10 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
         // `Stop`. This happens when there is a column-scoped float.
         let checkpoint = self.work.clone();
         let (inner, used_height) = loop {
+            // Wrapped elements are laid out again along with the in-flow
+            // content.
+            self.exclusions.clear();
+
             // Shrink the available space by the space used by column
             // insertions.
             let mut pod = regions;
@@ -603,6 +651,72 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
         Ok(())
     }
 
+    /// Lays out the sidenotes whose markers are in the region's `frame` into a
+    /// frame for the outer page margin, if this is the root flow of a page.
+    ///
+    /// Each note is aligned with the line of its marker, but moved down if it
+    /// would overlap the previous note. Notes that don't fit into the margin
+    /// anymore are queued for the next region.
+    fn sidenotes(&mut self, frame: &Frame) -> SourceResult<Option<Frame>> {
+        let Some(config) = &self.config.sidenote else { return Ok(None) };
+
+        // Notes from previous regions come first. The others are sorted by
+        // their position since notes from multiple columns end up in the same
+        // margin.
+        let mut found = find_in_frame::<SidenoteElem>(frame);
+        found.sort_by_key(|&(y, _)| y);
+        let notes: Vec<_> = std::mem::take(&mut self.work.sidenotes)
+            .into_iter()
+            .map(|note| (Abs::zero(), note))
+            .chain(found)
+            .collect();
+        if notes.is_empty() {
+            return Ok(None);
+        }
+
+        let width = (config.width - config.clearance * 2.0).max(Abs::zero());
+        let mut output = Frame::soft(Size::new(config.width, frame.height()));
+        let mut prev_bottom = None;
+
+        for (y, note) in notes {
+            // Once a note is queued, the following ones are as well to keep
+            // them in order.
+            if !self.work.sidenotes.is_empty() {
+                self.work.sidenotes.push(note);
+                continue;
+            }
+
+            let entry = layout_sidenote(self.engine, self.config, &note, width)?;
+
+            // Align the baseline of the note's first line with the marker,
+            // which sits on the baseline of its line.
+            let mut top = y;
+            if entry.has_baseline() {
+                top -= entry.baseline();
+            }
+            if let Some(bottom) = prev_bottom {
+                top.set_max(bottom + config.gap);
+            }
+            top.set_max(Abs::zero());
+
+            // If the note runs off the page, queue it for the next region. The
+            // first note can move up instead since the margin above it is
+            // free.
+            if top + entry.height() > output.height() {
+                if prev_bottom.is_some() {
+                    self.work.sidenotes.push(note);
+                    continue;
+                }
+                top = (output.height() - entry.height()).max(Abs::zero());
+            }
+
+            prev_bottom = Some(top + entry.height());
+            output.push_frame(Point::new(config.clearance, top), entry);
+        }
+
+        Ok(Some(output))
+    }
+
     /// Checks whether an insertion was already processed and doesn't need to be
     /// handled again.
     fn skipped(&self, loc: Location) -> bool {
@@ -660,6 +774,32 @@ fn layout_footnote(
     })
 }
 
+/// Lay out a sidenote.
+fn layout_sidenote(
+    engine: &mut Engine,
+    config: &Config,
+    elem: &Packed<SidenoteElem>,
+    width: Abs,
+) -> SourceResult<Frame> {
+    let loc = elem.location().unwrap();
+    crate::layout_frame(
+        engine,
+        &SidenoteEntry::new(elem.clone())
+            .pack()
+            .spanned(elem.span())
+            // We attach a well-known derived location to the entry so that the
+            // note can link to this entry without first querying for it.
+            .located(loc.variant(1)),
+        Locator::synthesize(loc),
+        config.shared,
+        Region::new(Size::new(width, Abs::inf()), Axes::new(true, false)),
+    )
+    .map(|mut frame| {
+        frame.set_parent(FrameParent::new(loc, Inherit::No));
+        frame
+    })
+}
+
 /// An additive list of insertions.
 #[derive(Default)]
 struct Insertions<'a, 'b> {
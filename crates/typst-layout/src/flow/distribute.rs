@@ -1,13 +1,15 @@
 use typst_library::introspection::Tag;
 use typst_library::layout::{
-    Abs, Axes, FixedAlignment, Fr, Frame, FrameItem, Point, Region, Regions, Rel, Size,
+    Abs, Axes, FixedAlignment, Fr, Frame, FrameItem, PlacementWrap, Point, Region,
+    Regions, Rel, Size,
 };
 use typst_utils::Numeric;
 
 use super::{
-    Child, Composer, FlowResult, LineChild, MultiChild, MultiSpill, PlacedChild,
-    SingleChild, Stop, Work,
+    Child, Composer, FlowResult, LineChild, MultiChild, MultiSpill, ParChild, ParSpill,
+    PlacedChild, SingleChild, Stop, Work,
 };
+use crate::inline::{Exclusion, Exclusions};
 
 /// Distributes as many children as fit from `composer.work` into the first
 /// region and returns the resulting frame and the height actually used
@@ -80,6 +82,7 @@ struct DistributionSnapshot<'a, 'b> {
     work: Work<'a, 'b>,
     items: usize,
     used: Size,
+    exclusions: usize,
 }
 
 /// A laid out item in a distribution.
@@ -105,10 +108,15 @@ impl Item<'_, '_> {
             Self::Frame(frame, _) => {
                 frame.size().is_zero()
                     && frame.items().all(|(_, item)| {
-                        matches!(item, FrameItem::Link(_, _) | FrameItem::Tag(_))
+                        matches!(
+                            item,
+                            FrameItem::Link(_, _)
+                                | FrameItem::Annotation(_, _)
+                                | FrameItem::Tag(_)
+                        )
                     })
             }
-            Self::Placed(_, placed) => !placed.float,
+            Self::Placed(_, placed) => !placed.float && placed.wrap.is_none(),
             _ => false,
         }
     }
@@ -122,11 +130,23 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             self.multi_spill(spill)?;
         }
 
+        // Then, handle the remaining lines of a paragraph that flows around
+        // wrapped elements.
+        if let Some(spill) = self.composer.work.par_spill.take() {
+            self.par_spill(spill)?;
+        }
+
         // If spill are taken care of, process children until no space is left
         // or no children are left.
         while let Some(child) = self.composer.work.head() {
             self.child(child)?;
             self.composer.work.advance();
+
+            // A paragraph that was laid out again to flow around wrapped
+            // elements leaves its lines in a spill.
+            if let Some(spill) = self.composer.work.par_spill.take() {
+                self.par_spill(spill)?;
+            }
         }
 
         Ok(())
@@ -144,6 +164,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             Child::Tag(tag) => self.tag(tag),
             Child::Rel(amount, weakness) => self.rel(*amount, *weakness),
             Child::Fr(fr, weakness) => self.fr(*fr, *weakness),
+            Child::Par(par) => self.par(par)?,
             Child::Line(line) => self.line(line)?,
             Child::Single(single) => self.single(single)?,
             Child::Multi(multi) => self.multi(multi)?,
@@ -299,8 +320,67 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
                 .is_none_or(|target| target.fits(self.used.y))
     }
 
+    /// Processes the start of a paragraph's lines.
+    ///
+    /// If the paragraph is next to wrapped elements, it is laid out again such
+    /// that its lines flow around them. The new lines are then processed from
+    /// a spill while the original lines are skipped.
+    fn par(&mut self, par: &'b ParChild<'a>) -> FlowResult<()> {
+        let y = self.used.y;
+        let areas: Vec<_> = self
+            .composer
+            .exclusions
+            .iter()
+            .filter(|area| area.bottom > y)
+            .map(|area| Exclusion {
+                top: area.top - y,
+                bottom: area.bottom - y,
+                ..*area
+            })
+            .collect();
+
+        if areas.is_empty() {
+            return Ok(());
+        }
+
+        let exclusions = Exclusions {
+            areas,
+            leading: par.leading,
+            line_height: par.line_height,
+        };
+
+        let lines = par.layout(self.composer.engine, &exclusions)?;
+        self.composer.work.skip(par.len);
+        self.composer.work.par_spill = Some(ParSpill {
+            lines: lines.into_iter(),
+            leading: par.leading,
+            started: false,
+        });
+
+        Ok(())
+    }
+
+    /// Processes the lines of a paragraph that flows around wrapped elements.
+    fn par_spill(&mut self, mut spill: ParSpill) -> FlowResult<()> {
+        while let Some(line) = spill.lines.as_slice().first().cloned() {
+            if spill.started {
+                self.rel(spill.leading.into(), 5);
+            }
+
+            if let Err(err) = self.line(&line) {
+                self.composer.work.par_spill = Some(spill);
+                return Err(err);
+            }
+
+            spill.lines.next();
+            spill.started = true;
+        }
+
+        Ok(())
+    }
+
     /// Processes a line of a paragraph.
-    fn line(&mut self, line: &'b LineChild) -> FlowResult<()> {
+    fn line(&mut self, line: &LineChild) -> FlowResult<()> {
         // If the line doesn't fit and a followup region may improve things,
         // finish the region.
         if !self.fits(line.frame.height()) && self.regions.may_progress() {
@@ -326,6 +406,8 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
 
     /// Processes an unbreakable block.
     fn single(&mut self, single: &'b SingleChild<'a>) -> FlowResult<()> {
+        self.clear();
+
         // Lay out the block.
         let frame = single.layout(
             self.composer.engine,
@@ -352,6 +434,8 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
 
     /// Processes a breakable block.
     fn multi(&mut self, multi: &'b MultiChild<'a>) -> FlowResult<()> {
+        self.clear();
+
         let mut pod = self.regions;
 
         // For column balancing, reduce the region size for layout.
@@ -501,6 +585,10 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
                 true,
             )?;
             self.use_height(weak_spacing);
+        } else if let Some(wrap) = placed.wrap
+            && self.regions.base().x.is_finite()
+        {
+            self.wrapped(placed, wrap)?;
         } else {
             let frame = placed.layout(self.composer.engine, self.regions.base())?;
             self.composer
@@ -511,6 +599,60 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         Ok(())
     }
 
+    /// Processes a placed child that text flows around.
+    fn wrapped(
+        &mut self,
+        placed: &'b PlacedChild<'a>,
+        wrap: PlacementWrap,
+    ) -> FlowResult<()> {
+        // Wrapped elements don't overlap each other.
+        self.clear();
+
+        let base = self.regions.base();
+        let frame = placed.layout(self.composer.engine, base)?;
+
+        // If the element doesn't fit and a followup region may improve things,
+        // finish the region.
+        if !self.fits(frame.height()) && self.regions.may_progress() {
+            return Err(Stop::Finish(false));
+        }
+
+        self.composer
+            .footnotes(&self.regions, &frame, frame.height(), false, true)?;
+
+        // Register the area, including the clearance, which the following
+        // paragraphs flow around.
+        let clearance = placed.clearance;
+        let x = placed.align_x.position(base.x - frame.width());
+        self.composer.exclusions.push(Exclusion {
+            top: self.used.y,
+            bottom: self.used.y + frame.height() + clearance,
+            left: x - clearance,
+            right: x + frame.width() + clearance,
+            wrap,
+        });
+
+        self.flush_tags();
+        self.items.push(Item::Placed(frame, placed));
+        Ok(())
+    }
+
+    /// Moves below all wrapped elements that extend past the current position.
+    /// This is used for content that can't flow around them.
+    fn clear(&mut self) {
+        let y = self.used.y;
+        let bottom = self
+            .composer
+            .exclusions
+            .iter()
+            .map(|area| area.bottom)
+            .fold(y, Abs::max);
+        if bottom > y {
+            self.use_height(bottom - y);
+            self.items.push(Item::Abs(bottom - y, 0));
+        }
+    }
+
     /// Processes a float flush.
     fn flush(&mut self) -> FlowResult<()> {
         // If there are still pending floats, finish the region instead of
@@ -559,6 +701,12 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
 
         self.trim_spacing();
 
+        // Wrapped elements take up space even if the content next to them is
+        // shorter.
+        for area in &self.composer.exclusions {
+            self.used.y.set_max(area.bottom);
+        }
+
         let used_height_without_fr = self.used.y;
 
         // Determine the sum of fractionals.
@@ -673,6 +821,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             work: self.composer.work.clone(),
             items: self.items.len(),
             used: self.used,
+            exclusions: self.composer.exclusions.len(),
         }
     }
 
@@ -681,5 +830,6 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         *self.composer.work = snapshot.work;
         self.items.truncate(snapshot.items);
         self.used = snapshot.used;
+        self.composer.exclusions.truncate(snapshot.exclusions);
     }
 }
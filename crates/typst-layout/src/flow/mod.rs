@@ -24,7 +24,9 @@ use typst_library::layout::{
     Abs, ColumnsElem, Dir, Em, Fragment, Frame, PageElem, PlacementScope, Region,
     Regions, Rel, Size,
 };
-use typst_library::model::{FootnoteElem, FootnoteEntry, LineNumberingScope, ParLine};
+use typst_library::model::{
+    FootnoteElem, FootnoteEntry, LineNumberingScope, ParLine, SidenoteElem, SidenoteEntry,
+};
 use typst_library::pdf::ArtifactKind;
 use typst_library::routines::{Arenas, FragmentKind, Pair, RealizationKind};
 use typst_library::text::TextElem;
@@ -33,7 +35,8 @@ use typst_utils::{LazyHash, NonZeroExt, Numeric, Protected};
 
 use self::block::{layout_multi_block, layout_single_block};
 use self::collect::{
-    Child, LineChild, MultiChild, MultiSpill, PlacedChild, SingleChild, collect,
+    Child, LineChild, MultiChild, MultiSpill, ParChild, ParSpill, PlacedChild,
+    SingleChild, collect,
 };
 use self::compose::{Composer, compose};
 use self::distribute::distribute;
@@ -173,7 +176,7 @@ fn layout_fragment_impl(
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum FlowMode {
     /// A root flow with block-level elements. Like `FlowMode::Block`, but can
-    /// additionally host footnotes and line numbers.
+    /// additionally host footnotes, sidenotes, and line numbers.
     Root,
     /// A flow whose children are block-level elements.
     Block,
@@ -196,12 +199,54 @@ pub fn layout_flow<'a>(
     children: &[Pair<'a>],
     locator: &mut SplitLocator<'a>,
     shared: StyleChain<'a>,
-    mut regions: Regions,
+    regions: Regions,
     column: ColumnOptions,
     mode: FlowMode,
 ) -> SourceResult<Fragment> {
+    let finished =
+        layout_flow_impl(engine, children, locator, shared, regions, column, mode, None)?;
+    Ok(Fragment::frames(finished.into_iter().map(|(frame, _)| frame).collect()))
+}
+
+/// Lays out the root flow of a page run.
+///
+/// In addition to the frame for each region, returns the region's sidenotes,
+/// laid out into a frame that fills an outer page margin of the given width.
+pub fn layout_root_flow<'a>(
+    engine: &mut Engine,
+    children: &[Pair<'a>],
+    locator: &mut SplitLocator<'a>,
+    shared: StyleChain<'a>,
+    regions: Regions,
+    column: ColumnOptions,
+    margin: Abs,
+) -> SourceResult<Vec<(Frame, Option<Frame>)>> {
+    layout_flow_impl(
+        engine,
+        children,
+        locator,
+        shared,
+        regions,
+        column,
+        FlowMode::Root,
+        Some(margin),
+    )
+}
+
+/// The shared implementation of [`layout_flow`] and [`layout_root_flow`].
+#[expect(clippy::too_many_arguments)]
+fn layout_flow_impl<'a>(
+    engine: &mut Engine,
+    children: &[Pair<'a>],
+    locator: &mut SplitLocator<'a>,
+    shared: StyleChain<'a>,
+    mut regions: Regions,
+    column: ColumnOptions,
+    mode: FlowMode,
+    margin: Option<Abs>,
+) -> SourceResult<Vec<(Frame, Option<Frame>)>> {
     // Prepare configuration that is shared across the whole flow.
-    let config = configuration(shared, regions, column, mode);
+    let config = configuration(shared, regions, column, mode, margin);
 
     // Collect the elements into pre-processed children. These are much easier
     // to handle than the raw elements.
@@ -221,8 +266,8 @@ pub fn layout_flow<'a>(
 
     // This loop runs once per region produced by the flow layout.
     loop {
-        let frame = compose(engine, &mut work, &config, locator.next(&()), regions)?;
-        finished.push(frame);
+        let output = compose(engine, &mut work, &config, locator.next(&()), regions)?;
+        finished.push(output);
 
         // Terminate the loop when everything is processed, though draining the
         // backlog if necessary.
@@ -233,7 +278,7 @@ pub fn layout_flow<'a>(
         regions.next();
     }
 
-    Ok(Fragment::frames(finished))
+    Ok(finished)
 }
 
 /// Determine the flow's configuration.
@@ -242,6 +287,7 @@ fn configuration<'x>(
     regions: Regions,
     column: ColumnOptions,
     mode: FlowMode,
+    margin: Option<Abs>,
 ) -> Config<'x> {
     Config {
         mode,
@@ -271,6 +317,11 @@ fn configuration<'x>(
             gap: shared.resolve(FootnoteEntry::gap),
             expand: regions.expand.x,
         },
+        sidenote: margin.map(|width| SidenoteConfig {
+            width,
+            clearance: shared.resolve(SidenoteEntry::clearance),
+            gap: shared.resolve(SidenoteEntry::gap),
+        }),
         line_numbers: (mode == FlowMode::Root).then(|| LineNumberConfig {
             scope: shared.get(ParLine::numbering_scope),
             default_clearance: {
@@ -303,12 +354,16 @@ struct Work<'a, 'b> {
     children: &'b [Child<'a>],
     /// Leftovers from a breakable block.
     spill: Option<MultiSpill<'a, 'b>>,
+    /// Leftover lines from a paragraph that flows around wrapped elements.
+    par_spill: Option<ParSpill>,
     /// Queued floats that didn't fit in previous regions.
     floats: EcoVec<&'b PlacedChild<'a>>,
     /// Queued footnotes that didn't fit in previous regions.
     footnotes: EcoVec<Packed<FootnoteElem>>,
     /// Spilled frames of a footnote that didn't fully fit. Similar to `spill`.
     footnote_spill: Option<std::vec::IntoIter<Frame>>,
+    /// Queued sidenotes that didn't fit into the margin of previous regions.
+    sidenotes: EcoVec<Packed<SidenoteElem>>,
     /// Queued tags that will be attached to the next frame.
     tags: EcoVec<&'a Tag>,
     /// Identifies floats and footnotes that can be skipped if visited because
@@ -323,9 +378,11 @@ impl<'a, 'b> Work<'a, 'b> {
         Self {
             children,
             spill: None,
+            par_spill: None,
             floats: EcoVec::new(),
             footnotes: EcoVec::new(),
             footnote_spill: None,
+            sidenotes: EcoVec::new(),
             tags: EcoVec::new(),
             skips: Rc::new(FxHashSet::default()),
         }
@@ -341,13 +398,20 @@ impl<'a, 'b> Work<'a, 'b> {
         self.children = &self.children[1..];
     }
 
+    /// Skip the given number of children.
+    fn skip(&mut self, n: usize) {
+        self.children = &self.children[n..];
+    }
+
     /// Whether all work is done. This means we can terminate flow layout.
     fn done(&self) -> bool {
         self.children.is_empty()
             && self.spill.is_none()
+            && self.par_spill.is_none()
             && self.floats.is_empty()
             && self.footnote_spill.is_none()
             && self.footnotes.is_empty()
+            && self.sidenotes.is_empty()
     }
 
     /// Add skipped floats and footnotes from the insertion areas to the skip
@@ -382,6 +446,8 @@ struct Config<'x> {
     columns: ColumnConfig,
     /// Settings for footnotes.
     footnote: FootnoteConfig,
+    /// Settings for sidenotes. Only present for the root flow of a page run.
+    sidenote: Option<SidenoteConfig>,
     /// Settings for line numbers.
     line_numbers: Option<LineNumberConfig>,
 }
@@ -398,6 +464,16 @@ struct FootnoteConfig {
     expand: bool,
 }
 
+/// Configuration of sidenotes.
+struct SidenoteConfig {
+    /// The width of the outer page margin, which hosts the sidenotes.
+    width: Abs,
+    /// The amount of space on both sides of the sidenotes.
+    clearance: Abs,
+    /// The minimum gap between sidenotes.
+    gap: Abs,
+}
+
 /// Configuration of columns.
 struct ColumnConfig {
     /// The number of columns.
@@ -406,8 +482,8 @@ struct ColumnConfig {
     width: Abs,
     /// The amount of space between columns.
     gutter: Abs,
-    /// The horizontal direction in which columns progress. Defined by
-    /// `text.dir`.
+    /// The text direction, which determines the horizontal direction in which
+    /// columns progress. Defined by `text.dir`.
     dir: Dir,
     /// Whether to equalize the height of columns by breaking columns early.
     balanced: bool,
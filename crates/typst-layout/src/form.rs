@@ -0,0 +1,140 @@
+use typst_library::diag::SourceResult;
+use typst_library::engine::Engine;
+use typst_library::foundations::{NativeElement, Packed, Smart, StyleChain};
+use typst_library::introspection::Locator;
+use typst_library::layout::{
+    Abs, BoxElem, Corners, Em, Frame, FrameItem, InlineItem, Length, Point, Ratio, Rel,
+    Sides, Size, Sizing,
+};
+use typst_library::pdf::{
+    ButtonElem, CheckboxElem, ChoiceElem, RadioElem, SignatureFieldElem, TextFieldElem,
+};
+use typst_library::visualize::{Color, Stroke};
+
+/// Lay out a text field.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_text_field(
+    elem: &Packed<TextFieldElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    let field = BoxElem::new()
+        .with_width(Sizing::Rel(elem.width.get(styles)))
+        .with_height(Smart::Custom(elem.height.get(styles)));
+    layout_field(elem, field, engine, locator, styles, region)
+}
+
+/// Lay out a checkbox.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_checkbox(
+    elem: &Packed<CheckboxElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    let size = Rel::from(elem.size.get(styles));
+    let field = BoxElem::new()
+        .with_width(Sizing::Rel(size))
+        .with_height(Smart::Custom(size));
+    layout_field(elem, field, engine, locator, styles, region)
+}
+
+/// Lay out a radio button.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_radio(
+    elem: &Packed<RadioElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    let size = Rel::from(elem.size.get(styles));
+    let field = BoxElem::new()
+        .with_width(Sizing::Rel(size))
+        .with_height(Smart::Custom(size))
+        .with_radius(Corners::splat(Some(Rel::new(Ratio::new(0.5), Length::zero()))));
+    layout_field(elem, field, engine, locator, styles, region)
+}
+
+/// Lay out a choice field.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_choice(
+    elem: &Packed<ChoiceElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    let field = BoxElem::new()
+        .with_width(Sizing::Rel(elem.width.get(styles)))
+        .with_height(Smart::Custom(elem.height.get(styles)));
+    layout_field(elem, field, engine, locator, styles, region)
+}
+
+/// Lay out a push button.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_button(
+    elem: &Packed<ButtonElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    let field = BoxElem::new()
+        .with_body(Some(elem.body.clone()))
+        .with_inset(Sides::splat(Some(Em::new(0.3).into())))
+        .with_radius(Corners::splat(Some(Abs::pt(2.0).into())))
+        .with_fill(Some(Color::SILVER.into()));
+    layout_field(elem, field, engine, locator, styles, region)
+}
+
+/// Lay out a signature field.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_signature_field(
+    elem: &Packed<SignatureFieldElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    // An invisible field still needs an area to be placed in the PDF, but it
+    // is discarded during export.
+    if !elem.visible.get(styles) {
+        let location = elem.location().unwrap();
+        let mut frame = Frame::soft(Size::zero());
+        frame.push(
+            Point::zero(),
+            FrameItem::Annotation(location, Size::splat(Abs::pt(1.0))),
+        );
+        return Ok(vec![InlineItem::Frame(frame)]);
+    }
+
+    let field = BoxElem::new()
+        .with_width(Sizing::Rel(elem.width.get(styles)))
+        .with_height(Smart::Custom(elem.height.get(styles)));
+    layout_field(elem, field, engine, locator, styles, region)
+}
+
+/// Lay out the box that represents a form field and mark it as the field's
+/// interactive area.
+fn layout_field<T: NativeElement>(
+    elem: &Packed<T>,
+    field: BoxElem,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    let stroke = Stroke::from_pair(Color::GRAY, Abs::pt(0.5).into());
+    let field = Packed::new(field.with_stroke(Sides::splat(Some(Some(stroke)))))
+        .spanned(elem.span());
+
+    let mut frame = crate::inline::layout_box(&field, engine, locator, styles, region)?;
+    let location = elem.location().unwrap();
+    frame.push(Point::zero(), FrameItem::Annotation(location, frame.size()));
+
+    Ok(vec![InlineItem::Frame(frame)])
+}
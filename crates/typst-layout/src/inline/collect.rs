@@ -6,13 +6,15 @@ use typst_library::layout::{
 };
 use typst_library::routines::Pair;
 use typst_library::text::{
-    LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem, TextElem,
-    is_default_ignorable,
+    LinebreakElem, RubyElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem,
+    TextElem, is_default_ignorable,
 };
 use typst_syntax::Span;
 use typst_utils::Numeric;
 
 use super::*;
+use crate::inline::ruby::layout_ruby;
+use crate::inline::vertical::upright;
 use crate::modifiers::{FrameModifiers, FrameModify, layout_and_modify};
 
 // The characters by which spacing, inline content and pins are replaced in the
@@ -148,7 +150,7 @@ pub fn collect<'a>(
         collector.spans.push(1, Span::detached());
     }
 
-    for &(child, styles) in children {
+    for (i, &(child, styles)) in children.iter().enumerate() {
         let prev_len = collector.full.len();
 
         if child.is::<SpaceElem>() {
@@ -235,8 +237,36 @@ pub fn collect<'a>(
                     layout_box(elem, engine, loc, styles, region)
                 })?;
                 apply_shift(&engine.world, &mut frame, styles);
+                if config.dir == Dir::TTB {
+                    // Boxes stand upright in vertical text.
+                    frame = upright(frame);
+                }
+                collector.push_item(Item::Frame(frame));
+            }
+        } else if let Some(elem) = child.to_packed::<RubyElem>() {
+            // The neighbouring characters decide whether the annotation may
+            // overhang onto them.
+            let before = collector.full.chars().rev().find(|&c| !is_default_ignorable(c));
+            let after = children[i + 1..]
+                .iter()
+                .map(|&(child, _)| child)
+                .find(|child| !child.is::<TagElem>())
+                .and_then(|child| child.to_packed::<TextElem>())
+                .and_then(|elem| elem.text.chars().next());
+
+            let loc = locator.next(&elem.span());
+            let frames = layout_ruby(elem, engine, loc, styles, region, before, after)?;
+
+            collector.push_item(Item::Skip(LTR_ISOLATE));
+            for mut frame in frames {
+                frame.modify(&FrameModifiers::get_in(styles));
+                apply_shift(&engine.world, &mut frame, styles);
+                if config.dir == Dir::TTB {
+                    frame = upright(frame);
+                }
                 collector.push_item(Item::Frame(frame));
             }
+            collector.push_item(Item::Skip(POP_ISOLATE));
         } else if let Some(elem) = child.to_packed::<TagElem>() {
             collector.push_item(Item::Tag(&elem.tag));
         } else {
@@ -12,6 +12,7 @@ use typst_utils::Numeric;
 use super::*;
 use crate::inline::linebreak::Trim;
 use crate::inline::shaping::{Adjustability, ShapedGlyph};
+use crate::inline::vertical::center;
 use crate::modifiers::layout_and_modify;
 
 const SHY: char = '\u{ad}';
@@ -496,10 +497,10 @@ pub fn commit(
     let mut remaining = width - line.width - p.config.hanging_indent;
     let mut offset = Abs::zero();
 
-    // We always build the line from left to right. In an LTR paragraph, we must
-    // thus add the hanging indent to the offset. In an RTL paragraph, the
-    // hanging indent arises naturally due to the line width.
-    if p.config.dir == Dir::LTR {
+    // We always build the line from left to right. In an LTR or vertical
+    // paragraph, we must thus add the hanging indent to the offset. In an RTL
+    // paragraph, the hanging indent arises naturally due to the line width.
+    if p.config.dir.is_positive() {
         offset += p.config.hanging_indent;
     }
 
@@ -588,12 +589,15 @@ pub fn commit(
                 }
             }
             Item::Text(shaped) => {
-                let frame = shaped.build(
+                let mut frame = shaped.build(
                     engine,
                     &p.spans,
                     justification_ratio,
                     extra_justification,
                 );
+                if p.config.dir == Dir::TTB && shaped.dir != Dir::TTB {
+                    center(&mut frame, shaped);
+                }
                 push(&mut offset, frame, idx);
             }
             Item::Frame(frame) => {
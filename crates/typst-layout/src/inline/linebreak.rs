@@ -155,19 +155,41 @@ pub fn linebreak<'a>(
     width: Abs,
 ) -> Vec<Line<'a>> {
     match p.config.linebreaks {
-        Linebreaks::Simple => linebreak_simple(engine, p, width),
+        Linebreaks::Simple => linebreak_simple(engine, p, |_| width),
         Linebreaks::Optimized => linebreak_optimized(engine, p, width),
     }
 }
 
+/// Breaks the text into lines whose available width may differ from line to
+/// line. It is given by `width` for each line index.
+pub fn linebreak_variable<'a>(
+    engine: &Engine,
+    p: &'a Preparation<'a>,
+    width: impl Fn(usize) -> Abs,
+) -> Vec<Line<'a>> {
+    match p.config.linebreaks {
+        Linebreaks::Simple => linebreak_simple(engine, p, width),
+        Linebreaks::Optimized => {
+            // With varying widths, the table only keeps the best layout up to
+            // each breakpoint, which need not be part of the best layout
+            // overall. Thus, the approximate pass doesn't give a sound bound.
+            let metrics = CostMetrics::compute(p);
+            linebreak_optimized_bounded(engine, p, &width, &metrics, Cost::INFINITY)
+        }
+    }
+}
+
 /// Performs line breaking in simple first-fit style. This means that we build
 /// lines greedily, always taking the longest possible line. This may lead to
 /// very unbalanced line, but is fast and simple.
+///
+/// The available width may differ from line to line. It is given by `width`
+/// for each line index.
 #[typst_macros::time]
 fn linebreak_simple<'a>(
     engine: &Engine,
     p: &'a Preparation<'a>,
-    width: Abs,
+    width: impl Fn(usize) -> Abs,
 ) -> Vec<Line<'a>> {
     let mut lines = Vec::with_capacity(16);
     let mut start = 0;
@@ -180,7 +202,7 @@ fn linebreak_simple<'a>(
         // If the line doesn't fit anymore, we push the last fitting attempt
         // into the stack and rebuild the line from the attempt's end. The
         // resulting line cannot be broken up further.
-        if !width.fits(attempt.width)
+        if !width(lines.len()).fits(attempt.width)
             && let Some((last_attempt, last_end)) = last.take()
         {
             lines.push(last_attempt);
@@ -191,7 +213,8 @@ fn linebreak_simple<'a>(
         // Finish the current line if there is a mandatory line break (i.e. due
         // to "\n") or if the line doesn't fit horizontally already since then
         // no shorter line will be possible.
-        if breakpoint == Breakpoint::Mandatory || !width.fits(attempt.width) {
+        if breakpoint == Breakpoint::Mandatory || !width(lines.len()).fits(attempt.width)
+        {
             lines.push(attempt);
             start = end;
             last = None;
@@ -237,16 +260,18 @@ fn linebreak_optimized<'a>(
     let upper_bound = linebreak_optimized_approximate(engine, p, width, &metrics);
 
     // Using the upper bound, perform exact optimized linebreaking.
-    linebreak_optimized_bounded(engine, p, width, &metrics, upper_bound)
+    linebreak_optimized_bounded(engine, p, &|_| width, &metrics, upper_bound)
 }
 
 /// Performs line breaking in optimized Knuth-Plass style, but with an upper
 /// bound on the cost. This allows us to skip many parts of the search space.
+///
+/// The available width is given by `width` for each line index.
 #[typst_macros::time]
 fn linebreak_optimized_bounded<'a>(
     engine: &Engine,
     p: &'a Preparation<'a>,
-    width: Abs,
+    width: &impl Fn(usize) -> Abs,
     metrics: &CostMetrics,
     upper_bound: Cost,
 ) -> Vec<Line<'a>> {
@@ -256,10 +281,18 @@ fn linebreak_optimized_bounded<'a>(
         total: Cost,
         line: Line<'a>,
         end: usize,
+        /// The number of lines up to and including this one.
+        count: usize,
     }
 
     // Dynamic programming table.
-    let mut table = vec![Entry { pred: 0, total: 0.0, line: Line::empty(), end: 0 }];
+    let mut table = vec![Entry {
+        pred: 0,
+        total: 0.0,
+        line: Line::empty(),
+        end: 0,
+        count: 0,
+    }];
 
     let mut active = 0;
     let mut prev_end = 0;
@@ -290,7 +323,7 @@ fn linebreak_optimized_bounded<'a>(
             let (line_ratio, line_cost) = ratio_and_cost(
                 p,
                 metrics,
-                width,
+                width(pred.count),
                 &pred.line,
                 &attempt,
                 breakpoint,
@@ -335,7 +368,13 @@ fn linebreak_optimized_bounded<'a>(
 
             // If this attempt is better than what we had before, take it!
             if best.as_ref().is_none_or(|best| best.total >= total) {
-                best = Some(Entry { pred: pred_index, total, line: attempt, end });
+                best = Some(Entry {
+                    pred: pred_index,
+                    total,
+                    line: attempt,
+                    end,
+                    count: pred.count + 1,
+                });
             }
         }
 
@@ -6,10 +6,14 @@ mod finalize;
 mod line;
 mod linebreak;
 mod prepare;
+mod ruby;
 mod shaping;
+mod vertical;
+mod wrap;
 
 pub use self::box_::layout_box;
 pub use self::shaping::{SharedShapingContext, create_shape_plan, get_font_and_covers};
+pub use self::wrap::{Exclusion, Exclusions};
 
 use comemo::{Track, Tracked, TrackedMut};
 use typst_library::diag::SourceResult;
@@ -36,6 +40,8 @@ use self::shaping::{
     BEGIN_PUNCT_PAT, END_PUNCT_PAT, ShapedGlyph, ShapedText, cjk_punct_style,
     is_of_cj_script, shape_range,
 };
+use self::vertical::vertical;
+use self::wrap::wrap;
 
 /// Range of a substring of text.
 type Range = std::ops::Range<usize>;
@@ -49,6 +55,7 @@ pub fn layout_par(
     region: Size,
     expand: bool,
     situation: ParSituation,
+    exclusions: &Exclusions,
 ) -> SourceResult<Fragment> {
     layout_par_impl(
         elem,
@@ -63,6 +70,7 @@ pub fn layout_par(
         region,
         expand,
         situation,
+        exclusions,
     )
 }
 
@@ -82,6 +90,7 @@ fn layout_par_impl(
     region: Size,
     expand: bool,
     situation: ParSituation,
+    exclusions: &Exclusions,
 ) -> SourceResult<Fragment> {
     let introspector = Protected::from_raw(introspector);
     let link = LocatorLink::new(locator);
@@ -113,6 +122,7 @@ fn layout_par_impl(
         region,
         expand,
         Some(situation),
+        exclusions,
         &ConfigBase {
             justify: elem.justify.get(styles),
             linebreaks: elem.linebreaks.get(styles),
@@ -139,6 +149,7 @@ pub fn layout_inline<'a>(
         region,
         expand,
         None,
+        &Exclusions::default(),
         &ConfigBase {
             justify: shared.get(ParElem::justify),
             linebreaks: shared.get(ParElem::linebreaks),
@@ -158,6 +169,7 @@ fn layout_inline_impl<'a>(
     region: Size,
     expand: bool,
     par: Option<ParSituation>,
+    exclusions: &Exclusions,
     base: &ConfigBase,
 ) -> SourceResult<Fragment> {
     // Prepare configuration that is shared across the whole inline layout.
@@ -170,6 +182,16 @@ fn layout_inline_impl<'a>(
     // proceed to line breaking.
     let p = prepare(engine, &config, &text, segments, spans)?;
 
+    // Vertical text brings its own line progression.
+    if config.dir == Dir::TTB {
+        return vertical(engine, &p, region, expand, locator);
+    }
+
+    // Break the text into lines that flow around exclusions, if any.
+    if !exclusions.is_empty() {
+        return wrap(engine, &p, exclusions, region, locator);
+    }
+
     // Break the text into lines.
     let lines = linebreak(engine, &p, region.x - config.hanging_indent);
 
@@ -232,6 +254,7 @@ fn configuration(
         align: shared.get(AlignElem::alignment).fix(dir).x,
         font_size,
         dir,
+        leading: shared.resolve(ParElem::leading),
         hyphenate: shared_get(children, shared, |s| s.get(TextElem::hyphenate))
             .map(|uniform| uniform.unwrap_or(justify)),
         lang: shared_get(children, shared, |s| s.get(TextElem::lang)),
@@ -285,6 +308,9 @@ struct Config {
     font_size: Abs,
     /// The dominant direction.
     dir: Dir,
+    /// The spacing between lines. Only used for vertical text, whose lines
+    /// are stacked by inline layout rather than by the flow.
+    leading: Abs,
     /// A uniform hyphenation setting (only `Some(_)` if it's the same for all
     /// children, otherwise `None`).
     hyphenate: Option<bool>,
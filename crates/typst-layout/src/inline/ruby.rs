@@ -0,0 +1,171 @@
+use typst_library::diag::SourceResult;
+use typst_library::engine::Engine;
+use typst_library::foundations::{Content, Packed, Resolve, Smart, StyleChain};
+use typst_library::introspection::Locator;
+use typst_library::layout::{Abs, Axes, Frame, Point, Region, Size};
+use typst_library::pdf::PdfMarkerTag;
+use typst_library::text::{
+    BottomEdge, BottomEdgeMetric, RubyElem, RubyPlacement, TextElem, TopEdge,
+    TopEdgeMetric,
+};
+
+/// A piece of ruby that the line may be broken around.
+struct Cell {
+    /// The base text with the annotation above it.
+    frame: Frame,
+    /// The horizontal extent of the base text within the frame.
+    base: (Abs, Abs),
+}
+
+/// Lays out ruby annotations above their base text.
+///
+/// Returns one frame per piece of the base text that may end up on its own
+/// line: A single one for a single or grouped annotation and one per character
+/// otherwise. The characters before and after the ruby determine whether
+/// the annotations may overhang onto them.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_ruby(
+    elem: &Packed<RubyElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Size,
+    before: Option<char>,
+    after: Option<char>,
+) -> SourceResult<Vec<Frame>> {
+    let placement = elem.placement.get(styles);
+    let pairs = if placement == Smart::Custom(RubyPlacement::Group) {
+        let annotation = Content::sequence(elem.annotation.0.iter().cloned());
+        vec![(elem.base.clone(), annotation)]
+    } else {
+        elem.pairs()?
+    };
+
+    // The base text's line extends up to the ascender so that the annotation
+    // doesn't collide with tall glyphs. Likewise, the annotation's line
+    // extends down to the descender.
+    let mut locator = locator.split();
+    let pod = Region::new(region, Axes::splat(false));
+    let mut bases = Vec::with_capacity(pairs.len());
+    let mut annotations = Vec::with_capacity(pairs.len());
+    for (base, annotation) in pairs {
+        let base = base.set(TextElem::top_edge, TopEdge::Metric(TopEdgeMetric::Ascender));
+        let annotation = annotation
+            .set(TextElem::size, elem.size.get(styles))
+            .set(TextElem::bottom_edge, BottomEdge::Metric(BottomEdgeMetric::Descender));
+        let base = PdfMarkerTag::RubyBase(base);
+        let annotation = PdfMarkerTag::RubyText(annotation);
+        let loc = locator.next(&base.span());
+        bases.push(crate::layout_frame(engine, &base, loc, styles, pod)?);
+        let loc = locator.next(&annotation.span());
+        annotations.push(crate::layout_frame(engine, &annotation, loc, styles, pod)?);
+    }
+
+    // With jukugo placement, the annotations only stay above their own
+    // characters if all of them fit.
+    let fits = annotations.iter().zip(&bases).all(|(a, b)| a.width() <= b.width());
+    let gap = elem.gap.resolve(styles);
+    let mut cells = if placement == Smart::Custom(RubyPlacement::Mono) || fits {
+        mono(bases, annotations, gap)
+    } else {
+        jukugo(bases, annotations, gap)
+    };
+
+    if elem.overhang.get(styles) {
+        let limit = elem.size.resolve(styles);
+        if let Some(first) = cells.first_mut()
+            && before.is_some_and(is_kana)
+        {
+            let shift = first.base.0.min(limit);
+            first.frame.translate(Point::with_x(-shift));
+            first
+                .frame
+                .set_size(Size::new(first.frame.width() - shift, first.frame.height()));
+            first.base.0 -= shift;
+            first.base.1 -= shift;
+        }
+
+        if let Some(last) = cells.last_mut()
+            && after.is_some_and(is_kana)
+        {
+            let shift = (last.frame.width() - last.base.1).min(limit);
+            last.frame
+                .set_size(Size::new(last.frame.width() - shift, last.frame.height()));
+        }
+    }
+
+    Ok(cells.into_iter().map(|cell| cell.frame).collect())
+}
+
+/// Centers each annotation above its own piece of the base text.
+fn mono(bases: Vec<Frame>, annotations: Vec<Frame>, gap: Abs) -> Vec<Cell> {
+    let top = annotations.iter().map(Frame::height).max().unwrap_or_default();
+    bases
+        .into_iter()
+        .zip(annotations)
+        .map(|(base, annotation)| {
+            let width = base.width().max(annotation.width());
+            let x = (width - base.width()) / 2.0;
+            let ax = (width - annotation.width()) / 2.0;
+            let extent = (x, x + base.width());
+            let frame = join(base, x, annotation, ax, width, top, gap);
+            Cell { frame, base: extent }
+        })
+        .collect()
+}
+
+/// Sets the annotations side by side, centered above the whole base text, and
+/// spreads the base text's characters evenly if the annotations are wider.
+/// Each annotation stays in the cell of its own character, so that it moves
+/// along with it if the line is broken.
+fn jukugo(bases: Vec<Frame>, annotations: Vec<Frame>, gap: Abs) -> Vec<Cell> {
+    let top = annotations.iter().map(Frame::height).max().unwrap_or_default();
+    let base_width: Abs = bases.iter().map(Frame::width).sum();
+    let annotation_width: Abs = annotations.iter().map(Frame::width).sum();
+    let width = base_width.max(annotation_width);
+    let extra = (width - base_width) / bases.len() as f64;
+
+    let mut cell_x = Abs::zero();
+    let mut annotation_x = (width - annotation_width) / 2.0;
+    let mut cells = Vec::with_capacity(bases.len());
+    for (base, annotation) in bases.into_iter().zip(annotations) {
+        let cell_width = base.width() + extra;
+        let x = extra / 2.0;
+        let extent = (x, x + base.width());
+        let ax = annotation_x - cell_x;
+        cell_x += cell_width;
+        annotation_x += annotation.width();
+        let frame = join(base, x, annotation, ax, cell_width, top, gap);
+        cells.push(Cell { frame, base: extent });
+    }
+
+    cells
+}
+
+/// Places an annotation above a piece of base text.
+///
+/// The annotation's bottom edge is `top` below the frame's top edge and the
+/// frame's baseline is the one of the base text.
+fn join(
+    base: Frame,
+    x: Abs,
+    annotation: Frame,
+    ax: Abs,
+    width: Abs,
+    top: Abs,
+    gap: Abs,
+) -> Frame {
+    let y = top + gap;
+    let mut frame = Frame::soft(Size::new(width, y + base.height()));
+    frame.set_baseline(y + base.baseline());
+    // The base comes first in the reading order.
+    frame.push_frame(Point::new(x, y), base);
+    frame.push_frame(Point::new(ax, top - annotation.height()), annotation);
+    frame
+}
+
+/// Whether a character is hiragana or katakana, onto which ruby annotations
+/// may overhang.
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}')
+}
@@ -22,6 +22,7 @@ use typst_utils::SliceExt;
 use unicode_bidi::{BidiInfo, Level as BidiLevel};
 use unicode_script::{Script, UnicodeScript};
 
+use super::vertical::{combine, is_combined, is_upright, upright};
 use super::{Item, Range, SpanMapper, decorate};
 use crate::modifiers::FrameModifyText;
 
@@ -136,6 +137,10 @@ pub struct ShapedGlyph {
     /// The glyph's index in the font.
     pub glyph_id: u16,
     /// The advance width of the glyph.
+    ///
+    /// For upright glyphs in vertical text, the advance and offsets are
+    /// relative to the line: This is the advance along the line, `x_offset` is
+    /// the offset along the line, and `y_offset` is the offset across it.
     pub x_advance: Em,
     /// The horizontal offset of the glyph.
     pub x_offset: Em,
@@ -303,6 +308,35 @@ impl ShapedGlyph {
         self.adjustability.shrinkability
     }
 
+    /// The advance width and horizontal offset of the glyph after
+    /// justification.
+    ///
+    /// The `justification_ratio` scales the glyph's adjustability and
+    /// [justifiable glyphs](Self::is_justifiable) additionally get the
+    /// `extra_justification`.
+    pub fn justified(
+        &self,
+        justification_ratio: f64,
+        extra_justification: Abs,
+    ) -> (Em, Em) {
+        let adjustability = if justification_ratio < 0.0 {
+            self.shrinkability()
+        } else {
+            self.stretchability()
+        };
+
+        let justification_left = adjustability.0 * justification_ratio;
+        let mut justification_right = adjustability.1 * justification_ratio;
+        if self.is_justifiable() {
+            justification_right += Em::from_abs(extra_justification, self.size);
+        }
+
+        (
+            self.x_advance + justification_left + justification_right,
+            self.x_offset + justification_left,
+        )
+    }
+
     /// Shrink the width of glyph on the left side.
     pub fn shrink_left(&mut self, amount: Em) {
         self.x_offset -= amount;
@@ -329,6 +363,10 @@ impl<'a> ShapedText<'a> {
         justification_ratio: f64,
         extra_justification: Abs,
     ) -> Frame {
+        if self.dir == Dir::TTB {
+            return self.build_upright(spans, justification_ratio, extra_justification);
+        }
+
         let (top, bottom) = self.measure(engine);
         let size = Size::new(self.width(), top + bottom);
 
@@ -366,32 +404,11 @@ impl<'a> ShapedText<'a> {
                     let kept = self.glyphs.kept.contains(&i);
 
                     let (x_advance, x_offset) = if kept {
-                        let adjustability_left = if justification_ratio < 0.0 {
-                            shaped.shrinkability().0
-                        } else {
-                            shaped.stretchability().0
-                        };
-                        let adjustability_right = if justification_ratio < 0.0 {
-                            shaped.shrinkability().1
-                        } else {
-                            shaped.stretchability().1
-                        };
-
-                        let justification_left = adjustability_left * justification_ratio;
-                        let mut justification_right =
-                            adjustability_right * justification_ratio;
-                        if shaped.is_justifiable() {
-                            justification_right +=
-                                Em::from_abs(extra_justification, glyph_size);
-                        }
-
-                        frame.size_mut().x += justification_left.at(glyph_size)
-                            + justification_right.at(glyph_size);
-
-                        (
-                            shaped.x_advance + justification_left + justification_right,
-                            shaped.x_offset + justification_left,
-                        )
+                        let (x_advance, x_offset) =
+                            shaped.justified(justification_ratio, extra_justification);
+                        frame.size_mut().x +=
+                            (x_advance - shaped.x_advance).at(glyph_size);
+                        (x_advance, x_offset)
                     } else {
                         (Em::zero(), Em::zero())
                     };
@@ -463,6 +480,84 @@ impl<'a> ShapedText<'a> {
         frame
     }
 
+    /// Build the frame of upright text in a vertical line.
+    ///
+    /// The glyphs are stacked from top to bottom in a frame that is then turned
+    /// to fit into the line, which is built as if it were horizontal.
+    /// Decorations are not supported for upright text.
+    fn build_upright(
+        &self,
+        spans: &SpanMapper,
+        justification_ratio: f64,
+        extra_justification: Abs,
+    ) -> Frame {
+        let size = self.styles.resolve(TextElem::size);
+        let fill = self.styles.get_ref(TextElem::fill);
+        let stroke = self.styles.resolve(TextElem::stroke);
+        let span_offset = self.styles.get(TextElem::span_offset);
+
+        let mut offset = Abs::zero();
+        let mut frame = Frame::soft(Size::with_x(size));
+
+        let mut i = 0;
+        for ((font, glyph_size), group) in
+            self.glyphs.all().group_by_key(|g| (g.font.clone(), g.size))
+        {
+            let mut range = group[0].range.clone();
+            for glyph in group {
+                range.start = range.start.min(glyph.range.start);
+                range.end = range.end.max(glyph.range.end);
+            }
+
+            let glyphs: Vec<Glyph> = group
+                .iter()
+                .map(|shaped: &ShapedGlyph| {
+                    let (advance, offset) = if self.glyphs.kept.contains(&i) {
+                        shaped.justified(justification_ratio, extra_justification)
+                    } else {
+                        (Em::zero(), Em::zero())
+                    };
+                    i += 1;
+
+                    let mut span = spans.span_at(shaped.range.start);
+                    span.1 = span.1.saturating_add(span_offset.saturating_as());
+
+                    // The glyphs advance downwards and are offset from the
+                    // middle of the line.
+                    Glyph {
+                        id: shaped.glyph_id,
+                        x_advance: Em::zero(),
+                        x_offset: shaped.y_offset,
+                        y_advance: -advance,
+                        y_offset: -offset,
+                        range: (shaped.range.start - range.start).saturating_as()
+                            ..(shaped.range.end - range.start).saturating_as(),
+                        span,
+                    }
+                })
+                .collect();
+
+            let item = TextItem {
+                font,
+                size: glyph_size,
+                lang: self.lang,
+                region: self.region,
+                fill: fill.clone(),
+                stroke: stroke.clone().map(|s| s.unwrap_or_default()),
+                text: self.text[range.start - self.base..range.end - self.base].into(),
+                glyphs,
+            };
+
+            let height = -item.height();
+            frame.push(Point::new(size / 2.0, offset), FrameItem::Text(item));
+            offset += height;
+        }
+
+        frame.size_mut().y = offset;
+        frame.modify_text(self.styles);
+        upright(frame)
+    }
+
     /// Computes the width of a run of glyphs relative to the font size,
     /// accounting for their individual scaling factors and other font metrics.
     pub fn width(&self) -> Abs {
@@ -729,46 +824,59 @@ pub fn shape_range<'a>(
     let script = styles.get(TextElem::script);
     let lang = styles.get(TextElem::lang);
     let region = styles.get(TextElem::region);
-    let mut process = |range: Range, level: BidiLevel| {
-        let dir = if level.is_ltr() { Dir::LTR } else { Dir::RTL };
-        let shaped =
-            shape(engine, range.start, &text[range.clone()], styles, dir, lang, region);
+    let vertical = styles.resolve(TextElem::dir) == Dir::TTB;
+    let mut process = |range: Range, level: BidiLevel, upright: bool| {
+        let piece = &text[range.clone()];
+        let dir = if upright || (vertical && is_combined(piece)) {
+            Dir::TTB
+        } else if level.is_ltr() {
+            Dir::LTR
+        } else {
+            Dir::RTL
+        };
+        let shaped = shape(engine, range.start, piece, styles, dir, lang, region);
         items.push((range, Item::Text(shaped)));
     };
 
     let mut prev_level = BidiLevel::ltr();
     let mut prev_script = Script::Unknown;
+    let mut prev_upright = false;
     let mut cursor = range.start;
 
-    // Group by embedding level and script.  If the text's script is explicitly
-    // set (rather than inferred from the glyphs), we keep the script at an
-    // unchanging `Script::Unknown` so that only level changes cause breaks.
+    // Group by embedding level, script, and, in vertical text, orientation. If
+    // the text's script is explicitly set (rather than inferred from the
+    // glyphs), we keep the script at an unchanging `Script::Unknown` so that
+    // only level and orientation changes cause breaks.
     for i in range.clone() {
         if !text.is_char_boundary(i) {
             continue;
         }
 
         let level = bidi.levels[i];
+        let c = text[i..].chars().next();
         let curr_script = match script {
-            Smart::Auto => {
-                text[i..].chars().next().map_or(Script::Unknown, |c| c.script())
-            }
+            Smart::Auto => c.map_or(Script::Unknown, |c| c.script()),
             Smart::Custom(_) => Script::Unknown,
         };
+        let curr_upright = vertical && c.is_some_and(is_upright);
 
-        if level != prev_level || !is_compatible(curr_script, prev_script) {
+        if level != prev_level
+            || !is_compatible(curr_script, prev_script)
+            || curr_upright != prev_upright
+        {
             if cursor < i {
-                process(cursor..i, prev_level);
+                process(cursor..i, prev_level, prev_upright);
             }
             cursor = i;
             prev_level = level;
             prev_script = curr_script;
+            prev_upright = curr_upright;
         } else if is_generic_script(prev_script) {
             prev_script = curr_script;
         }
     }
 
-    process(cursor..range.end, prev_level);
+    process(cursor..range.end, prev_level, prev_upright);
 }
 
 /// Whether this is not a specific script.
@@ -807,11 +915,21 @@ fn shape<'a>(
         shift_settings,
     };
 
+    // Short numbers in vertical text are shaped horizontally and then combined
+    // into a single em of the line.
+    let combined = dir == Dir::TTB && is_combined(text);
+    if combined {
+        ctx.dir = Dir::LTR;
+    }
+
     if !text.is_empty() {
         shape_segment(&mut ctx, base, text, families(styles));
     }
 
     track_and_space(&mut ctx);
+    if combined {
+        combine(&mut ctx.glyphs, size);
+    }
     calculate_adjustability(&mut ctx, lang, region);
 
     #[cfg(debug_assertions)]
@@ -986,7 +1104,8 @@ fn shape_segment<'a>(
     buffer.set_direction(match ctx.dir {
         Dir::LTR => rustybuzz::Direction::LeftToRight,
         Dir::RTL => rustybuzz::Direction::RightToLeft,
-        _ => unimplemented!("vertical text layout"),
+        Dir::TTB => rustybuzz::Direction::TopToBottom,
+        Dir::BTT => unreachable!("text direction cannot be bottom-to-top"),
     });
     buffer.guess_segment_properties();
 
@@ -1086,14 +1205,27 @@ fn shape_segment<'a>(
 
             let c = text[cluster..].chars().next().unwrap();
             let script = c.script();
-            let x_advance = font.to_em(pos[i].x_advance);
+            let (x_advance, x_offset, y_offset) = if ctx.dir == Dir::TTB {
+                // Vertical glyphs advance downwards, while the positions from
+                // rustybuzz point upwards.
+                (
+                    font.to_em(-pos[i].y_advance),
+                    font.to_em(-pos[i].y_offset),
+                    font.to_em(pos[i].x_offset),
+                )
+            } else {
+                (
+                    font.to_em(pos[i].x_advance),
+                    font.to_em(pos[i].x_offset) + script_compensation,
+                    font.to_em(pos[i].y_offset) + script_shift,
+                )
+            };
             ctx.glyphs.push(ShapedGlyph {
                 font: font.clone(),
                 glyph_id: info.glyph_id as u16,
-                // TODO: Don't ignore y_advance.
                 x_advance,
-                x_offset: font.to_em(pos[i].x_offset) + script_compensation,
-                y_offset: font.to_em(pos[i].y_offset) + script_shift,
+                x_offset,
+                y_offset,
                 size: scale.at(ctx.size),
                 adjustability: Adjustability::default(),
                 range: start..end,
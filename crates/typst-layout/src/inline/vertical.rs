@@ -0,0 +1,174 @@
+use typst_library::introspection::SplitLocator;
+use typst_library::layout::{Angle, Em, Frame, Point, Transform};
+
+use super::*;
+
+/// The maximum number of digits that are set horizontally within a vertical
+/// line (tate-chū-yoko).
+const MAX_COMBINED_DIGITS: usize = 2;
+
+/// Lays out the lines of vertical text.
+///
+/// The lines are built as if they were horizontal, with the region's height as
+/// their width, and then turned into place. They follow each other from right
+/// to left. Lines that don't fit next to each other into the region's width
+/// move into a new frame, which ends up below the previous one.
+#[typst_macros::time]
+pub fn vertical(
+    engine: &mut Engine,
+    p: &Preparation,
+    region: Size,
+    expand: bool,
+    locator: &mut SplitLocator<'_>,
+) -> SourceResult<Fragment> {
+    let transposed = Size::new(region.y, region.x);
+    let lines = linebreak(engine, p, transposed.x - p.config.hanging_indent);
+    let lines = finalize(engine, p, &lines, transposed, expand, locator)?.into_frames();
+
+    let leading = p.config.leading;
+    let mut frames = vec![];
+    let mut block: Vec<Frame> = vec![];
+    let mut width = Abs::zero();
+    for line in lines {
+        let line = turn(line);
+        if !block.is_empty() && width + leading + line.width() > region.x {
+            frames.push(stack(std::mem::take(&mut block), leading, region.x));
+        }
+
+        width =
+            if block.is_empty() { line.width() } else { width + leading + line.width() };
+        block.push(line);
+    }
+
+    if !block.is_empty() {
+        frames.push(stack(block, leading, region.x));
+    }
+
+    Ok(Fragment::frames(frames))
+}
+
+/// Stacks turned lines from right to left into one frame. The frame spans the
+/// full width of the region so that the first line is at its right edge.
+fn stack(lines: Vec<Frame>, leading: Abs, width: Abs) -> Frame {
+    let width = if width.is_finite() {
+        width
+    } else {
+        lines.iter().map(Frame::width).sum::<Abs>()
+            + leading * lines.len().saturating_sub(1) as f64
+    };
+    let height = lines.iter().map(Frame::height).max().unwrap_or_default();
+
+    let mut output = Frame::soft(Size::new(width, height));
+    let mut x = width;
+    for line in lines {
+        x -= line.width();
+        output.push_frame(Point::with_x(x), line);
+        x -= leading;
+    }
+
+    output
+}
+
+/// Turns a line that was built horizontally by a quarter clockwise, so that it
+/// starts at the top and its top edge faces right.
+fn turn(mut frame: Frame) -> Frame {
+    let size = frame.size();
+    frame.transform(
+        Transform::translate(size.y, Abs::zero())
+            .pre_concat(Transform::rotate(Angle::deg(90.0))),
+    );
+    frame.set_size(Size::new(size.y, size.x));
+    frame
+}
+
+/// Turns upright content by a quarter counter-clockwise, so that it is upright
+/// again once its line is turned into place.
+///
+/// The baseline of the resulting frame runs through its middle, which is where
+/// lines of vertical text are aligned.
+pub fn upright(mut frame: Frame) -> Frame {
+    let size = frame.size();
+    frame.transform(
+        Transform::translate(Abs::zero(), size.x)
+            .pre_concat(Transform::rotate(Angle::deg(-90.0))),
+    );
+    frame.set_size(Size::new(size.y, size.x));
+    frame.set_baseline(size.x / 2.0);
+    frame
+}
+
+/// Moves the baseline of a rotated text run so that the middle of the font's
+/// em box is on the central baseline of the line.
+pub fn center(frame: &mut Frame, text: &ShapedText) {
+    let Some(glyph) = text.glyphs.first() else { return };
+    let metrics = glyph.font.metrics();
+    let central = ((metrics.ascender + metrics.descender) / 2.0).at(glyph.size);
+    frame.set_baseline(frame.baseline() - central);
+}
+
+/// Whether a character is set upright in vertical text rather than rotated
+/// with the line.
+///
+/// This approximates the `U` and `Tu` values of the Unicode
+/// `Vertical_Orientation` property by the East Asian blocks. Characters like
+/// brackets that need a different form in vertical text are also set upright
+/// and receive it through the font's `vert` feature.
+pub fn is_upright(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{11FF}'
+            | '\u{2E80}'..='\u{2FFF}'
+            | '\u{3000}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{A960}'..='\u{A97F}'
+            | '\u{AC00}'..='\u{D7FF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FE10}'..='\u{FE1F}'
+            | '\u{FE30}'..='\u{FE6F}'
+            | '\u{FF00}'..='\u{FF60}'
+            | '\u{FFE0}'..='\u{FFE7}'
+            | '\u{1B000}'..='\u{1B16F}'
+            | '\u{1F200}'..='\u{1F2FF}'
+            | '\u{20000}'..='\u{3FFFF}'
+    )
+}
+
+/// Whether a text run that would be rotated in vertical text is instead set
+/// horizontally within a single em of the line (tate-chū-yoko). This is the
+/// case for short numbers.
+pub fn is_combined(text: &str) -> bool {
+    !text.is_empty()
+        && text.len() <= MAX_COMBINED_DIGITS
+        && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Arranges horizontally shaped glyphs side by side within a single em of a
+/// vertical line. Glyphs that would together be wider than an em are scaled
+/// down.
+///
+/// Afterwards, the glyphs follow the conventions of vertical text: The advance
+/// and offset are along the line and the vertical offset is across it, relative
+/// to the line's middle.
+pub fn combine(glyphs: &mut [ShapedGlyph], size: Abs) {
+    let width: Abs = glyphs.iter().map(|g| g.x_advance.at(g.size)).sum();
+    let scale = if width > size { size / width } else { 1.0 };
+
+    let last = glyphs.len().saturating_sub(1);
+    let mut x = -width * scale / 2.0;
+    for (i, glyph) in glyphs.iter_mut().enumerate() {
+        let advance = glyph.x_advance.at(glyph.size) * scale;
+        let dx = glyph.x_offset.at(glyph.size) * scale;
+        let dy = glyph.y_offset.at(glyph.size) * scale;
+        glyph.size *= scale;
+
+        // Center the digits within the em.
+        let cap_height = glyph.font.metrics().cap_height.at(glyph.size);
+        let baseline = (size + cap_height) / 2.0;
+
+        glyph.x_advance =
+            if i == last { Em::from_abs(size, glyph.size) } else { Em::zero() };
+        glyph.x_offset = Em::from_abs(baseline - dy, glyph.size);
+        glyph.y_offset = Em::from_abs(x + dx, glyph.size);
+        x += advance;
+    }
+}
@@ -0,0 +1,195 @@
+use typst_library::introspection::SplitLocator;
+use typst_library::layout::{Em, Frame, PlacementWrap, Point};
+
+use super::linebreak::linebreak_variable;
+use super::*;
+
+/// Segments of a line that are narrower than this are left empty.
+const MIN_SEGMENT: Em = Em::new(2.0);
+
+/// The areas a paragraph's lines flow around.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct Exclusions {
+    /// The areas, with vertical positions relative to the top of the
+    /// paragraph.
+    pub areas: Vec<Exclusion>,
+    /// The spacing between the lines.
+    pub leading: Abs,
+    /// A lower bound for the height of the lines. This is used to estimate
+    /// where each line ends up before the lines are built.
+    pub line_height: Abs,
+}
+
+impl Exclusions {
+    /// Whether there are no areas to flow around.
+    pub fn is_empty(&self) -> bool {
+        self.areas.is_empty()
+    }
+}
+
+/// An area next to which lines are shortened, typically occupied by a
+/// wrapped placed element, including its clearance.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub struct Exclusion {
+    /// The top edge of the area.
+    pub top: Abs,
+    /// The bottom edge of the area.
+    pub bottom: Abs,
+    /// The left edge of the area.
+    pub left: Abs,
+    /// The right edge of the area.
+    pub right: Abs,
+    /// On which sides of the area text flows.
+    pub wrap: PlacementWrap,
+}
+
+/// A row of a paragraph next to exclusions.
+struct Row {
+    /// The horizontal offsets and widths of the segments that are free for
+    /// text, in reading order. A row without segments is empty space.
+    segments: Vec<(Abs, Abs)>,
+    /// The minimum height of the row.
+    height: Abs,
+}
+
+/// Breaks the text into lines that flow around the exclusions and turns them
+/// into frames, one per row. Each frame spans the full width of the region.
+#[typst_macros::time]
+pub fn wrap(
+    engine: &mut Engine,
+    p: &Preparation,
+    exclusions: &Exclusions,
+    region: Size,
+    locator: &mut SplitLocator<'_>,
+) -> SourceResult<Fragment> {
+    let rows = rows(p, exclusions, region.x);
+    let slots: Vec<_> =
+        rows.iter().flat_map(|row| row.segments.iter().copied()).collect();
+    let slot = |i: usize| slots.get(i).copied().unwrap_or((Abs::zero(), region.x));
+
+    // Lines next to the exclusions have varying widths.
+    let lines = linebreak_variable(engine, p, |i| slot(i).1 - p.config.hanging_indent);
+
+    let mut frames = Vec::with_capacity(lines.len());
+    let mut lines = lines.iter().enumerate().peekable();
+    for row in &rows {
+        if lines.peek().is_none() {
+            break;
+        }
+
+        let mut segments = vec![];
+        for _ in 0..row.segments.len() {
+            let Some((i, line)) = lines.next() else { break };
+            let (x, width) = slot(i);
+            let frame = commit(engine, p, line, width, region.y, locator)?;
+            segments.push((x, frame));
+        }
+
+        frames.push(stack(region.x, row.height, segments));
+    }
+
+    // The remaining lines are below the exclusions.
+    for (_, line) in lines {
+        frames.push(commit(engine, p, line, region.x, region.y, locator)?);
+    }
+
+    Ok(Fragment::frames(frames))
+}
+
+/// Determines the rows next to the exclusions.
+///
+/// Since the lines aren't built yet, the rows' vertical positions are
+/// estimated from the lower bound for the line height. Rows that end up
+/// further down are then just narrower than necessary.
+fn rows(p: &Preparation, exclusions: &Exclusions, width: Abs) -> Vec<Row> {
+    let Exclusions { areas, leading, line_height } = exclusions;
+    let min = MIN_SEGMENT.at(p.config.font_size);
+    let end = areas.iter().map(|area| area.bottom).max().unwrap_or_default();
+
+    // Without any height, the rows would never progress.
+    if *line_height + *leading <= Abs::zero() {
+        return vec![];
+    }
+
+    let mut rows = vec![];
+    let mut y = Abs::zero();
+    while y < end {
+        let overlapping: Vec<_> = areas
+            .iter()
+            .filter(|area| area.top < y + *line_height && y < area.bottom)
+            .collect();
+
+        let mut segments = free(width, &overlapping);
+        segments.retain(|&(_, width)| width >= min);
+        if p.config.dir == Dir::RTL {
+            segments.reverse();
+        }
+
+        if segments.is_empty() {
+            // Continue below the areas that block the whole row.
+            let next = overlapping.iter().map(|area| area.bottom).min().unwrap_or(end);
+            let height = (next - y - *leading).max(Abs::zero());
+            rows.push(Row { segments, height });
+            y = next;
+        } else {
+            rows.push(Row { segments, height: *line_height });
+            y += *line_height + *leading;
+        }
+    }
+
+    rows
+}
+
+/// Determines the horizontal segments that the areas leave free.
+fn free(width: Abs, areas: &[&Exclusion]) -> Vec<(Abs, Abs)> {
+    let mut blocked: Vec<_> = areas
+        .iter()
+        .map(|area| match area.wrap {
+            PlacementWrap::Left => (area.left, width),
+            PlacementWrap::Right => (Abs::zero(), area.right),
+            PlacementWrap::Both => (area.left, area.right),
+        })
+        .collect();
+    blocked.sort_by_key(|&(start, _)| start);
+
+    let mut segments = vec![];
+    let mut x = Abs::zero();
+    for (start, end) in blocked {
+        if start > x && x < width {
+            segments.push((x, start.min(width) - x));
+        }
+        x.set_max(end);
+    }
+
+    if x < width {
+        segments.push((x, width - x));
+    }
+
+    segments
+}
+
+/// Stacks the lines of a row into one frame, aligning their baselines.
+fn stack(width: Abs, height: Abs, segments: Vec<(Abs, Frame)>) -> Frame {
+    let top = segments
+        .iter()
+        .map(|(_, frame)| frame.baseline())
+        .max()
+        .unwrap_or_default();
+    let bottom = segments
+        .iter()
+        .map(|(_, frame)| frame.height() - frame.baseline())
+        .max()
+        .unwrap_or_default();
+
+    let mut output = Frame::soft(Size::new(width, (top + bottom).max(height)));
+    if !segments.is_empty() {
+        output.set_baseline(top);
+    }
+
+    for (x, frame) in segments {
+        let y = top - frame.baseline();
+        output.push_frame(Point::new(x, y), frame);
+    }
+
+    output
+}
@@ -1,7 +1,9 @@
 //! Typst's layout engine.
 
+mod annotation;
 mod document;
 mod flow;
+mod form;
 mod grid;
 mod image;
 mod inline;
@@ -1,6 +1,8 @@
-use typst_library::foundations::StyleChain;
+use typst_library::foundations::{StyleChain, Styles};
+use typst_library::introspection::Location;
 use typst_library::layout::{Abs, Fragment, Frame, FrameItem, HideElem, Point, Sides};
 use typst_library::model::{Destination, LinkElem, ParElem};
+use typst_library::pdf::{AnnotationElem, Layer, LayerElem};
 
 /// Frame-level modifications resulting from styles that do not impose any
 /// layout structure.
@@ -17,12 +19,18 @@ use typst_library::model::{Destination, LinkElem, ParElem};
 /// Currently existing frame modifiers are:
 /// - `HideElem::hidden`
 /// - `LinkElem::dests`
+/// - `AnnotationElem::current`
+/// - `LayerElem::current`
 #[derive(Debug, Clone)]
 pub struct FrameModifiers {
     /// A destination to link to.
     dest: Option<Destination>,
+    /// An annotation that refers to the contents of the frame.
+    annotation: Option<Location>,
     /// Whether the contents of the frame should be hidden.
     hidden: bool,
+    /// The PDF layer the contents of the frame belong to.
+    layer: Option<Layer>,
 }
 
 impl FrameModifiers {
@@ -30,7 +38,9 @@ impl FrameModifiers {
     pub fn get_in(styles: StyleChain) -> Self {
         Self {
             dest: styles.get_cloned(LinkElem::current),
+            annotation: styles.get(AnnotationElem::current),
             hidden: styles.get(HideElem::hidden),
+            layer: styles.get_cloned(LayerElem::current),
         }
     }
 }
@@ -94,20 +104,29 @@ fn modify_frame(
     modifiers: &FrameModifiers,
     link_box_outset: Option<Sides<Abs>>,
 ) {
+    let mut pos = Point::zero();
+    let mut size = frame.size();
+    if let Some(outset) = link_box_outset {
+        pos.y -= outset.top;
+        pos.x -= outset.left;
+        size += outset.sum_by_axis();
+    }
+
     if let Some(dest) = &modifiers.dest {
-        let mut pos = Point::zero();
-        let mut size = frame.size();
-        if let Some(outset) = link_box_outset {
-            pos.y -= outset.top;
-            pos.x -= outset.left;
-            size += outset.sum_by_axis();
-        }
         frame.push(pos, FrameItem::Link(dest.clone(), size));
     }
 
+    if let Some(location) = modifiers.annotation {
+        frame.push(pos, FrameItem::Annotation(location, size));
+    }
+
     if modifiers.hidden {
         frame.hide();
     }
+
+    if let Some(layer) = &modifiers.layer {
+        frame.layer(layer.clone());
+    }
 }
 
 /// Performs layout and modification in one step.
@@ -123,16 +142,22 @@ where
 {
     let modifiers = FrameModifiers::get_in(styles);
 
-    // Disable the current link internally since it's already applied at this
-    // level of layout. This means we don't generate redundant nested links,
-    // which may bloat the output considerably.
-    let reset;
-    let outer = styles;
-    let mut styles = styles;
+    // Disable the current link, annotation, and layer internally since they
+    // are already applied at this level of layout. This means we don't
+    // generate redundant nested links, which may bloat the output
+    // considerably.
+    let mut reset = Styles::new();
     if modifiers.dest.is_some() {
-        reset = LinkElem::current.set(None).wrap();
-        styles = outer.chain(&reset);
+        reset.set(LinkElem::current, None);
+    }
+    if modifiers.annotation.is_some() {
+        reset.set(AnnotationElem::current, None);
     }
+    if modifiers.layer.is_some() {
+        reset.set(LayerElem::current, None);
+    }
+
+    let styles = if reset.is_empty() { styles } else { styles.chain(&reset) };
 
     layout(styles).modified(&modifiers)
 }
@@ -4,6 +4,7 @@ use typst_library::introspection::{ManualPageCounter, Tag};
 use typst_library::layout::{Frame, FrameItem, Point};
 
 use super::LayoutedPage;
+use super::marks::draw_marks;
 use crate::Page;
 
 /// Piece together the inner page frame and the marginals. We can only do this
@@ -19,11 +20,14 @@ pub fn finalize(
         margin_two_sided,
         mut bleed,
         bleed_two_sided,
+        marks,
+        slug,
         binding,
         header,
         footer,
         background,
         foreground,
+        sidenotes,
         fill,
         numbering,
         supplement,
@@ -63,6 +67,19 @@ pub fn finalize(
     // Add the inner contents.
     frame.push_frame(Point::new(margin.left, margin.top), inner);
 
+    // Add the sidenotes to the outer margin. With two-sided margins, it is on
+    // the left exactly if the margins were swapped. Otherwise, the notes are
+    // in the wider margin.
+    if let Some(sidenotes) = sidenotes {
+        let left = if margin_two_sided { swap } else { margin.left > margin.right };
+        let x = if left {
+            margin.left - sidenotes.width()
+        } else {
+            frame.width() - margin.right
+        };
+        frame.push_frame(Point::new(x, margin.top), sidenotes);
+    }
+
     // Add the "after" marginals.
     if let Some(footer) = footer {
         let y = frame.height() - footer.height();
@@ -72,6 +89,9 @@ pub fn finalize(
         frame.push_frame(bleed_origin, foreground);
     }
 
+    // Draw the printer's marks and the slug outside of the bleed.
+    let marks = draw_marks(&mut frame, bleed, marks, slug);
+
     // Apply counter updates from within the page to the manual page counter.
     counter.visit(engine, &frame)?;
 
@@ -79,5 +99,13 @@ pub fn finalize(
     let number = counter.logical();
     counter.step();
 
-    Ok(Page { frame, bleed, fill, numbering, supplement, number })
+    Ok(Page {
+        frame,
+        bleed,
+        marks,
+        fill,
+        numbering,
+        supplement,
+        number,
+    })
 }
@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use typst_library::layout::{
+    Abs, Frame, FrameItem, PageMarks, Point, Ratio, Sides, Size,
+};
+use typst_library::visualize::{
+    Cmyk, Color, Curve, FixedStroke, Geometry, ProcessColor, SpotColor, SpotColorant,
+    SpotColorantName,
+};
+use typst_syntax::Span;
+
+/// The distance between the bleed and the marks.
+const GAP: f64 = 3.0;
+
+/// The length of crop marks and the size of registration targets.
+const LENGTH: f64 = 18.0;
+
+/// The thickness of the marks' lines.
+const THICKNESS: f64 = 0.25;
+
+/// The inks of the color bar patches, from left to right.
+const BARS: [[f32; 4]; 10] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+    [1.0, 1.0, 0.0, 0.0],
+    [1.0, 0.0, 1.0, 0.0],
+    [0.0, 1.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 0.75],
+    [0.0, 0.0, 0.0, 0.5],
+    [0.0, 0.0, 0.0, 0.25],
+];
+
+/// The space available to the slug below a page of the given size.
+///
+/// The slug starts right of the bottom left crop mark and ends before the
+/// bottom registration target or the bottom right crop mark.
+pub fn slug_size(size: Size, marks: PageMarks) -> Size {
+    let (gap, length) = (Abs::pt(GAP), Abs::pt(LENGTH));
+    let end = if marks.registration { size.x / 2.0 - length / 2.0 } else { size.x };
+    Size::new((end - 2.0 * gap).max(Abs::zero()), length)
+}
+
+/// Draws printer's marks and the slug around the bleed of a page frame and
+/// returns the area they take up beyond the bleed.
+pub fn draw_marks(
+    frame: &mut Frame,
+    bleed: Sides<Abs>,
+    marks: PageMarks,
+    slug: Option<Frame>,
+) -> Sides<Abs> {
+    if !marks.any() && slug.is_none() {
+        return Sides::splat(Abs::zero());
+    }
+
+    let gap = Abs::pt(GAP);
+    let length = Abs::pt(LENGTH);
+    let size = frame.size();
+    let stroke = FixedStroke::from_pair(registration_color(), Abs::pt(THICKNESS));
+    let line = |frame: &mut Frame, from: Point, delta: Point| {
+        let shape = Geometry::Line(delta).stroked(stroke.clone());
+        frame.push(from, FrameItem::Shape(shape, Span::detached()));
+    };
+
+    // The distance from the trim edge to the center of the marks on each
+    // side.
+    let center = bleed.map(|bleed| bleed + gap + length / 2.0);
+
+    if marks.crop {
+        for (x, sx, bx) in [(Abs::zero(), -1.0, bleed.left), (size.x, 1.0, bleed.right)] {
+            for (y, sy, by) in
+                [(Abs::zero(), -1.0, bleed.top), (size.y, 1.0, bleed.bottom)]
+            {
+                let h = Point::new(x + sx * (bx + gap), y);
+                line(frame, h, Point::with_x(sx * length));
+                let v = Point::new(x, y + sy * (by + gap));
+                line(frame, v, Point::with_y(sy * length));
+            }
+        }
+    }
+
+    if marks.registration {
+        for target in [
+            Point::new(size.x / 2.0, -center.top),
+            Point::new(size.x / 2.0, size.y + center.bottom),
+            Point::new(-center.left, size.y / 2.0),
+            Point::new(size.x + center.right, size.y / 2.0),
+        ] {
+            let half = length / 2.0;
+            line(frame, target - Point::with_x(half), Point::with_x(length));
+            line(frame, target - Point::with_y(half), Point::with_y(length));
+
+            let diameter = length * (2.0 / 3.0);
+            let circle = Geometry::Curve(Curve::ellipse(Size::splat(diameter)))
+                .stroked(stroke.clone());
+            let pos = target - Point::splat(diameter / 2.0);
+            frame.push(pos, FrameItem::Shape(circle, Span::detached()));
+        }
+    }
+
+    if marks.bars {
+        // The bars start right of the top registration target and stop before
+        // the top right crop mark.
+        let patch = length / 2.0;
+        let mut x = size.x / 2.0 + length / 2.0 + gap;
+        let top = -center.top - patch / 2.0;
+        for [c, m, y, k] in BARS {
+            if x + patch > size.x - gap {
+                break;
+            }
+
+            let color = Color::Process(ProcessColor::Cmyk(Cmyk { c, m, y, k }));
+            let shape = Geometry::Rect(Size::splat(patch)).filled(color);
+            frame.push(Point::new(x, top), FrameItem::Shape(shape, Span::detached()));
+            x += patch;
+        }
+    }
+
+    if let Some(slug) = slug {
+        frame.push_frame(Point::new(gap, size.y + bleed.bottom + gap), slug);
+    }
+
+    Sides::splat(gap + length + gap)
+}
+
+/// The color of the marks, which appears on all printing plates.
+fn registration_color() -> Color {
+    let colorant = SpotColorant {
+        name: Some(SpotColorantName::All),
+        fallback: ProcessColor::Cmyk(Cmyk { c: 1.0, m: 1.0, y: 1.0, k: 1.0 }),
+    };
+    Color::Spot(SpotColor::new(Arc::new(colorant), Ratio::one()))
+}
@@ -2,6 +2,7 @@
 
 mod collect;
 mod finalize;
+mod marks;
 mod run;
 
 use comemo::{Track, Tracked, TrackedMut};
@@ -9,8 +9,8 @@ use typst_library::introspection::{
 };
 use typst_library::layout::{
     Abs, AlignElem, Alignment, Axes, Binding, ColumnsElem, Dir, Frame, HAlignment,
-    Length, OuterVAlignment, PageElem, Paper, Region, Regions, Rel, Sides, Size,
-    VAlignment,
+    Length, OuterVAlignment, PageElem, PageMarks, Paper, Region, Regions, Rel, Sides,
+    Size, VAlignment,
 };
 use typst_library::model::Numbering;
 use typst_library::pdf::ArtifactKind;
@@ -20,7 +20,8 @@ use typst_library::visualize::Paint;
 use typst_library::{Library, World};
 use typst_utils::{LazyHash, Numeric, Protected};
 
-use crate::flow::{ColumnOptions, FlowMode, layout_flow};
+use super::marks::slug_size;
+use crate::flow::{ColumnOptions, layout_root_flow};
 
 /// A mostly finished layout for one page. Needs only knowledge of its exact
 /// page number to be finalized into a `Page`. (Because the margins can depend
@@ -32,11 +33,14 @@ pub struct LayoutedPage {
     pub margin_two_sided: bool,
     pub bleed: Sides<Abs>,
     pub bleed_two_sided: bool,
+    pub marks: PageMarks,
+    pub slug: Option<Frame>,
     pub binding: Binding,
     pub header: Option<Frame>,
     pub footer: Option<Frame>,
     pub background: Option<Frame>,
     pub foreground: Option<Frame>,
+    pub sidenotes: Option<Frame>,
     pub fill: Smart<Option<Paint>>,
     pub numbering: Option<Numbering>,
     pub supplement: Content,
@@ -135,6 +139,8 @@ fn layout_page_run_impl(
         .resolve(styles)
         .relative_to(size);
 
+    let marks = styles.get(PageElem::marks);
+    let slug = styles.get_ref(PageElem::slug);
     let fill = styles.get_cloned(PageElem::fill);
     let foreground = styles.get_ref(PageElem::foreground);
     let background = styles.get_ref(PageElem::background);
@@ -148,9 +154,12 @@ fn layout_page_run_impl(
     };
     let number_align = styles.get(PageElem::number_align);
     let binding = styles.get(PageElem::binding).unwrap_or_else(|| {
+        // Vertical text is read from right to left, so it is bound on the
+        // right, like right-to-left text.
         match styles.resolve(TextElem::dir) {
             Dir::LTR => Binding::Left,
-            _ => Binding::Right,
+            Dir::RTL | Dir::TTB => Binding::Right,
+            Dir::BTT => unreachable!("text direction cannot be bottom-to-top"),
         }
     });
 
@@ -185,9 +194,14 @@ fn layout_page_run_impl(
         (header.as_ref().unwrap_or(&None), footer.as_ref().unwrap_or(&numbering_marginal))
     };
 
+    // Sidenotes go into the outer margin. With two-sided margins, that's the
+    // right one before swapping. Otherwise, it's the wider one.
+    let outside =
+        if margin_two_sided { margin.right } else { margin.left.max(margin.right) };
+
     // Layout the children.
     let area = size - margin.sum_by_axis();
-    let fragment = layout_flow(
+    let regions = layout_root_flow(
         &mut engine,
         children,
         &mut locator,
@@ -198,7 +212,7 @@ fn layout_page_run_impl(
             balanced: styles.get(ColumnsElem::balanced),
             gutter: styles.get(ColumnsElem::gutter).resolve(styles),
         },
-        FlowMode::Root,
+        outside,
     )?;
 
     // Layouts a single marginal.
@@ -216,16 +230,18 @@ fn layout_page_run_impl(
     };
 
     // Layout marginals.
-    let mut layouted = Vec::with_capacity(fragment.len());
+    let mut layouted = Vec::with_capacity(regions.len());
 
     let header = header.clone().map(|h| h.artifact(ArtifactKind::Header));
     let footer = footer.clone().map(|f| f.artifact(ArtifactKind::Footer));
     let background = background.clone().map(|b| b.artifact(ArtifactKind::Background));
+    let slug = slug.clone().map(|s| s.artifact(ArtifactKind::Page));
 
-    for inner in fragment {
+    for (inner, sidenotes) in regions {
         let header_size = Size::new(inner.width(), margin.top - header_ascent);
         let footer_size = Size::new(inner.width(), margin.bottom - footer_descent);
         let full_size = inner.size() + margin.sum_by_axis() + bleed.sum_by_axis();
+        let slug_area = slug_size(inner.size() + margin.sum_by_axis(), marks);
         let mid = HAlignment::Center + VAlignment::Horizon;
         layouted.push(LayoutedPage {
             inner,
@@ -236,10 +252,13 @@ fn layout_page_run_impl(
             footer: layout_marginal(&footer, footer_size, Alignment::TOP)?,
             background: layout_marginal(&background, full_size, mid)?,
             foreground: layout_marginal(foreground, full_size, mid)?,
+            slug: layout_marginal(&slug, slug_area, HAlignment::Left + VAlignment::Top)?,
+            sidenotes,
             margin,
             margin_two_sided,
             bleed,
             bleed_two_sided,
+            marks,
             binding,
         });
     }
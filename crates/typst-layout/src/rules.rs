@@ -6,23 +6,28 @@ use typst_library::foundations::{
     Content, Context, NativeElement, NativeRuleMap, Packed, Resolve, ShowFn, Smart,
     StyleChain, Synthesize, Target, dict,
 };
-use typst_library::introspection::{Counter, Locator, LocatorLink};
+use typst_library::introspection::{Counter, Locator, LocatorLink, QueryIntrospection};
 use typst_library::layout::{
     Abs, AlignElem, Alignment, Axes, BlockBody, BlockElem, ColumnsElem, Em,
     FixedAlignment, GridCell, GridChild, GridElem, GridItem, HAlignment, HElem, HideElem,
     InlineElem, LayoutElem, Length, MoveElem, OuterVAlignment, PadElem, PageElem,
-    PlaceElem, PlacementScope, Region, Rel, RepeatElem, RotateElem, ScaleElem, Sides,
-    Size, Sizing, SkewElem, Spacing, StackChild, StackElem, TrackSizings, VElem,
+    PlaceElem, PlacementScope, PlacementWrap, Region, Rel, RepeatElem, RotateElem,
+    ScaleElem, Sides, Size, Sizing, SkewElem, Spacing, StackChild, StackElem,
+    TrackSizings, VElem,
 };
 use typst_library::math::EquationElem;
 use typst_library::model::{
     Attribution, BibliographyElem, CiteElem, CiteGroup, CslIndentElem, CslLightElem,
     Destination, DirectLinkElem, DividerElem, EmphElem, EnumElem, FigureCaption,
     FigureElem, FootnoteElem, FootnoteEntry, HeadingElem, LinkElem, LinkMarker, ListElem,
-    OutlineElem, OutlineEntry, ParElem, ParbreakElem, QuoteElem, RefElem, StrongElem,
-    TableCell, TableElem, TermsElem, TitleElem, Works,
+    OutlineElem, OutlineEntry, ParElem, ParbreakElem, QuoteElem, RefElem, SidenoteElem,
+    SidenoteEntry, StrongElem, TableCell, TableElem, TermsElem, TitleElem, Works,
+};
+use typst_library::pdf::{
+    AnnotationElem, ArtifactElem, ArtifactKind, AttachElem, ButtonElem, CheckboxElem,
+    ChoiceElem, Layer, LayerElem, PageLabelElem, PdfMarkerTag, RadioElem,
+    SignatureFieldElem, TextFieldElem,
 };
-use typst_library::pdf::{ArtifactElem, ArtifactKind, AttachElem, PdfMarkerTag};
 use typst_library::text::{
     DecoLine, Decoration, HighlightElem, ItalicToggle, LinebreakElem, LocalName,
     OverlineElem, RawElem, RawLine, ScriptKind, ShiftSettings, Smallcaps, SmallcapsElem,
@@ -56,6 +61,8 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Paged, QUOTE_RULE);
     rules.register(Paged, FOOTNOTE_RULE);
     rules.register(Paged, FOOTNOTE_ENTRY_RULE);
+    rules.register(Paged, SIDENOTE_RULE);
+    rules.register(Paged, SIDENOTE_ENTRY_RULE);
     rules.register(Paged, OUTLINE_RULE);
     rules.register(Paged, OUTLINE_ENTRY_RULE);
     rules.register(Paged, REF_RULE);
@@ -109,6 +116,15 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Paged, ATTACH_RULE);
     rules.register(Paged, ARTIFACT_RULE);
     rules.register(Paged, PDF_MARKER_TAG_RULE);
+    rules.register(Paged, TEXT_FIELD_RULE);
+    rules.register(Paged, CHECKBOX_RULE);
+    rules.register(Paged, RADIO_RULE);
+    rules.register(Paged, CHOICE_RULE);
+    rules.register(Paged, BUTTON_RULE);
+    rules.register(Paged, SIGNATURE_FIELD_RULE);
+    rules.register(Paged, ANNOTATION_RULE);
+    rules.register(Paged, LAYER_RULE);
+    rules.register(Paged, PAGE_LABEL_RULE);
 }
 
 const STRONG_RULE: ShowFn<StrongElem> = |elem, _, styles| {
@@ -335,7 +351,16 @@ const FIGURE_RULE: ShowFn<FigureElem> = |elem, _, styles| {
     realized = BlockElem::packed(realized).spanned(span);
 
     // Wrap in a float.
+    let wrap = elem.wrap.get(styles);
     if let Some(align) = elem.placement.get(styles) {
+        if wrap.is_some() {
+            bail!(
+                span,
+                "floating figures cannot wrap text";
+                hint: "you can disable floating placement with `figure(placement: none, ..)`";
+            );
+        }
+
         realized = PlaceElem::new(realized)
             .with_alignment(align.map(|align| HAlignment::Center + align))
             .with_scope(elem.scope.get(styles))
@@ -348,6 +373,19 @@ const FIGURE_RULE: ShowFn<FigureElem> = |elem, _, styles| {
             "parent-scoped placement is only available for floating figures";
             hint: "you can enable floating placement with `figure(placement: auto, ..)`";
         );
+    } else if let Some(wrap) = wrap {
+        // The figure goes to the side where the text doesn't flow.
+        let align = match wrap {
+            PlacementWrap::Left => HAlignment::Right,
+            PlacementWrap::Right => HAlignment::Left,
+            PlacementWrap::Both => HAlignment::Center,
+        };
+
+        realized = PlaceElem::new(realized)
+            .with_alignment(Smart::Custom(align.into()))
+            .with_wrap(Some(wrap))
+            .pack()
+            .spanned(span);
     }
 
     Ok(realized)
@@ -411,6 +449,23 @@ const FOOTNOTE_ENTRY_RULE: ShowFn<FootnoteEntry> = |elem, engine, styles| {
     ]))
 };
 
+const SIDENOTE_RULE: ShowFn<SidenoteElem> = |elem, engine, styles| {
+    // The sidenote number that links to the sidenote entry.
+    let link = elem.realize(engine, styles)?;
+    let sup = SuperElem::new(link).pack().spanned(elem.span());
+    Ok(HElem::hole().clone() + PdfMarkerTag::Label(sup))
+};
+
+const SIDENOTE_ENTRY_RULE: ShowFn<SidenoteEntry> = |elem, engine, styles| {
+    let number_gap = Em::new(0.05);
+    let (sup, body) = elem.realize(engine, styles)?;
+    Ok(Content::sequence([
+        PdfMarkerTag::Label(sup),
+        HElem::new(number_gap.into()).with_weak(true).pack(),
+        body,
+    ]))
+};
+
 const OUTLINE_RULE: ShowFn<OutlineElem> = |elem, engine, styles| {
     let title = elem.realize_title(styles);
     let entries = elem.realize_flat(engine, styles)?;
@@ -826,3 +881,57 @@ const ATTACH_RULE: ShowFn<AttachElem> = |_, _, _| Ok(Content::empty());
 const ARTIFACT_RULE: ShowFn<ArtifactElem> = |elem, _, _| Ok(elem.body.clone());
 
 const PDF_MARKER_TAG_RULE: ShowFn<PdfMarkerTag> = |elem, _, _| Ok(elem.body.clone());
+
+const TEXT_FIELD_RULE: ShowFn<TextFieldElem> = |elem, _, _| {
+    Ok(InlineElem::layouter(elem.clone(), crate::form::layout_text_field).pack())
+};
+
+const CHECKBOX_RULE: ShowFn<CheckboxElem> = |elem, _, _| {
+    Ok(InlineElem::layouter(elem.clone(), crate::form::layout_checkbox).pack())
+};
+
+const RADIO_RULE: ShowFn<RadioElem> =
+    |elem, _, _| Ok(InlineElem::layouter(elem.clone(), crate::form::layout_radio).pack());
+
+const CHOICE_RULE: ShowFn<ChoiceElem> = |elem, _, _| {
+    Ok(InlineElem::layouter(elem.clone(), crate::form::layout_choice).pack())
+};
+
+const BUTTON_RULE: ShowFn<ButtonElem> = |elem, _, _| {
+    Ok(InlineElem::layouter(elem.clone(), crate::form::layout_button).pack())
+};
+
+const SIGNATURE_FIELD_RULE: ShowFn<SignatureFieldElem> = |elem, _, _| {
+    Ok(InlineElem::layouter(elem.clone(), crate::form::layout_signature_field).pack())
+};
+
+const ANNOTATION_RULE: ShowFn<AnnotationElem> = |elem, _, styles| {
+    Ok(match elem.body.get_cloned(styles) {
+        Some(body) => body.set(AnnotationElem::current, elem.location()),
+        None => InlineElem::layouter(elem.clone(), crate::annotation::layout_annotation)
+            .pack(),
+    })
+};
+
+const LAYER_RULE: ShowFn<LayerElem> = |elem, engine, styles| {
+    // The first layer element with a name determines the visibility of the
+    // whole layer, so that all export formats agree.
+    let visible = engine
+        .introspect(QueryIntrospection(LayerElem::ELEM.select(), elem.span()))
+        .iter()
+        .filter_map(|other| other.to_packed::<LayerElem>())
+        .find(|other| other.name == elem.name)
+        .map_or_else(
+            || elem.visible.get(styles),
+            |first| first.visible.get(StyleChain::default()),
+        );
+
+    let layer = Layer {
+        name: elem.name.clone(),
+        visible,
+        span: elem.span(),
+    };
+    Ok(elem.body.clone().set(LayerElem::current, Some(layer)))
+};
+
+const PAGE_LABEL_RULE: ShowFn<PageLabelElem> = |_, _, _| Ok(Content::empty());
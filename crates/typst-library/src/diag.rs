@@ -319,6 +319,9 @@ pub struct SourceDiagnostic {
     /// - When a span is given, the hint is related to a secondary piece of code
     ///   and will be annotated at that code.
     pub hints: EcoVec<Spanned<EcoString, DiagSpan>>,
+    /// Structured suggestions for resolving the problem, which IDEs can offer
+    /// as quick fixes.
+    pub fixes: EcoVec<Fix>,
 }
 
 /// The severity of a [`SourceDiagnostic`].
@@ -339,6 +342,7 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            fixes: eco_vec![],
         }
     }
 
@@ -350,6 +354,7 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            fixes: eco_vec![],
         }
     }
 
@@ -389,6 +394,31 @@ impl SourceDiagnostic {
         self
     }
 
+    /// Adds a single fix to the diagnostic.
+    pub fn fix(&mut self, fix: Fix) {
+        self.fixes.push(fix);
+    }
+
+    /// Adds a single fix to the diagnostic.
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix(fix);
+        self
+    }
+
+    /// Adds multiple fixes to the diagnostic.
+    ///
+    /// Edits with a detached span are resolved to the diagnostic's span.
+    pub fn with_fixes(mut self, fixes: impl IntoIterator<Item = Fix>) -> Self {
+        let span = self.span;
+        self.fixes.extend(fixes.into_iter().map(|mut fix| {
+            for edit in fix.edits.make_mut() {
+                edit.span = edit.span.or(span);
+            }
+            fix
+        }));
+        self
+    }
+
     /// Adds a single tracepoint to the diagnostic.
     pub fn with_tracepoint(mut self, tracepoint: Tracepoint, span: Span) -> Self {
         self.trace.push(Spanned::new(tracepoint, span));
@@ -405,10 +435,118 @@ impl From<SyntaxDiagnostic> for SourceDiagnostic {
             message,
             trace: eco_vec![],
             hints,
+            fixes: eco_vec![],
         }
     }
 }
 
+/// A structured suggestion for resolving a [`SourceDiagnostic`], consisting
+/// of one or more edits to the source code.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Fix {
+    /// A short description of the fix, e.g. ``replace with `fill` ``.
+    pub title: EcoString,
+    /// The edits that make up the fix. They must not overlap.
+    pub edits: EcoVec<FixEdit>,
+}
+
+impl Fix {
+    /// Create a fix without any edits.
+    pub fn new(title: impl Into<EcoString>) -> Self {
+        Self { title: title.into(), edits: eco_vec![] }
+    }
+
+    /// Create a fix that replaces the code at the span with the text.
+    pub fn replace(
+        title: impl Into<EcoString>,
+        span: impl Into<DiagSpan>,
+        text: impl Into<EcoString>,
+    ) -> Self {
+        Self::new(title).with_edit(FixEdit::new(span, FixKind::Replace, text))
+    }
+
+    /// Create a fix that inserts the text before the code at the span.
+    pub fn insert(
+        title: impl Into<EcoString>,
+        span: impl Into<DiagSpan>,
+        text: impl Into<EcoString>,
+    ) -> Self {
+        Self::new(title).with_edit(FixEdit::new(span, FixKind::InsertBefore, text))
+    }
+
+    /// Create a fix that surrounds the code at the span with the two texts.
+    pub fn wrap(
+        title: impl Into<EcoString>,
+        span: impl Into<DiagSpan>,
+        before: impl Into<EcoString>,
+        after: impl Into<EcoString>,
+    ) -> Self {
+        let span = span.into();
+        Self::new(title)
+            .with_edit(FixEdit::new(span, FixKind::InsertBefore, before))
+            .with_edit(FixEdit::new(span, FixKind::InsertAfter, after))
+    }
+
+    /// Create a fix that imports the name from one of the modules that the
+    /// file imports items from.
+    ///
+    /// Which modules provide the name can only be determined by analyzing the
+    /// file, so IDEs resolve this fix themselves.
+    pub fn import(name: impl Into<EcoString>) -> Self {
+        let name = name.into();
+        Self::new(eco_format!("import `{name}`")).with_edit(FixEdit::new(
+            Span::detached(),
+            FixKind::Import,
+            name,
+        ))
+    }
+
+    /// Adds an edit to the fix.
+    pub fn with_edit(mut self, edit: FixEdit) -> Self {
+        self.edits.push(edit);
+        self
+    }
+}
+
+/// A single edit that is part of a [`Fix`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FixEdit {
+    /// The code the edit applies to.
+    ///
+    /// For fixes attached to a [`HintedString`], a detached span stands for
+    /// the span at which the error is eventually reported.
+    pub span: DiagSpan,
+    /// How the text is applied to the code at the span.
+    pub kind: FixKind,
+    /// The text to insert.
+    pub text: EcoString,
+}
+
+impl FixEdit {
+    /// Create a new edit.
+    pub fn new(
+        span: impl Into<DiagSpan>,
+        kind: FixKind,
+        text: impl Into<EcoString>,
+    ) -> Self {
+        Self { span: span.into(), kind, text: text.into() }
+    }
+}
+
+/// How a [`FixEdit`] is applied.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FixKind {
+    /// Replace the code at the span with the text.
+    Replace,
+    /// Insert the text right before the code at the span.
+    InsertBefore,
+    /// Insert the text right after the code at the span.
+    InsertAfter,
+    /// Import the text, a name, from a module that is imported elsewhere. The
+    /// span is the one of the identifier that refers to the name.
+    Import,
+}
+
 /// Destination for a warning message.
 pub trait WarningSink {
     /// Emits the message as a warning.
@@ -506,21 +644,23 @@ where
 /// create an error for this type is with the `bail!` macro.
 pub type HintedStrResult<T> = Result<T, HintedString>;
 
-/// A string message with hints. The recommended way to create one is with the
-/// `error!` macro.
+/// A string message with hints and fixes. The recommended way to create one is
+/// with the `error!` macro.
 ///
-/// This is internally represented by a vector of strings.
+/// The message and hints are internally represented by a vector of strings.
 /// - The first element of the vector contains the message.
 /// - The remaining elements are the hints.
 /// - This is done to reduce the size of a [`HintedString`].
 /// - The vector is guaranteed to not be empty.
+///
+/// Fixes are rare, so they are boxed to keep the type small.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct HintedString(EcoVec<EcoString>);
+pub struct HintedString(EcoVec<EcoString>, Option<Box<Vec<Fix>>>);
 
 impl HintedString {
     /// Creates a new hinted string with the given message.
     pub fn new(message: EcoString) -> Self {
-        Self(eco_vec![message])
+        Self(eco_vec![message], None)
     }
 
     /// A diagnostic message describing the problem.
@@ -534,6 +674,11 @@ impl HintedString {
         self.0.get(1..).unwrap_or(&[])
     }
 
+    /// Structured suggestions for resolving the problem.
+    pub fn fixes(&self) -> &[Fix] {
+        self.1.as_deref().map_or(&[], Vec::as_slice)
+    }
+
     /// Adds a single hint to the hinted string.
     pub fn hint(&mut self, hint: impl Into<EcoString>) {
         self.0.push(hint.into());
@@ -550,6 +695,34 @@ impl HintedString {
         self.0.extend(hints);
         self
     }
+
+    /// Adds a single fix to the hinted string.
+    ///
+    /// Edits with a detached span apply to the span at which the error is
+    /// eventually reported.
+    pub fn fix(&mut self, fix: Fix) {
+        self.1.get_or_insert_default().push(fix);
+    }
+
+    /// Adds a single fix to the hinted string.
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix(fix);
+        self
+    }
+
+    /// Turns the hinted string into a diagnostic with the given severity
+    /// at the given span.
+    pub fn into_diagnostic(self, severity: Severity, span: Span) -> SourceDiagnostic {
+        let HintedString(components, fixes) = self;
+        let mut components = components.into_iter();
+        let message = components.next().unwrap();
+        let diag = match severity {
+            Severity::Error => SourceDiagnostic::error(span, message),
+            Severity::Warning => SourceDiagnostic::warning(span, message),
+        };
+        diag.with_hints(components)
+            .with_fixes(fixes.into_iter().flat_map(|fixes| *fixes))
+    }
 }
 
 impl<S> From<S> for HintedString
@@ -563,12 +736,7 @@ where
 
 impl<T> At<T> for HintedStrResult<T> {
     fn at(self, span: Span) -> SourceResult<T> {
-        self.map_err(|err| {
-            let mut components = err.0.into_iter();
-            let message = components.next().unwrap();
-            let diag = SourceDiagnostic::error(span, message).with_hints(components);
-            eco_vec![diag]
-        })
+        self.map_err(|err| eco_vec![err.into_diagnostic(Severity::Error, span)])
     }
 }
 
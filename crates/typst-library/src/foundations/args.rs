@@ -4,9 +4,9 @@ use std::slice;
 
 use comemo::Tracked;
 use ecow::{EcoString, EcoVec, eco_format, eco_vec};
-use typst_syntax::{Span, Spanned};
+use typst_syntax::{DiagSpan, Span, Spanned, SubRange};
 
-use crate::diag::{At, SourceDiagnostic, SourceResult, StrResult, bail, error};
+use crate::diag::{At, Fix, SourceDiagnostic, SourceResult, StrResult, bail, error};
 use crate::engine::Engine;
 use crate::foundations::{
     Array, Context, Dict, FromValue, Func, IntoValue, Repr, Str, Value, cast, func, repr,
@@ -257,14 +257,65 @@ impl Args {
     /// Return an "unexpected argument" error if there is any remaining
     /// argument.
     pub fn finish(self) -> SourceResult<()> {
-        if let Some(arg) = self.items.first() {
-            match &arg.name {
-                Some(name) => bail!(arg.span, "unexpected argument: {name}"),
-                _ => bail!(arg.span, "unexpected argument"),
-            }
+        self.finish_with_params::<&str>([])
+    }
+
+    /// Like [`finish`](Self::finish), but for an unexpected named argument,
+    /// suggests a fix that replaces its name with the most similar of the
+    /// given parameter names.
+    pub fn finish_with_params<S: AsRef<str>>(
+        self,
+        params: impl IntoIterator<Item = S>,
+    ) -> SourceResult<()> {
+        let Some(arg) = self.items.first() else { return Ok(()) };
+        let Some(name) = &arg.name else {
+            bail!(arg.span, "unexpected argument");
+        };
+
+        let mut diag = error!(arg.span, "unexpected argument: {name}");
+        // Only a named argument written out at the call site starts with its
+        // name. For spread arguments, the value shares the argument's span.
+        if !arg.span.is_detached()
+            && arg.value.span != arg.span
+            && let Some(param) = most_similar(name, params)
+        {
+            let span = DiagSpan::from_span(arg.span, SubRange::new(0, name.len()));
+            diag.fix(Fix::replace(eco_format!("replace with `{param}`"), span, param));
+        }
+
+        Err(eco_vec![diag])
+    }
+}
+
+/// Find the name that is most similar to the given one, if any is similar
+/// enough to likely be what was meant.
+fn most_similar<S: AsRef<str>>(
+    name: &str,
+    candidates: impl IntoIterator<Item = S>,
+) -> Option<EcoString> {
+    let max = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate.as_ref()), candidate))
+        .filter(|(distance, _)| *distance <= max)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_ref().into())
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &y) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(x != y);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
         }
-        Ok(())
     }
+    row[b.len()]
 }
 
 /// A key that can be used to get an argument: either the index of a positional
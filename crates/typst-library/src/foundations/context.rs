@@ -1,6 +1,7 @@
 use comemo::Track;
+use typst_syntax::Span;
 
-use crate::diag::{Hint, HintedStrResult, SourceResult, bail};
+use crate::diag::{Fix, HintedStrResult, SourceResult, bail, error};
 use crate::engine::Engine;
 use crate::foundations::{
     Args, Construct, Content, Func, ShowFn, StyleChain, Value, elem,
@@ -53,11 +54,15 @@ impl<'a> Context<'a> {
 /// Extracts an optional piece of context, yielding an error with hints if
 /// it isn't available.
 fn require<T>(val: Option<T>) -> HintedStrResult<T> {
-    val.ok_or("can only be used when context is known")
-    .hint("try wrapping this in a `context` expression")
-    .hint(
-        "the `context` expression should wrap everything that depends on this function",
-    )
+    val.ok_or_else(|| {
+        error!(
+            "can only be used when context is known";
+            hint: "try wrapping this in a `context` expression";
+            hint: "the `context` expression should wrap everything \
+                   that depends on this function";
+        )
+        .with_fix(Fix::insert("wrap in `context`", Span::detached(), "context "))
+    })
 }
 
 /// Executes a `context` block.
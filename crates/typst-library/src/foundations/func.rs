@@ -348,7 +348,12 @@ impl Func {
             }
             FuncInner::Element(func) => {
                 let value = func.construct(engine, &mut args)?;
-                args.finish()?;
+                args.finish_with_params(
+                    func.params()
+                        .iter()
+                        .filter(|param| param.named)
+                        .map(|param| param.name),
+                )?;
                 Ok(Value::Content(value))
             }
             FuncInner::Closure(closure) => (engine.library.routines.eval_closure)(
@@ -7,7 +7,7 @@ use indexmap::map::Entry;
 use rustc_hash::FxBuildHasher;
 use typst_syntax::Span;
 
-use crate::diag::{HintedStrResult, HintedString, SourceDiagnostic, WarningSink, error};
+use crate::diag::{Fix, HintedStrResult, HintedString, Severity, WarningSink, error};
 use crate::engine::Engine;
 use crate::foundations::{
     Func, IntoValue, NativeElement, NativeFunc, NativeFuncData, NativeType, Value,
@@ -617,13 +617,19 @@ fn cannot_mutate_constant(var: &str) -> HintedString {
 #[cold]
 fn unknown_variable(var: &str) -> HintedString {
     let mut res = HintedString::new(eco_format!("unknown variable `{var}`"));
+    res.fix(Fix::import(var));
 
     if var.contains('-') {
+        let fixed = var.replace('-', " - ");
         res.hint(eco_format!(
             "if you meant to use subtraction, \
-             try adding spaces around the minus sign{}: `{}`",
+             try adding spaces around the minus sign{}: `{fixed}`",
             if var.matches('-').count() > 1 { "s" } else { "" },
-            var.replace('-', " - ")
+        ));
+        res.fix(Fix::replace(
+            "add spaces around the minus sign",
+            Span::detached(),
+            fixed,
         ));
     }
 
@@ -634,12 +640,14 @@ fn unknown_variable(var: &str) -> HintedString {
 #[cold]
 fn unknown_variable_math(var: &str, in_global: bool) -> HintedString {
     let mut res = HintedString::new(eco_format!("unknown variable `{var}`"));
+    res.fix(Fix::import(var));
 
     if matches!(var, "none" | "auto" | "false" | "true") {
         res.hint(eco_format!(
             "if you meant to use a literal, \
              try adding a hash before it: `#{var}`",
         ));
+        res.fix(Fix::insert("add a hash", Span::detached(), "#"));
     } else if in_global {
         res.hint(eco_format!(
             "`{var}` is not available directly in math, but is in the standard library",
@@ -650,16 +658,20 @@ fn unknown_variable_math(var: &str, in_global: bool) -> HintedString {
         res.hint(eco_format!(
             "or access `{var}` in math mode by using the `std` module: `std.{var}`",
         ));
+        res.fix(Fix::insert("add a hash", Span::detached(), "#"));
+        res.fix(Fix::insert("access via the `std` module", Span::detached(), "std."));
     } else {
+        let spaced = var.chars().flat_map(|c| [' ', c]).skip(1).collect::<EcoString>();
         res.hint(eco_format!(
             "if you meant to display multiple letters as is, \
-             try adding spaces between each letter: `{}`",
-            var.chars().flat_map(|c| [' ', c]).skip(1).collect::<EcoString>()
+             try adding spaces between each letter: `{spaced}`",
         ));
         res.hint(eco_format!(
             "or if you meant to display this as text, \
              try placing it in quotes: `\"{var}\"`"
         ));
+        res.fix(Fix::replace("add spaces between the letters", Span::detached(), spaced));
+        res.fix(Fix::wrap("place in quotes", Span::detached(), "\"", "\""));
     }
 
     res
@@ -703,10 +715,9 @@ pub struct NormalBindingGuard<'x, 'y> {
 
 impl WarningSink for NormalBindingGuard<'_, '_> {
     fn emit(&mut self, message: HintedString) {
-        self.engine.sink.warn(
-            SourceDiagnostic::warning(self.span, message.message())
-                .with_hints(message.hints().iter().cloned()),
-        );
+        self.engine
+            .sink
+            .warn(message.into_diagnostic(Severity::Warning, self.span));
     }
 }
 
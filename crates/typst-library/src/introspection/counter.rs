@@ -21,7 +21,9 @@ use crate::introspection::{
 };
 use crate::layout::{Frame, FrameItem, PageElem};
 use crate::math::EquationElem;
-use crate::model::{FigureElem, FootnoteElem, HeadingElem, Numbering, NumberingPattern};
+use crate::model::{
+    FigureElem, FootnoteElem, HeadingElem, Numbering, NumberingPattern, SidenoteElem,
+};
 use crate::{Library, World};
 
 /// Counts through pages, elements, and more.
@@ -311,6 +313,9 @@ impl Counter {
                     } else if func == FootnoteElem::ELEM {
                         let elem = content.to_packed::<FootnoteElem>()?;
                         elem.numbering.as_option().clone()
+                    } else if func == SidenoteElem::ELEM {
+                        let elem = content.to_packed::<SidenoteElem>()?;
+                        elem.numbering.as_option().clone()
                     } else {
                         None
                     }
@@ -324,6 +329,8 @@ impl Counter {
                         styles.get_cloned(EquationElem::numbering)
                     } else if func == FootnoteElem::ELEM {
                         Some(styles.get_cloned(FootnoteElem::numbering))
+                    } else if func == SidenoteElem::ELEM {
+                        Some(styles.get_cloned(SidenoteElem::numbering))
                     } else {
                         None
                     }
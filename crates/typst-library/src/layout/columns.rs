@@ -77,6 +77,12 @@ pub struct ColumnsElem {
 
     /// Whether to equalize the height of columns by breaking columns early.
     ///
+    /// Balancing only applies to the last region of the columns, for example
+    /// the last page of page-level columns. It respects unbreakable blocks,
+    /// @text.costs[widow and orphan prevention], and parent-scoped floats. If
+    /// the content can't be distributed evenly without pushing some of it into
+    /// the next region, the columns are made as high as necessary instead.
+    ///
     /// #example(
     /// ```
     /// #set page(columns: 2, height: 5cm)
@@ -10,6 +10,7 @@ use crate::foundations::Label;
 use crate::introspection::{Location, Tag};
 use crate::layout::{Abs, Axes, FixedAlignment, Point, Size, Transform};
 use crate::model::Destination;
+use crate::pdf::Layer;
 use crate::text::TextItem;
 use crate::visualize::{Color, Curve, FixedStroke, Geometry, Image, Paint, Shape};
 
@@ -372,6 +373,13 @@ impl Frame {
         }
     }
 
+    /// Place the contents of the frame on a PDF layer.
+    pub fn layer(&mut self, layer: Layer) {
+        if !self.is_empty() {
+            self.group(|g| g.layer = Some(layer));
+        }
+    }
+
     /// Wrap the frame's contents in a group and modify that group with `f`.
     fn group<F>(&mut self, f: F)
     where
@@ -494,6 +502,9 @@ pub enum FrameItem {
     Image(Image, Size, Span),
     /// An internal or external link to a destination.
     Link(Destination, Size),
+    /// An interactive area that belongs to the element at the location, like
+    /// the widget of a PDF form field.
+    Annotation(Location, Size),
     /// An introspectable element that produced something within this frame.
     Tag(Tag),
 }
@@ -506,6 +517,7 @@ impl Debug for FrameItem {
             Self::Shape(shape, _) => write!(f, "{shape:?}"),
             Self::Image(image, _, _) => write!(f, "{image:?}"),
             Self::Link(dest, _) => write!(f, "Link({dest:?})"),
+            Self::Annotation(loc, _) => write!(f, "Annotation({loc:?})"),
             Self::Tag(tag) => write!(f, "{tag:?}"),
         }
     }
@@ -527,6 +539,8 @@ pub struct GroupItem {
     /// thought of as inserting the elements at the end but still inside of the
     /// parent.
     pub parent: Option<FrameParent>,
+    /// The PDF layer the group's contents belong to.
+    pub layer: Option<Layer>,
 }
 
 impl GroupItem {
@@ -538,6 +552,7 @@ impl GroupItem {
             clip: None,
             label: None,
             parent: None,
+            layer: None,
         }
     }
 }
@@ -8,7 +8,7 @@ use crate::diag::{HintedStrResult, SourceResult, bail};
 use crate::engine::Engine;
 use crate::foundations::{
     Args, Cast, CastInfo, Construct, Content, Dict, Fold, FromValue, IntoValue,
-    NativeElement, Reflect, Set, Smart, Value, cast, elem,
+    NativeElement, Reflect, Set, Smart, Value, cast, dict, elem,
 };
 use crate::layout::{
     Abs, Alignment, FlushElem, HAlignment, Length, OuterVAlignment, Ratio, Rel, Sides,
@@ -218,6 +218,65 @@ pub struct PageElem {
     #[ghost]
     pub bleed: Margin<Rel<Length>>,
 
+    /// Which printer's marks to draw around the page.
+    ///
+    /// Printer's marks help the print shop to align the printing plates and to
+    /// trim the printed sheets. They are drawn in a dedicated area outside of
+    /// the @page.bleed[bleed], which enlarges the exported page accordingly.
+    ///
+    /// Accepted values:
+    ///
+    /// - `{true}` or `{false}`: Enables or disables all marks.
+    /// - A dictionary: With a dictionary, the marks can be enabled
+    ///   individually. The dictionary may include the following keys:
+    ///   - `crop`: Lines at the corners that show where to trim the sheet.
+    ///   - `registration`: Targets centered on each side for aligning the
+    ///     printing plates. They are drawn in the special `{"all"}`
+    ///     @color.spot[spot colorant], so that they appear on every plate.
+    ///   - `bars`: A row of CMYK patches for checking the ink density.
+    ///
+    /// Omitted keys disable the respective marks.
+    ///
+    /// In PDF export, the page's `TrimBox` and `BleedBox` are set so that
+    /// production tools can find the trim and bleed areas within the enlarged
+    /// page. PNG and SVG export always include the bleed and the marks if
+    /// marks are enabled. The area around the bleed is not filled.
+    ///
+    /// ```typ
+    /// #set page(
+    ///   width: 8cm,
+    ///   height: 5cm,
+    ///   bleed: 3mm,
+    ///   marks: (crop: true, registration: true),
+    /// )
+    /// ```
+    #[ghost]
+    pub marks: PageMarks,
+
+    /// Slug information to print below the page, outside of the
+    /// @page.bleed[bleed].
+    ///
+    /// The slug identifies a printed sheet, for example with the name of the
+    /// job, its revision, and the date. It is placed in the area of the
+    /// @page.marks[printer's marks] next to the bottom left crop mark and
+    /// enlarges the exported page in the same way as the marks, even if no
+    /// marks are enabled. Like in the header and footer, the page counter can
+    /// be used within the slug.
+    ///
+    /// ```typ
+    /// #set page(
+    ///   width: 8cm,
+    ///   height: 5cm,
+    ///   bleed: 3mm,
+    ///   marks: (crop: true),
+    ///   slug: text(5pt)[
+    ///     Flyer, revision 2 -- page #context counter(page).display()
+    ///   ],
+    /// )
+    /// ```
+    #[ghost]
+    pub slug: Option<Content>,
+
     /// On which side the pages will be bound.
     ///
     /// - `{auto}`: Equivalent to `left` if the @text.dir[text direction] is
@@ -226,7 +285,7 @@ pub struct PageElem {
     /// - `right`: Bound on the right side.
     ///
     /// This affects the meaning of the `inside` and `outside` options for
-    /// margins.
+    /// margins and determines in which margin @sidenote[sidenotes] are placed.
     #[ghost]
     pub binding: Smart<Binding>,
 
@@ -721,6 +780,44 @@ impl<T: Reflect + FromValue + Copy + PartialEq> FromValue for Margin<T> {
     }
 }
 
+/// Which printer's marks to draw around a page.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PageMarks {
+    /// Whether to draw crop marks.
+    pub crop: bool,
+    /// Whether to draw registration targets.
+    pub registration: bool,
+    /// Whether to draw color bars.
+    pub bars: bool,
+}
+
+impl PageMarks {
+    /// Whether any marks are drawn.
+    pub fn any(self) -> bool {
+        self.crop || self.registration || self.bars
+    }
+}
+
+cast! {
+    PageMarks,
+    self => Value::Dict(dict! {
+        "crop" => self.crop,
+        "registration" => self.registration,
+        "bars" => self.bars,
+    }),
+    v: bool => Self { crop: v, registration: v, bars: v },
+    mut dict: Dict => {
+        let mut take = |key| {
+            dict.take(key).ok().map(Value::cast).transpose().map(Option::unwrap_or_default)
+        };
+        let crop = take("crop")?;
+        let registration = take("registration")?;
+        let bars = take("bars")?;
+        dict.finish(&["crop", "registration", "bars"])?;
+        Self { crop, registration, bars }
+    },
+}
+
 /// Specification of the page's binding.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Binding {
@@ -9,7 +9,8 @@ use crate::layout::{Alignment, Em, Length, Rel};
 /// in the container. Floating content is placed at the top or bottom of the
 /// container, displacing other content down or up respectively. In both cases,
 /// the content position can be adjusted with @place.dx[`dx`] and
-/// @place.dy[`dy`] offsets without affecting the layout.
+/// @place.dy[`dy`] offsets without affecting the layout. Overlaid content can
+/// also make text flow around it with the @place.wrap[`wrap`] parameter.
 ///
 /// The parent can be any container such as a @block, @box, @rect, etc. A top
 /// level `place` call will place content directly in the text area of the
@@ -136,10 +137,41 @@ pub struct PlaceElem {
     /// ```
     pub float: bool,
 
+    /// Whether and how paragraphs flow around the placed element.
+    ///
+    /// When set, the element is placed at its position in the flow, aligned
+    /// horizontally according to the @place.alignment[`alignment`]. The lines
+    /// of the following paragraphs are then shortened where they are next to
+    /// the element. The value determines on which sides of the element the
+    /// text flows:
+    ///
+    /// - `{none}`: Text doesn't flow around the element.
+    /// - `{"left"}`: Text flows to the left of the element.
+    /// - `{"right"}`: Text flows to the right of the element.
+    /// - `{"both"}`: Text flows on both sides of the element. The lines to its
+    ///   left are read before the lines to its right.
+    ///
+    /// Other blocks, such as headings, are moved below the element.
+    ///
+    /// Wrapping is only available for non-floating placement without a
+    /// vertical alignment.
+    ///
+    /// ```example
+    /// #set page(height: 140pt)
+    /// #place(
+    ///   right,
+    ///   wrap: "left",
+    ///   clearance: 6pt,
+    ///   rect(width: 40pt, height: 50pt, fill: aqua),
+    /// )
+    /// #lorem(40)
+    /// ```
+    pub wrap: Option<PlacementWrap>,
+
     /// The spacing between the placed element and other elements in a floating
-    /// layout.
+    /// layout or the text flowing around it.
     ///
-    /// Has no effect if `float` is `{false}`.
+    /// Has no effect if `float` is `{false}` and `wrap` is `{none}`.
     #[default(Em::new(1.5).into())]
     pub clearance: Length,
 
@@ -189,6 +221,17 @@ pub enum PlacementScope {
     Parent,
 }
 
+/// On which sides of a placed element text flows.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PlacementWrap {
+    /// Text flows to the left of the element.
+    Left,
+    /// Text flows to the right of the element.
+    Right,
+    /// Text flows on both sides of the element.
+    Both,
+}
+
 /// Asks the layout algorithm to place pending floating elements before
 /// continuing with the content.
 ///
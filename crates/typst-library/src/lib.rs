@@ -41,7 +41,7 @@ use crate::foundations::{
 use crate::layout::{Alignment, Dir};
 use crate::routines::Routines;
 use crate::text::{Font, FontBook};
-use crate::visualize::Color;
+use crate::visualize::{Color, ImageStore};
 
 /// The environment in which typesetting occurs.
 ///
@@ -97,6 +97,16 @@ pub trait World: Send + Sync {
     /// If this function returns `None`, Typst's `datetime` function will
     /// return an error.
     fn today(&self, offset: Option<Duration>) -> Option<Datetime>;
+
+    /// A persistent store for decoded raster images.
+    ///
+    /// If this returns a store, decoded images are written to it and later
+    /// decodings of the same data, possibly in other processes, are served
+    /// from it. By default, there is no store and images are only memoized
+    /// within the current process.
+    fn image_store(&self) -> Option<&dyn ImageStore> {
+        None
+    }
 }
 
 macro_rules! world_impl {
@@ -129,6 +139,10 @@ macro_rules! world_impl {
             fn today(&self, offset: Option<Duration>) -> Option<Datetime> {
                 self.deref().today(offset)
             }
+
+            fn image_store(&self) -> Option<&dyn ImageStore> {
+                self.deref().image_store()
+            }
         }
     };
 }
@@ -1,10 +1,10 @@
-use ecow::EcoString;
+use ecow::{EcoString, eco_format};
 use typst_syntax::VirtualPath;
 
 use crate::diag::{HintedStrResult, bail, error};
 use crate::foundations::{
-    Array, BundlePath, Cast, Content, Datetime, OneOrMultiple, Packed, ShowFn, ShowSet,
-    Smart, StyleChain, Styles, Target, Value, cast, elem,
+    Array, BundlePath, Cast, Content, Datetime, Dict, IntoValue, OneOrMultiple, Packed,
+    ShowFn, ShowSet, Smart, StyleChain, Styles, Target, Value, cast, elem,
 };
 use crate::text::{Locale, TextElem};
 
@@ -178,6 +178,43 @@ pub struct DocumentElem {
     /// something other than `{auto}`.
     pub date: Smart<Option<Datetime>>,
 
+    /// Custom metadata to embed into the document.
+    ///
+    /// This is a dictionary with string values. Its keys determine where each
+    /// value ends up:
+    ///
+    /// - A key without a colon, like `{"Department"}`, adds a custom entry to
+    ///   the PDF's document information dictionary. The standard entries like
+    ///   `Title` and `Author` are reserved; use the respective properties of
+    ///   the document instead. Since PDF 2.0 deprecates the document
+    ///   information dictionary, these entries are written to the XMP metadata
+    ///   in the `pdfx` namespace for PDF 2.0 and PDF/A-4 instead.
+    /// - A key of the form `{"prefix:name"}` adds a property to the document's
+    ///   XMP metadata. The following properties from the Dublin Core (`dc`)
+    ///   and XMP Rights Management (`xmpRights`) schemas are supported:
+    ///   `dc:contributor`, `dc:coverage`, `dc:identifier`, `dc:publisher`,
+    ///   `dc:relation`, `dc:rights`, `dc:source`, `dc:type`,
+    ///   `xmpRights:Certificate`, `xmpRights:Owner`, `xmpRights:UsageTerms`,
+    ///   and `xmpRights:WebStatement`.
+    /// - A key of the form `{"xmlns:prefix"}` declares a custom XMP namespace
+    ///   with the given URI. Afterwards, `{"prefix:name"}` keys can be used to
+    ///   add arbitrary text properties in this namespace. When custom
+    ///   namespaces are used, Typst describes them with a PDF/A extension
+    ///   schema, so that the metadata remains valid for PDF/A.
+    ///
+    /// This is currently only supported in PDF export.
+    ///
+    /// ```typ
+    /// #set document(metadata: (
+    ///   "Department": "Legal",
+    ///   "dc:rights": "CC BY 4.0",
+    ///   "dc:publisher": "ACME Corp.",
+    ///   "xmlns:acme": "https://acme.example/ns/archive/1.0/",
+    ///   "acme:retention": "10 years",
+    /// ))
+    /// ```
+    pub metadata: DocumentMetadata,
+
     /// The content that makes up the document.
     ///
     /// This property is only supported in the @reference:bundle[bundle] target.
@@ -246,6 +283,7 @@ impl ShowSet for Packed<DocumentElem> {
         self.description.copy_into(&mut styles);
         self.keywords.copy_into(&mut styles);
         self.date.copy_into(&mut styles);
+        self.metadata.copy_into(&mut styles);
         styles
     }
 }
@@ -319,6 +357,152 @@ cast! {
     v: Array => Self(v.into_iter().map(Value::cast).collect::<HintedStrResult<_>>()?),
 }
 
+/// Custom metadata of a document.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct DocumentMetadata {
+    /// Custom entries of the document information dictionary, as pairs of key
+    /// and value.
+    pub info: Vec<(EcoString, EcoString)>,
+    /// Custom XMP namespaces, as pairs of prefix and URI.
+    pub namespaces: Vec<(EcoString, EcoString)>,
+    /// XMP properties, as triples of namespace prefix, name, and value.
+    pub properties: Vec<(EcoString, EcoString, EcoString)>,
+}
+
+impl DocumentMetadata {
+    /// The entries of the document information dictionary that Typst writes
+    /// itself.
+    const RESERVED_INFO: &[&str] = &[
+        "Title",
+        "Author",
+        "Subject",
+        "Keywords",
+        "Creator",
+        "Producer",
+        "CreationDate",
+        "ModDate",
+        "Trapped",
+    ];
+
+    /// The properties of predefined XMP schemas that can be set.
+    const PREDEFINED: &[(&str, &[&str])] = &[
+        (
+            "dc",
+            &[
+                "contributor",
+                "coverage",
+                "identifier",
+                "publisher",
+                "relation",
+                "rights",
+                "source",
+                "type",
+            ],
+        ),
+        ("xmpRights", &["Certificate", "Owner", "UsageTerms", "WebStatement"]),
+    ];
+
+    /// Whether there is no custom metadata.
+    pub fn is_empty(&self) -> bool {
+        self.info.is_empty() && self.properties.is_empty()
+    }
+
+    /// Creates the metadata from a dictionary, validating its keys.
+    fn from_dict(dict: Dict) -> HintedStrResult<Self> {
+        let mut metadata = Self::default();
+        let mut properties = vec![];
+        for (key, value) in dict {
+            let value: EcoString = value.cast()?;
+            match key.split_once(':') {
+                None => {
+                    if Self::RESERVED_INFO.contains(&key.as_str()) {
+                        bail!(
+                            "the `{key}` entry is set by Typst";
+                            hint: "use the corresponding document property instead";
+                        );
+                    }
+                    metadata.info.push((key.as_str().into(), value));
+                }
+                Some(("xmlns", prefix)) => {
+                    if !is_xml_name(prefix) {
+                        bail!("`{prefix}` is not a valid namespace prefix");
+                    }
+                    if Self::PREDEFINED.iter().any(|&(p, _)| p == prefix)
+                        || prefix.starts_with("xml")
+                        || ["rdf", "x", "xmp", "pdf", "pdfx", "pdfaid", "pdfuaid"]
+                            .contains(&prefix)
+                    {
+                        bail!("the namespace prefix `{prefix}` is reserved");
+                    }
+                    metadata.namespaces.push((prefix.into(), value));
+                }
+                Some((prefix, name)) => {
+                    if !is_xml_name(name) {
+                        bail!("`{name}` is not a valid property name");
+                    }
+                    properties.push((
+                        EcoString::from(prefix),
+                        EcoString::from(name),
+                        value,
+                    ));
+                }
+            }
+        }
+
+        // Namespaces may be declared after the properties that use them, so we
+        // can only check the properties at the end.
+        for (prefix, name, _) in &properties {
+            if let Some(&(_, names)) =
+                Self::PREDEFINED.iter().find(|&&(p, _)| prefix == p)
+            {
+                if !names.contains(&name.as_str()) {
+                    bail!(
+                        "the property `{prefix}:{name}` is not supported";
+                        hint: "use a custom namespace for other properties";
+                    );
+                }
+            } else if !metadata.namespaces.iter().any(|(p, _)| p == prefix) {
+                bail!(
+                    "unknown namespace prefix `{prefix}`";
+                    hint: "declare the namespace with an `xmlns:{prefix}` key";
+                );
+            }
+        }
+
+        metadata.properties = properties;
+        Ok(metadata)
+    }
+}
+
+impl From<DocumentMetadata> for Dict {
+    fn from(metadata: DocumentMetadata) -> Self {
+        let mut dict = Dict::new();
+        for (key, value) in metadata.info {
+            dict.insert(key.into(), value.into_value());
+        }
+        for (prefix, uri) in metadata.namespaces {
+            dict.insert(eco_format!("xmlns:{prefix}").into(), uri.into_value());
+        }
+        for (prefix, name, value) in metadata.properties {
+            dict.insert(eco_format!("{prefix}:{name}").into(), value.into_value());
+        }
+        dict
+    }
+}
+
+cast! {
+    DocumentMetadata,
+    self => Value::Dict(self.into()),
+    v: Dict => Self::from_dict(v)?,
+}
+
+/// Whether the string is a valid unqualified XML name.
+fn is_xml_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
 /// A document resulting from compilation.
 pub trait Document {
     /// Get the document's metadata.
@@ -344,6 +528,8 @@ pub struct DocumentInfo {
     /// set text(lang: "...", region: "...")
     /// ```
     pub locale: Smart<Locale>,
+    /// The document's custom metadata.
+    pub metadata: DocumentMetadata,
 }
 
 impl DocumentInfo {
@@ -372,6 +558,9 @@ impl DocumentInfo {
         if styles.has(DocumentElem::date) {
             self.date = styles.get(DocumentElem::date);
         }
+        if styles.has(DocumentElem::metadata) {
+            self.metadata = styles.get_cloned(DocumentElem::metadata);
+        }
     }
 
     /// Populate this document info with locale details from the given styles.
@@ -14,7 +14,7 @@ use crate::foundations::{
 use crate::introspection::{Count, Counter, CounterKey, CounterUpdate, Location};
 use crate::layout::{
     AlignElem, Alignment, BlockElem, Em, Length, OuterVAlignment, PlacementScope,
-    VAlignment,
+    PlacementWrap, VAlignment,
 };
 use crate::model::{Numbering, NumberingPattern, Outlinable, Refable, Supplement};
 use crate::text::{Lang, Locale, TextElem};
@@ -200,6 +200,30 @@ pub struct FigureElem {
     /// ```
     pub scope: PlacementScope,
 
+    /// Whether and how paragraphs flow around the figure.
+    ///
+    /// The figure is aligned to the opposite side of the text: With
+    /// `{"left"}`, text flows to the left of a right-aligned figure, with
+    /// `{"right"}`, it flows to the right of a left-aligned figure, and with
+    /// `{"both"}`, it flows on both sides of a centered figure. See the
+    /// @place.wrap[`wrap` parameter] of `place` for more details.
+    ///
+    /// The gap between the figure and the text is controlled by the
+    /// @place.clearance[`clearance`] argument on the `place` function.
+    ///
+    /// Cannot be combined with a floating @figure.placement[`placement`].
+    ///
+    /// ```example
+    /// #set page(height: 160pt)
+    /// #figure(
+    ///   wrap: "left",
+    ///   caption: [A glacier],
+    ///   image("glacier.jpg", width: 40%),
+    /// )
+    /// #lorem(50)
+    /// ```
+    pub wrap: Option<PlacementWrap>,
+
     /// The figure's caption.
     pub caption: Option<Packed<FigureCaption>>,
 
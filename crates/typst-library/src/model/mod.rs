@@ -19,6 +19,7 @@ mod outline;
 mod par;
 mod quote;
 mod reference;
+mod sidenote;
 mod strong;
 mod table;
 mod terms;
@@ -41,6 +42,7 @@ pub use self::outline::*;
 pub use self::par::*;
 pub use self::quote::*;
 pub use self::reference::*;
+pub use self::sidenote::*;
 pub use self::strong::*;
 pub use self::table::*;
 pub use self::terms::*;
@@ -68,6 +70,7 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<FigureElem>();
     global.define_elem::<QuoteElem>();
     global.define_elem::<FootnoteElem>();
+    global.define_elem::<SidenoteElem>();
     global.define_elem::<OutlineElem>();
     global.define_elem::<RefElem>();
     global.define_elem::<CiteElem>();
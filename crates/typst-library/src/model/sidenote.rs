@@ -0,0 +1,199 @@
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use typst_utils::NonZeroExt;
+
+use crate::diag::{SourceResult, bail};
+use crate::engine::Engine;
+use crate::foundations::{
+    Content, NativeElement, Packed, ShowSet, StyleChain, Styles, elem, scope,
+};
+use crate::introspection::{Count, Counter, CounterUpdate};
+use crate::layout::{Em, Length};
+use crate::model::{DirectLinkElem, Numbering, NumberingPattern, ParElem};
+use crate::text::{SuperElem, TextElem, TextSize};
+
+/// A sidenote.
+///
+/// Places a remark in the outer margin of the page, next to the line it refers
+/// to. Like a @footnote, a sidenote inserts a superscript number into the text
+/// and repeats it in front of the note. Sidenotes are numbered sequentially
+/// throughout your document, independently of footnotes.
+///
+/// Notes that would overlap are moved down below each other. Notes that don't
+/// fit onto the page anymore move to the top of the next page's margin. When
+/// the @page.margin[margins] are given as `inside` and `outside`, the notes
+/// are in the outer margin, which depends on the @page.binding[binding]: For
+/// a left-bound document, it is the right margin on odd pages and the left
+/// margin on even pages. Otherwise, the notes are in the wider of the left and
+/// right margin, preferring the right one. Sidenotes are only supported in the
+/// main flow of the page and the margin must be wide enough to hold them.
+///
+/// In HTML export, sidenotes become inline `span` elements with the
+/// `doc-footnote` role right after their number, which can be moved into the
+/// margin with CSS.
+///
+/// To customize the appearance of the note in the margin, see @sidenote.entry.
+///
+/// = Example <example>
+/// ```example
+/// #set page(margin: (outside: 4cm))
+///
+/// Typst is a markup-based typesetting
+/// system.#sidenote[It was started
+/// in 2019.] It is designed to be as
+/// powerful as LaTeX while being much
+/// easier to learn and use.#sidenote[
+///   See the tutorial to get started.
+/// ]
+/// ```
+#[elem(scope, since = "0.16.0", Locatable, Tagged, Count)]
+pub struct SidenoteElem {
+    /// How to number sidenotes. Accepts a
+    /// @numbering[numbering pattern or function] taking a single number.
+    ///
+    /// ```example
+    /// >>> #set page(margin: (right: 3cm))
+    /// #set sidenote(numbering: "a")
+    ///
+    /// Sidenotes:
+    /// #sidenote[First],
+    /// #sidenote[Second]
+    /// ```
+    #[default(Numbering::Pattern(NumberingPattern::from_str("1").unwrap()))]
+    pub numbering: Numbering,
+
+    /// The content to put into the sidenote.
+    #[required]
+    pub body: Content,
+}
+
+#[scope]
+impl SidenoteElem {
+    #[elem]
+    type SidenoteEntry;
+}
+
+impl Packed<SidenoteElem> {
+    /// Returns the content that holds the number and links to the sidenote
+    /// entry.
+    pub fn realize(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<Content> {
+        let span = self.span();
+        let loc = self.location().unwrap();
+        let numbering = self.numbering.get_ref(styles);
+        let counter = Counter::of(SidenoteElem::ELEM);
+        let num = counter.display_at(engine, loc, styles, numbering, span)?;
+        let alt = num.plain_text();
+        let dest = loc.variant(1);
+        Ok(DirectLinkElem::new(dest, num, Some(alt)).pack().spanned(span))
+    }
+}
+
+impl Count for Packed<SidenoteElem> {
+    fn update(&self) -> Option<CounterUpdate> {
+        Some(CounterUpdate::Step(NonZeroUsize::ONE))
+    }
+}
+
+/// A sidenote in the page margin.
+///
+/// This function is not intended to be called directly. Instead, it is used in
+/// set and show rules to customize sidenotes.
+///
+/// ```example
+/// >>> #set page(margin: (right: 3cm))
+/// #show sidenote.entry: set text(blue)
+///
+/// Colorful
+/// #sidenote[It's blue]
+/// sidenotes.
+/// ```
+///
+/// _Note:_ Sidenote entry properties must be uniform across each page run (a
+/// page run is a sequence of pages without an explicit pagebreak in between).
+/// For this reason, set and show rules for sidenote entries should be defined
+/// before any page content, typically at the very start of the document.
+#[elem(
+    name = "entry",
+    title = "Sidenote Entry",
+    since = "0.16.0",
+    Locatable,
+    Tagged,
+    ShowSet
+)]
+pub struct SidenoteEntry {
+    /// The sidenote for this entry. Its location can be used to determine the
+    /// sidenote counter state.
+    #[required]
+    pub note: Packed<SidenoteElem>,
+
+    /// The amount of space between the text and the sidenotes. The same
+    /// amount of space is left between the sidenotes and the edge of the
+    /// page.
+    ///
+    /// ```example
+    /// >>> #set page(margin: (right: 3cm))
+    /// #set sidenote.entry(clearance: 0.5em)
+    ///
+    /// Close
+    /// #sidenote[Very close]
+    /// to the text.
+    /// ```
+    #[default(Em::new(1.0).into())]
+    pub clearance: Length,
+
+    /// The minimum gap between sidenotes that would otherwise overlap.
+    ///
+    /// ```example
+    /// >>> #set page(margin: (right: 3cm))
+    /// #set sidenote.entry(gap: 1em)
+    ///
+    /// Sidenotes:
+    /// #sidenote[Spaced],
+    /// #sidenote[Apart]
+    /// ```
+    #[default(Em::new(0.5).into())]
+    pub gap: Length,
+}
+
+impl Packed<SidenoteEntry> {
+    /// Returns the content of the superscript that holds the number and links
+    /// back to the sidenote, and the entry body.
+    pub fn realize(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<(Content, Content)> {
+        let span = self.span();
+        let default = StyleChain::default();
+        let numbering = self.note.numbering.get_ref(default);
+        let counter = Counter::of(SidenoteElem::ELEM);
+        let Some(dest) = self.note.location() else {
+            bail!(
+                self.span(), "sidenote entry must have a location";
+                hint: "try using a query or a show rule to customize the sidenote instead";
+            );
+        };
+
+        let num = counter.display_at(engine, dest, styles, numbering, span)?;
+        let alt = num.plain_text();
+        let link = DirectLinkElem::new(dest, num, Some(alt)).pack().spanned(span);
+        let sup = SuperElem::new(link).pack().spanned(span);
+        let body = self.note.body.clone();
+
+        Ok((sup, body))
+    }
+}
+
+impl ShowSet for Packed<SidenoteEntry> {
+    fn show_set(&self, _: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        out.set(ParElem::leading, Em::new(0.5).into());
+        out.set(TextElem::size, TextSize(Em::new(0.85).into()));
+        out
+    }
+}
@@ -368,4 +368,8 @@ pdf_marker_tag! {
     TermsItemBody,
     /// A generic `Lbl`.
     Label,
+    /// `RB` base text of a ruby.
+    RubyBase,
+    /// `RT` annotation of a ruby.
+    RubyText,
 }
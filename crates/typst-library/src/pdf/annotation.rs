@@ -0,0 +1,98 @@
+use ecow::EcoString;
+
+use crate::foundations::{Cast, Content, elem};
+use crate::introspection::Location;
+use crate::visualize::Color;
+
+/// A review annotation, like a sticky note or a highlighted passage.
+///
+/// When exporting to PDF, Typst adds a PDF annotation at the position of the
+/// body that PDF readers display in their comment panel. Highlights and
+/// underlines mark up the body, while notes and free text annotations are
+/// placed where the body starts. Without a body, the annotation is placed at
+/// its position in the text. In other export formats, only the body is
+/// visible.
+///
+/// = Example <example>
+/// ```typ
+/// Typst is a #pdf.annotation(
+///   "Maybe say what is new about it?",
+///   kind: "highlight",
+///   author: "Laura",
+/// )[new] markup-based typesetting system.
+/// #pdf.annotation(
+///   "Add a citation here.",
+///   author: "Martin",
+///   open: true,
+/// )
+/// ```
+///
+/// = Showing annotations conditionally <conditional>
+/// Review annotations are often only wanted in drafts. With a show rule, you
+/// can remove them while keeping their body, for example depending on an
+/// input passed on the command line with `--input review=true`:
+///
+/// ```typ
+/// #let review = sys.inputs.at("review", default: "false") == "true"
+/// #show pdf.annotation: it => if review { it } else { it.body }
+///
+/// Typst is #pdf.annotation("Too bold?")[fast].
+/// ```
+///
+/// = Accessibility <accessibility>
+/// The annotation's @pdf.annotation.contents[contents] double as its
+/// alternative description for Assistive Technology.
+///
+/// = Notes <notes>
+/// - Annotations are not supported in HTML export.
+/// - Annotations are not supported when exporting to PDF/A because their
+///   appearance is generated by the PDF reader.
+#[elem(since = "0.16.0", Locatable, Tagged)]
+pub struct AnnotationElem {
+    /// The text of the annotation.
+    #[required]
+    pub contents: EcoString,
+
+    /// The kind of annotation.
+    pub kind: AnnotationKind,
+
+    /// The author of the annotation.
+    ///
+    /// PDF readers display it as the annotation's title.
+    pub author: Option<EcoString>,
+
+    /// The color of the annotation.
+    ///
+    /// This is the color of the note's icon, the highlight, or the underline.
+    #[default(Color::YELLOW)]
+    pub color: Color,
+
+    /// Whether the annotation's popup is initially open.
+    ///
+    /// Only applies to notes.
+    #[default(false)]
+    pub open: bool,
+
+    /// The content the annotation refers to.
+    #[positional]
+    pub body: Option<Content>,
+
+    /// The location of the annotation the body belongs to.
+    #[internal]
+    #[ghost]
+    pub current: Option<Location>,
+}
+
+/// The kind of a review annotation.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum AnnotationKind {
+    /// A sticky note whose text is shown in a popup.
+    #[default]
+    Note,
+    /// Highlights the body like a text marker.
+    Highlight,
+    /// Underlines the body.
+    Underline,
+    /// Text that is displayed directly on the page.
+    FreeText,
+}
@@ -0,0 +1,371 @@
+use ecow::EcoString;
+
+use crate::foundations::{Content, Packed, StyleChain, elem};
+use crate::layout::{Em, Length, Rel};
+use crate::model::Url;
+
+/// A single-line or multi-line text input of a fillable PDF form.
+///
+/// Form fields are laid out like a @box with the given dimensions. When
+/// exporting to PDF, Typst adds an interactive widget at the box's position
+/// that PDF readers let users fill in. In other export formats, only the
+/// field's border is visible.
+///
+/// = Example <example>
+/// ```typ
+/// Name: #pdf.text-field("name", tooltip: "Full name") \
+/// Comments:
+/// #pdf.text-field(
+///   "comments",
+///   multiline: true,
+///   width: 100%,
+///   height: 4em,
+///   tooltip: "Additional comments",
+/// )
+/// ```
+///
+/// = Fields and names <names>
+/// Each field is identified by its name. Fields with the same name share their
+/// value: Filling in one of them also fills in the others. Names should
+/// therefore be unique unless this is intended. Combining fields of different
+/// kinds under the same name is an error.
+///
+/// = Accessibility <accessibility>
+/// Every form field should have a @pdf.text-field.tooltip[tooltip] describing
+/// its purpose. Assistive Technology announces it when the field receives
+/// focus. When exporting to PDF/UA-1, a tooltip is required.
+///
+/// Fields are focused in the order in which they appear in the document.
+///
+/// = Notes <notes>
+/// - Form fields are not supported in HTML export.
+/// - Form fields are not supported when exporting to PDF/A because their
+///   appearance is generated by the PDF reader.
+#[elem(since = "0.16.0", Locatable, Tagged)]
+pub struct TextFieldElem {
+    /// The field's name.
+    #[required]
+    pub name: EcoString,
+
+    /// The text the field is initially filled with.
+    ///
+    /// This is also the value that the field is reset to.
+    pub value: EcoString,
+
+    /// A description of the field's purpose.
+    ///
+    /// PDF readers display it when hovering over the field.
+    pub tooltip: Option<EcoString>,
+
+    /// Whether the field can contain multiple lines of text.
+    #[default(false)]
+    pub multiline: bool,
+
+    /// Whether the field's contents are hidden while typing, like for a
+    /// password.
+    #[default(false)]
+    pub password: bool,
+
+    /// The maximum number of characters the field accepts.
+    pub max_length: Option<usize>,
+
+    /// Whether the field must be filled in before the form is submitted.
+    #[default(false)]
+    pub required: bool,
+
+    /// Whether the field's value cannot be changed.
+    #[default(false)]
+    pub read_only: bool,
+
+    /// The field's width.
+    #[default(Em::new(10.0).into())]
+    pub width: Rel<Length>,
+
+    /// The field's height.
+    #[default(Em::new(1.4).into())]
+    pub height: Rel<Length>,
+}
+
+/// A checkbox of a fillable PDF form.
+///
+/// See @pdf.text-field for details on how form fields behave.
+///
+/// = Example <example>
+/// ```typ
+/// #pdf.checkbox("newsletter", tooltip: "Subscribe to the newsletter")
+/// Subscribe to the newsletter
+/// ```
+#[elem(since = "0.16.0", Locatable, Tagged)]
+pub struct CheckboxElem {
+    /// The field's name.
+    #[required]
+    pub name: EcoString,
+
+    /// Whether the checkbox is initially checked.
+    #[default(false)]
+    pub checked: bool,
+
+    /// A description of the field's purpose.
+    pub tooltip: Option<EcoString>,
+
+    /// Whether the field must be filled in before the form is submitted.
+    #[default(false)]
+    pub required: bool,
+
+    /// Whether the field's value cannot be changed.
+    #[default(false)]
+    pub read_only: bool,
+
+    /// The width and height of the checkbox.
+    #[default(Em::new(0.8).into())]
+    pub size: Length,
+}
+
+/// A radio button of a fillable PDF form.
+///
+/// Radio buttons with the same name form a group, of which at most one button
+/// can be selected at a time. The selected button's `value` becomes the value
+/// of the field.
+///
+/// See @pdf.text-field for details on how form fields behave.
+///
+/// = Example <example>
+/// ```typ
+/// #for size in ("S", "M", "L") [
+///   #pdf.radio(
+///     "size",
+///     size,
+///     checked: size == "M",
+///     tooltip: "Size " + size,
+///   ) #size
+/// ]
+/// ```
+#[elem(since = "0.16.0", Locatable, Tagged)]
+pub struct RadioElem {
+    /// The name of the radio button's group.
+    #[required]
+    pub name: EcoString,
+
+    /// The value of the group when this button is selected.
+    #[required]
+    pub value: EcoString,
+
+    /// Whether this button is initially selected.
+    ///
+    /// At most one button per group may be selected.
+    #[default(false)]
+    pub checked: bool,
+
+    /// A description of the button's purpose.
+    pub tooltip: Option<EcoString>,
+
+    /// Whether one of the group's buttons must be selected before the form is
+    /// submitted.
+    #[default(false)]
+    pub required: bool,
+
+    /// Whether the selection cannot be changed.
+    #[default(false)]
+    pub read_only: bool,
+
+    /// The diameter of the radio button.
+    #[default(Em::new(0.8).into())]
+    pub size: Length,
+}
+
+/// A drop-down or list selection of a fillable PDF form.
+///
+/// See @pdf.text-field for details on how form fields behave.
+///
+/// = Example <example>
+/// ```typ
+/// #pdf.choice(
+///   "country",
+///   ("Germany", "Switzerland", "Austria"),
+///   value: "Switzerland",
+///   tooltip: "Country of residence",
+/// )
+/// ```
+#[elem(since = "0.16.0", Locatable, Tagged)]
+pub struct ChoiceElem {
+    /// The field's name.
+    #[required]
+    pub name: EcoString,
+
+    /// The options to choose from.
+    #[required]
+    pub options: Vec<EcoString>,
+
+    /// The initially selected option.
+    pub value: Option<EcoString>,
+
+    /// A description of the field's purpose.
+    pub tooltip: Option<EcoString>,
+
+    /// Whether to show all options in a scrollable list instead of a drop-down.
+    #[default(false)]
+    pub list: bool,
+
+    /// Whether users may also enter a value that is not among the options.
+    ///
+    /// Only applies to drop-downs.
+    #[default(false)]
+    pub editable: bool,
+
+    /// Whether an option must be selected before the form is submitted.
+    #[default(false)]
+    pub required: bool,
+
+    /// Whether the selection cannot be changed.
+    #[default(false)]
+    pub read_only: bool,
+
+    /// The field's width.
+    #[default(Em::new(10.0).into())]
+    pub width: Rel<Length>,
+
+    /// The field's height.
+    #[default(Em::new(1.4).into())]
+    pub height: Rel<Length>,
+}
+
+/// A push button of a fillable PDF form.
+///
+/// A button can optionally open a URL when it is pressed.
+///
+/// See @pdf.text-field for details on how form fields behave.
+///
+/// = Example <example>
+/// ```typ
+/// #pdf.button(
+///   "help",
+///   url: "https://typst.app/docs",
+///   tooltip: "Open the documentation",
+/// )[Help]
+/// ```
+#[elem(since = "0.16.0", Locatable, Tagged)]
+pub struct ButtonElem {
+    /// The field's name.
+    #[required]
+    pub name: EcoString,
+
+    /// The URL to open when the button is pressed.
+    pub url: Option<Url>,
+
+    /// A description of the button's purpose.
+    pub tooltip: Option<EcoString>,
+
+    /// Whether the button cannot be pressed.
+    #[default(false)]
+    pub read_only: bool,
+
+    /// The button's label.
+    #[required]
+    pub body: Content,
+}
+
+/// A signature field of a fillable PDF form.
+///
+/// The field reserves space for a digital signature that is added after the
+/// document was exported, either with a PDF reader or programmatically. See
+/// @pdf.text-field for details on how form fields behave.
+///
+/// = Example <example>
+/// ```typ
+/// Signed: #pdf.signature-field("client", tooltip: "Client signature")
+/// ```
+///
+/// = Notes <notes>
+/// Unlike other form fields, signature fields are supported when exporting to
+/// PDF/A.
+#[elem(since = "0.16.0", Locatable, Tagged)]
+pub struct SignatureFieldElem {
+    /// The field's name.
+    #[required]
+    pub name: EcoString,
+
+    /// A description of the field's purpose.
+    pub tooltip: Option<EcoString>,
+
+    /// Whether the signature is visible on the page.
+    ///
+    /// An invisible signature field takes up no space.
+    #[default(true)]
+    pub visible: bool,
+
+    /// The field's width.
+    #[default(Em::new(12.0).into())]
+    pub width: Rel<Length>,
+
+    /// The field's height.
+    #[default(Em::new(3.0).into())]
+    pub height: Rel<Length>,
+}
+
+/// A form field element of any kind.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum FormField {
+    Text(Packed<TextFieldElem>),
+    Checkbox(Packed<CheckboxElem>),
+    Radio(Packed<RadioElem>),
+    Choice(Packed<ChoiceElem>),
+    Button(Packed<ButtonElem>),
+    Signature(Packed<SignatureFieldElem>),
+}
+
+impl FormField {
+    /// Tries to interpret the content as a form field.
+    pub fn from_content(content: &Content) -> Option<Self> {
+        if let Some(elem) = content.to_packed::<TextFieldElem>() {
+            Some(Self::Text(elem.clone()))
+        } else if let Some(elem) = content.to_packed::<CheckboxElem>() {
+            Some(Self::Checkbox(elem.clone()))
+        } else if let Some(elem) = content.to_packed::<RadioElem>() {
+            Some(Self::Radio(elem.clone()))
+        } else if let Some(elem) = content.to_packed::<ChoiceElem>() {
+            Some(Self::Choice(elem.clone()))
+        } else if let Some(elem) = content.to_packed::<ButtonElem>() {
+            Some(Self::Button(elem.clone()))
+        } else {
+            content
+                .to_packed::<SignatureFieldElem>()
+                .map(|elem| Self::Signature(elem.clone()))
+        }
+    }
+
+    /// The field's name.
+    pub fn name(&self) -> &EcoString {
+        match self {
+            Self::Text(elem) => &elem.name,
+            Self::Checkbox(elem) => &elem.name,
+            Self::Radio(elem) => &elem.name,
+            Self::Choice(elem) => &elem.name,
+            Self::Button(elem) => &elem.name,
+            Self::Signature(elem) => &elem.name,
+        }
+    }
+
+    /// A description of the field's purpose.
+    pub fn tooltip<'a>(&'a self, styles: StyleChain<'a>) -> Option<&'a EcoString> {
+        match self {
+            Self::Text(elem) => elem.tooltip.get_ref(styles).as_ref(),
+            Self::Checkbox(elem) => elem.tooltip.get_ref(styles).as_ref(),
+            Self::Radio(elem) => elem.tooltip.get_ref(styles).as_ref(),
+            Self::Choice(elem) => elem.tooltip.get_ref(styles).as_ref(),
+            Self::Button(elem) => elem.tooltip.get_ref(styles).as_ref(),
+            Self::Signature(elem) => elem.tooltip.get_ref(styles).as_ref(),
+        }
+    }
+
+    /// The field's element.
+    pub fn elem(&self) -> &Content {
+        match self {
+            Self::Text(elem) => elem.pack_ref(),
+            Self::Checkbox(elem) => elem.pack_ref(),
+            Self::Radio(elem) => elem.pack_ref(),
+            Self::Choice(elem) => elem.pack_ref(),
+            Self::Button(elem) => elem.pack_ref(),
+            Self::Signature(elem) => elem.pack_ref(),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use ecow::EcoString;
+use typst_syntax::Span;
+
+use crate::foundations::{Content, elem};
+
+/// A layer of content that PDF readers can show and hide.
+///
+/// When exporting to PDF, the body is placed in an _optional content group_
+/// with the given name. PDF readers list these groups in their layer panel,
+/// where the user can toggle them. Multiple layer elements with the same name
+/// belong to the same layer. In this case, the visibility of the first one
+/// applies to the whole layer.
+///
+/// In other export formats, the body is only shown if its layer is visible.
+///
+/// = Example <example>
+/// ```typ
+/// #pdf.layer("Solutions", visible: false)[
+///   The answer is 42.
+/// ]
+/// ```
+///
+/// = Notes <notes>
+/// - Layers are not supported in HTML export.
+/// - PDF/A-1 and PDF 1.4 do not support optional content. When exporting to
+///   these, visible layers are flattened into the page and hidden layers
+///   result in an error.
+#[elem(since = "0.16.0", Locatable)]
+pub struct LayerElem {
+    /// The name of the layer, as shown by PDF readers.
+    #[required]
+    pub name: EcoString,
+
+    /// Whether the layer is initially visible.
+    #[default(true)]
+    pub visible: bool,
+
+    /// The content on the layer.
+    #[required]
+    pub body: Content,
+
+    /// The layer the body belongs to.
+    #[internal]
+    #[ghost]
+    pub current: Option<Layer>,
+}
+
+/// The layer that some content belongs to.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Layer {
+    /// The name of the layer.
+    pub name: EcoString,
+    /// Whether the layer is initially visible. This is the same for all
+    /// elements of the layer.
+    pub visible: bool,
+    /// The span of the layer element.
+    pub span: Span,
+}
@@ -1,10 +1,18 @@
 //! PDF-specific functionality.
 
 mod accessibility;
+mod annotation;
 mod attach;
+mod form;
+mod layer;
+mod page_label;
 
 pub use self::accessibility::*;
+pub use self::annotation::*;
 pub use self::attach::*;
+pub use self::form::*;
+pub use self::layer::*;
+pub use self::page_label::*;
 
 use crate::Feature;
 use crate::foundations::{Module, Scope};
@@ -15,6 +23,15 @@ pub fn module() -> Module {
     pdf.start_category(crate::Category::Pdf);
     pdf.define_elem::<AttachElem>();
     pdf.define_elem::<ArtifactElem>();
+    pdf.define_elem::<TextFieldElem>();
+    pdf.define_elem::<CheckboxElem>();
+    pdf.define_elem::<RadioElem>();
+    pdf.define_elem::<ChoiceElem>();
+    pdf.define_elem::<ButtonElem>();
+    pdf.define_elem::<SignatureFieldElem>();
+    pdf.define_elem::<AnnotationElem>();
+    pdf.define_elem::<LayerElem>();
+    pdf.define_elem::<PageLabelElem>();
 
     pdf.define_func::<table_summary>().with_feature(Feature::A11yExtras);
     pdf.define_func::<header_cell>().with_feature(Feature::A11yExtras);
@@ -0,0 +1,81 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+use typst_utils::NonZeroExt;
+
+use crate::foundations::{Cast, Smart, elem};
+
+/// Sets the page labels that PDF readers display, starting at the current
+/// page.
+///
+/// By default, Typst derives the page labels from the
+/// @page.numbering[page numbering]. This element overrides them from the
+/// page it is placed on until the next page label element. Within this range,
+/// the pages are numbered consecutively, starting at the given
+/// @pdf.page-label.start[`start`] number.
+///
+/// This is useful when the labels should differ from the printed page
+/// numbers, for example to label the cover or to prefix the appendix pages.
+///
+/// = Example <example>
+/// ```typ
+/// #pdf.page-label(prefix: "Cover")
+/// = My Book
+///
+/// #pagebreak()
+/// #pdf.page-label("i")
+/// = Preface
+///
+/// #pagebreak()
+/// #pdf.page-label("1")
+/// = Introduction
+///
+/// #pagebreak()
+/// #pdf.page-label("1", prefix: "A-")
+/// = Appendix
+/// ```
+///
+/// = Notes <notes>
+/// - This element is ignored if exporting to a format other than PDF.
+/// - If multiple page label elements end up on the same page, the last one
+///   applies.
+#[elem(since = "0.16.0", Locatable)]
+pub struct PageLabelElem {
+    /// How to number the pages.
+    ///
+    /// - `{none}`: The label consists only of the
+    ///   @pdf.page-label.prefix[`prefix`].
+    /// - `{auto}`: Goes back to deriving the labels from the page numbering.
+    ///   The other properties are ignored.
+    /// - A numbering style: Numbers the pages with the given style, after the
+    ///   prefix.
+    #[positional]
+    pub style: Smart<Option<PageLabelStyle>>,
+
+    /// Text that precedes the number in each label.
+    pub prefix: Option<EcoString>,
+
+    /// The number of the first page in the range.
+    #[default(NonZeroUsize::ONE)]
+    pub start: NonZeroUsize,
+}
+
+/// How to number the pages in a page label.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PageLabelStyle {
+    /// Arabic numerals: 1, 2, 3, ...
+    #[string("1")]
+    Arabic,
+    /// Lowercase roman numerals: i, ii, iii, ...
+    #[string("i")]
+    LowerRoman,
+    /// Uppercase roman numerals: I, II, III, ...
+    #[string("I")]
+    UpperRoman,
+    /// Lowercase letters: a, b, ..., z, aa, bb, ...
+    #[string("a")]
+    LowerAlpha,
+    /// Uppercase letters: A, B, ..., Z, AA, BB, ...
+    #[string("A")]
+    UpperAlpha,
+}
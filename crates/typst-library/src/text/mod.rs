@@ -9,6 +9,7 @@ mod linebreak;
 #[path = "lorem.rs"]
 mod lorem_;
 mod raw;
+mod ruby;
 mod shift;
 #[path = "smallcaps.rs"]
 mod smallcaps_;
@@ -23,6 +24,7 @@ pub use self::lang::*;
 pub use self::linebreak::*;
 pub use self::lorem_::*;
 pub use self::raw::*;
+pub use self::ruby::*;
 pub use self::shift::*;
 pub use self::smallcaps_::*;
 pub use self::smartquote::*;
@@ -48,7 +50,7 @@ use crate::foundations::{
     NoneValue, Packed, PlainText, Regex, Repr, Resolve, Scope, Set, Smart, Str,
     StyleChain, cast, dict, elem,
 };
-use crate::layout::{Abs, Axis, Dir, Em, Length, Ratio, Rel};
+use crate::layout::{Abs, Dir, Em, Length, Ratio, Rel};
 use crate::math::{EquationElem, MathSize};
 use crate::visualize::{Color, Paint, RelativeTo, Stroke};
 
@@ -66,6 +68,7 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<HighlightElem>();
     global.define_elem::<SmallcapsElem>();
     global.define_elem::<RawElem>();
+    global.define_elem::<RubyElem>();
     global.define_func::<lower>();
     global.define_func::<upper>();
     global.define_func::<lorem>();
@@ -519,6 +522,8 @@ pub struct TextElem {
     /// - `{auto}`: Automatically infer the direction from the `lang` property.
     /// - `{ltr}`: Layout text from left to right.
     /// - `{rtl}`: Layout text from right to left.
+    /// - `{ttb}`: Layout text from top to bottom, in lines that follow each
+    ///   other from right to left.
     ///
     /// When writing in right-to-left scripts like Arabic or Hebrew, you should
     /// set the @text.lang[text language] or direction. While individual runs of
@@ -539,6 +544,24 @@ pub struct TextElem {
     /// #set text(dir: rtl)
     /// هذا عربي.
     /// ```
+    ///
+    /// Vertical text is used for Chinese and Japanese. Characters from these
+    /// scripts stand upright and take on their vertical forms through the
+    /// font's `vert` and `vrt2` features. Other text, like Latin words, is
+    /// rotated by a quarter turn. Numbers with up to two digits are set
+    /// horizontally within the line (tate-chū-yoko) and boxes stand upright.
+    /// The lines of a vertical paragraph are as long as the available height.
+    /// Lines that don't fit next to each other into the available width
+    /// continue below. As with `{rtl}`, @columns progress from right to left
+    /// and pages are bound on the right. Decorations are not supported for
+    /// upright text.
+    ///
+    /// ```example
+    /// #set page(height: 120pt)
+    /// #set text(dir: ttb, lang: "ja", font: "Noto Serif CJK SC")
+    /// 吾輩は猫である。名前はまだ無い。
+    /// 第12章では Typst を使う。
+    /// ```
     #[ghost]
     pub dir: TextDir,
 
@@ -1255,8 +1278,8 @@ cast! {
     TextDir,
     self => self.0.into_value(),
     v: Smart<Dir> => {
-        if v.is_custom_and(|dir| dir.axis() == Axis::Y) {
-            bail!("text direction must be horizontal");
+        if v.is_custom_and(|dir| dir == Dir::BTT) {
+            bail!("text direction cannot be bottom-to-top");
         }
         Self(v)
     },
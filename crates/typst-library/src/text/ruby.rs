@@ -0,0 +1,160 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::diag::{SourceResult, bail};
+use crate::foundations::{
+    Cast, Content, NativeElement, OneOrMultiple, Packed, Smart, elem,
+};
+use crate::layout::{Em, Length};
+use crate::text::{TextElem, TextSize};
+
+/// Attaches a phonetic annotation to base text.
+///
+/// Ruby annotations are small runs of text that are set above their base
+/// text. They are commonly used in Japanese (_furigana_) and Chinese
+/// (_pinyin_ or _zhuyin_) to indicate the pronunciation of characters, for
+/// example in educational material.
+///
+/// The annotation can either be given as a whole, in which case it is centered
+/// above the whole base text, or as an array with one annotation per
+/// character of the base text. In the latter case, the
+/// @ruby.placement[`placement`] determines how the annotations are
+/// distributed.
+///
+/// = Example <example>
+/// ```example
+/// #set text(lang: "ja")
+/// #ruby[東京][とうきょう]に
+/// #ruby[行][い]きます。
+///
+/// #ruby("漢字", ("かん", "じ"))を
+/// #ruby[勉強][べんきょう]する。
+/// ```
+///
+/// = Line breaking <line-breaking>
+/// Ruby with a single annotation is never broken across lines. With one
+/// annotation per character, the text may be broken between the characters
+/// if the placement is `{"mono"}` or `{"jukugo"}`.
+///
+/// = Overhang <overhang>
+/// If an annotation is wider than its base text, it may extend over adjacent
+/// hiragana and katakana by up to the annotation's font size. This avoids
+/// gaps in the text that would otherwise be necessary to make room for the
+/// annotation. See @ruby.overhang[`overhang`] for more details.
+#[elem(since = "0.16.0", Tagged)]
+pub struct RubyElem {
+    /// How annotations given per character are placed above the base text.
+    ///
+    /// If set to `{auto}`, per-character annotations use `{"jukugo"}`
+    /// placement. A single annotation is always centered above the whole base
+    /// text.
+    ///
+    /// ```example
+    /// #set text(lang: "ja")
+    /// #let word = ("熟語", ("じゅく", "ご"))
+    ///
+    /// #ruby(..word, placement: "mono") \
+    /// #ruby(..word, placement: "group") \
+    /// #ruby(..word, placement: "jukugo")
+    /// ```
+    pub placement: Smart<RubyPlacement>,
+
+    /// The font size of the annotation.
+    ///
+    /// ```example
+    /// #set text(lang: "ja")
+    /// #set ruby(size: 0.4em)
+    /// #ruby[振][ふ]り#ruby[仮名][がな]
+    /// ```
+    #[default(TextSize(Em::new(0.5).into()))]
+    pub size: TextSize,
+
+    /// The space between the base text and the annotation.
+    ///
+    /// ```example
+    /// #set text(lang: "ja")
+    /// #set ruby(gap: 0.2em)
+    /// #ruby[振][ふ]り#ruby[仮名][がな]
+    /// ```
+    pub gap: Length,
+
+    /// Whether annotations that are wider than their base text may extend over
+    /// adjacent hiragana and katakana.
+    ///
+    /// When disabled, space is inserted around the base text instead.
+    ///
+    /// ```example
+    /// #set text(lang: "ja")
+    /// の#ruby[上][うえ]に \
+    /// の#ruby(overhang: false)[上][うえ]に
+    /// ```
+    #[default(true)]
+    pub overhang: bool,
+
+    /// The base text to annotate.
+    #[required]
+    pub base: Content,
+
+    /// The annotation to set above the base text.
+    ///
+    /// This can either be a single annotation for the whole base text or an
+    /// array with one annotation per character. In the latter case, the base
+    /// text must be plain text.
+    #[required]
+    pub annotation: OneOrMultiple<Content>,
+}
+
+impl Packed<RubyElem> {
+    /// Pairs up the pieces of the base text with their annotations.
+    ///
+    /// With a single annotation, the whole base text forms one piece.
+    /// Otherwise, each character of the base text is a piece.
+    pub fn pairs(&self) -> SourceResult<Vec<(Content, Content)>> {
+        let span = self.span();
+        let annotations = &self.annotation.0;
+        if let [annotation] = annotations.as_slice() {
+            return Ok(vec![(self.base.clone(), annotation.clone())]);
+        }
+
+        let Some(text) = self.base.to_packed::<TextElem>() else {
+            bail!(
+                span, "ruby base must be plain text when annotating each character";
+                hint: "try passing a single annotation instead";
+            );
+        };
+
+        let count = text.text.graphemes(true).count();
+        if count != annotations.len() {
+            bail!(
+                span,
+                "expected {count} ruby annotations, found {}",
+                annotations.len();
+                hint: "each character of the base text needs its own annotation";
+            );
+        }
+
+        Ok(text
+            .text
+            .graphemes(true)
+            .zip(annotations)
+            .map(|(c, annotation)| {
+                (TextElem::packed(c).spanned(self.base.span()), annotation.clone())
+            })
+            .collect())
+    }
+}
+
+/// How per-character ruby annotations are placed above the base text.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum RubyPlacement {
+    /// Each annotation is centered above its own character. Wide annotations
+    /// spread the characters apart.
+    Mono,
+    /// The annotations are joined and centered above the whole base text, as
+    /// if a single annotation was given.
+    Group,
+    /// Each annotation is centered above its own character if all of them
+    /// fit. Otherwise, the annotations are joined and distributed over the
+    /// base text as a whole. When the text is broken across lines, each
+    /// character keeps its own annotation.
+    Jukugo,
+}
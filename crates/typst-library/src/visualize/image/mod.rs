@@ -11,6 +11,7 @@ pub use self::raster::{
 pub use self::svg::SvgImage;
 
 use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 
@@ -245,10 +246,11 @@ impl Packed<ImageElem> {
         // Construct the image itself.
         let kind = match format {
             ImageFormat::Raster(format) => ImageKind::Raster(
-                RasterImage::new(
+                RasterImage::with_store(
                     loaded.data.clone(),
                     format,
                     self.icc.get_ref(styles).as_ref().map(|icc| icc.derived.clone()),
+                    engine.world.image_store(),
                 )
                 .at(span)?,
             ),
@@ -545,6 +547,31 @@ impl From<SvgImage> for ImageKind {
     }
 }
 
+/// A persistent store for decoded raster images, which a
+/// [`World`](crate::World) can provide to share decoding work across
+/// compilations and processes.
+///
+/// SVGs are not stored. Their conversion depends on the available fonts and a
+/// converted tree can only be written back as an SVG in which text has already
+/// been turned into paths, which would lose the text in PDF export.
+///
+/// The store deals in opaque buffers. It should return exactly what was stored
+/// for a key, but may forget entries at any time, e.g. to bound its size.
+pub trait ImageStore: Send + Sync {
+    /// Load the buffer stored for the given key.
+    fn load(&self, key: u128) -> Option<Vec<u8>>;
+
+    /// Store a buffer under the given key.
+    fn store(&self, key: u128, buf: &[u8]);
+}
+
+impl Hash for dyn ImageStore + '_ {
+    fn hash<H: Hasher>(&self, _: &mut H) {
+        // Decoding yields the same image with or without a store, so the store
+        // is irrelevant for memoization.
+    }
+}
+
 /// A raster or vector image format.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum ImageFormat {
@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use crate::diag::{StrResult, bail};
 use crate::foundations::{Bytes, Cast, Dict, Smart, Value, cast, dict};
+use crate::visualize::ImageStore;
 use ecow::{EcoString, eco_format};
 use image::codecs::gif::GifDecoder;
 use image::codecs::jpeg::JpegDecoder;
@@ -35,7 +36,22 @@ impl RasterImage {
         format: impl Into<RasterFormat>,
         icc: Smart<Bytes>,
     ) -> StrResult<Self> {
-        Self::new_impl(data, format.into(), icc)
+        Self::new_impl(data, format.into(), icc, None)
+    }
+
+    /// Decode a raster image, reusing the result of an earlier decoding of the
+    /// same data from a persistent store.
+    ///
+    /// The pixels of decoded PNG, JPEG, GIF, and WebP images with 8-bit
+    /// channels are written to the store. The result is the same as with
+    /// [`new`](Self::new).
+    pub fn with_store(
+        data: Bytes,
+        format: impl Into<RasterFormat>,
+        icc: Smart<Bytes>,
+        store: Option<&dyn ImageStore>,
+    ) -> StrResult<Self> {
+        Self::new_impl(data, format.into(), icc, store)
     }
 
     /// Create a raster image with optional properties set to the default.
@@ -50,11 +66,29 @@ impl RasterImage {
         data: Bytes,
         format: RasterFormat,
         icc: Smart<Bytes>,
+        store: Option<&dyn ImageStore>,
     ) -> StrResult<RasterImage> {
         let mut exif_rot = None;
 
         let (dynamic, icc, dpi) = match format {
             RasterFormat::Exchange(format) => {
+                // Decoded images may be persisted across processes, keyed by
+                // everything that determines the decoding result.
+                let key = typst_utils::hash128(&("raster", &data, format, &icc));
+                if let Some(cached) = store
+                    .and_then(|store| store.load(key))
+                    .and_then(|buf| Cached::decode(&buf))
+                {
+                    return Ok(Self(Arc::new(RasterImageInner {
+                        data,
+                        format: format.into(),
+                        exif_rotation: cached.exif_rotation,
+                        dynamic: Arc::new(cached.dynamic),
+                        icc: cached.icc,
+                        dpi: cached.dpi,
+                    })));
+                }
+
                 fn decode<T: ImageDecoder>(
                     decoder: ImageResult<T>,
                     icc: Smart<Bytes>,
@@ -95,6 +129,13 @@ impl RasterImage {
                 // Extract pixel density.
                 let dpi = determine_dpi(&data, exif.as_ref());
 
+                if let Some(store) = store
+                    && let Some(buf) =
+                        Cached::encode(&dynamic, icc.as_ref(), dpi, exif_rot)
+                {
+                    store.store(key, &buf);
+                }
+
                 (dynamic, icc, dpi)
             }
 
@@ -197,6 +238,115 @@ impl RasterImage {
     }
 }
 
+/// A decoded image as it is persisted in an [`ImageStore`].
+struct Cached {
+    dynamic: DynamicImage,
+    icc: Option<Bytes>,
+    dpi: Option<f64>,
+    exif_rotation: Option<u32>,
+}
+
+impl Cached {
+    /// Identifies the buffer layout. Bump this when changing it.
+    const MAGIC: &[u8; 8] = b"TYPRAS01";
+
+    /// Serialize a decoded image.
+    ///
+    /// Only images with 8-bit channels are supported. For others, this returns
+    /// `None` and the image is simply decoded again next time.
+    fn encode(
+        dynamic: &DynamicImage,
+        icc: Option<&Bytes>,
+        dpi: Option<f64>,
+        exif_rotation: Option<u32>,
+    ) -> Option<Vec<u8>> {
+        let color = match dynamic {
+            DynamicImage::ImageLuma8(_) => 0u8,
+            DynamicImage::ImageLumaA8(_) => 1,
+            DynamicImage::ImageRgb8(_) => 2,
+            DynamicImage::ImageRgba8(_) => 3,
+            _ => return None,
+        };
+
+        let pixels = dynamic.as_bytes();
+        let icc = icc.map(|icc| icc.as_slice());
+        let mut buf = Vec::with_capacity(38 + icc.map_or(0, <[u8]>::len) + pixels.len());
+        buf.extend_from_slice(Self::MAGIC);
+        buf.push(color);
+        buf.extend_from_slice(&dynamic.width().to_le_bytes());
+        buf.extend_from_slice(&dynamic.height().to_le_bytes());
+        buf.extend_from_slice(&exif_rotation.unwrap_or(0).to_le_bytes());
+        buf.extend_from_slice(&dpi.unwrap_or(0.0).to_le_bytes());
+        match icc {
+            Some(icc) => {
+                buf.push(1);
+                buf.extend_from_slice(&u32::try_from(icc.len()).ok()?.to_le_bytes());
+                buf.extend_from_slice(icc);
+            }
+            None => buf.push(0),
+        }
+        buf.extend_from_slice(pixels);
+        Some(buf)
+    }
+
+    /// Deserialize a decoded image. Returns `None` if the buffer is malformed.
+    fn decode(buf: &[u8]) -> Option<Self> {
+        fn take<'a>(buf: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+            let (head, tail) = buf.split_at_checked(n)?;
+            *buf = tail;
+            Some(head)
+        }
+
+        fn take_u32(buf: &mut &[u8]) -> Option<u32> {
+            Some(u32::from_le_bytes(take(buf, 4)?.try_into().ok()?))
+        }
+
+        let mut buf = buf;
+        if take(&mut buf, Self::MAGIC.len())? != Self::MAGIC {
+            return None;
+        }
+
+        let color = take(&mut buf, 1)?[0];
+        let width = take_u32(&mut buf)?;
+        let height = take_u32(&mut buf)?;
+        let exif_rotation = Some(take_u32(&mut buf)?).filter(|&r| r != 0);
+        let dpi = f64::from_le_bytes(take(&mut buf, 8)?.try_into().ok()?);
+        let dpi = Some(dpi).filter(|&dpi| dpi > 0.0);
+        let icc = match take(&mut buf, 1)?[0] {
+            0 => None,
+            _ => {
+                let len = take_u32(&mut buf)? as usize;
+                Some(Bytes::new(take(&mut buf, len)?.to_vec()))
+            }
+        };
+
+        let channels = [1, 2, 3, 4].get(usize::from(color))?;
+        if (width as usize)
+            .checked_mul(height as usize)?
+            .checked_mul(*channels)?
+            != buf.len()
+        {
+            return None;
+        }
+
+        let pixels = buf.to_vec();
+        let dynamic =
+            match color {
+                0 => ImageBuffer::from_raw(width, height, pixels)
+                    .map(DynamicImage::ImageLuma8),
+                1 => ImageBuffer::from_raw(width, height, pixels)
+                    .map(DynamicImage::ImageLumaA8),
+                2 => ImageBuffer::from_raw(width, height, pixels)
+                    .map(DynamicImage::ImageRgb8),
+                3 => ImageBuffer::from_raw(width, height, pixels)
+                    .map(DynamicImage::ImageRgba8),
+                _ => None,
+            }?;
+
+        Some(Self { dynamic, icc, dpi, exif_rotation })
+    }
+}
+
 impl Hash for RasterImageInner {
     fn hash<H: Hasher>(&self, state: &mut H) {
         // The image is fully defined by data, format, and ICC profile.
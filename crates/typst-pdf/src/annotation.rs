@@ -0,0 +1,226 @@
+//! Review annotations and the dispatch of interactive areas.
+//!
+//! Like form field widgets, review annotations are written as link annotations
+//! with a placeholder URI during conversion and turned into proper markup
+//! annotations after serialization.
+
+use krilla::action::{Action, LinkAction};
+use krilla::annotation::Target;
+use typst_library::diag::{At, ExpectInternal, SourceResult, bail};
+use typst_library::foundations::{Packed, StyleChain};
+use typst_library::introspection::Location;
+use typst_library::layout::Size;
+use typst_library::pdf::{AnnotationElem, AnnotationKind, FormField};
+
+use crate::convert::{FrameContext, GlobalContext};
+use crate::form;
+use crate::link::{LinkAnnotation, LinkAnnotationKind, bounding_box};
+use crate::patch::{Dict, Object, PdfFile, Ref};
+use crate::tags::{self, GroupId};
+
+/// The prefix of the placeholder URIs that mark review annotations.
+const PLACEHOLDER: &str = "typst-annotation:";
+
+/// Handles the interactive area of the element at the given location.
+pub(crate) fn handle_annotation(
+    fc: &mut FrameContext,
+    gc: &mut GlobalContext,
+    loc: Location,
+    size: Size,
+) -> SourceResult<()> {
+    let Some(elem) = gc.document.introspector().elements().get_by_loc(&loc) else {
+        return Ok(());
+    };
+
+    if let Some(field) = FormField::from_content(elem) {
+        form::handle_field(fc, gc, field, size)
+    } else if let Some(annotation) = elem.to_packed::<AnnotationElem>() {
+        handle_review(fc, gc, loc, annotation.clone(), size)
+    } else {
+        Ok(())
+    }
+}
+
+/// Places a review annotation or extends it by another area if the
+/// annotation already exists on the current page.
+fn handle_review(
+    fc: &mut FrameContext,
+    gc: &mut GlobalContext,
+    loc: Location,
+    elem: Packed<AnnotationElem>,
+    size: Size,
+) -> SourceResult<()> {
+    let rect = bounding_box(fc, size);
+    if let Some(annotation) = fc.get_annotation_for(loc) {
+        annotation.rects.push(rect);
+        return Ok(());
+    }
+
+    let span = elem.span();
+    if let Some(archival) = gc.options.archival_validator() {
+        let validator = archival.as_str();
+        bail!(
+            span,
+            "{validator} error: annotations are not supported";
+            hint: "the appearance of annotations is generated by the PDF reader";
+        );
+    }
+
+    // Annotations in tilings would be repeated arbitrarily often.
+    if gc.tags.in_tiling {
+        bail!(span, "annotations are not supported in tilings");
+    }
+
+    let alt = Some(elem.contents.to_string());
+    let uri = format!("{PLACEHOLDER}{}", gc.markup_annotations.len());
+    let target = Target::Action(Action::Link(LinkAction::new(uri)));
+    gc.markup_annotations.push(elem);
+
+    if tags::disabled(gc) || gc.tags.tree.parent_artifact().is_some() {
+        fc.push_annotation_for(
+            loc,
+            GroupId::INVALID,
+            LinkAnnotation {
+                kind: LinkAnnotationKind::Artifact,
+                alt,
+                span,
+                rects: vec![rect],
+                target,
+            },
+        );
+        return Ok(());
+    }
+
+    let group_id = (gc.tags.tree.parent_annot())
+        .expect_internal("expected annotation ancestor in logical tree")
+        .at(span)?;
+    let annot_id = gc.tags.annotations.reserve();
+    fc.push_annotation_for(
+        loc,
+        group_id,
+        LinkAnnotation {
+            kind: LinkAnnotationKind::Tagged(annot_id),
+            alt,
+            span,
+            rects: vec![rect],
+            target,
+        },
+    );
+    gc.tags.tree.groups.get_mut(group_id).push_annotation(annot_id);
+
+    Ok(())
+}
+
+/// Turns the placeholder annotations into markup annotations.
+pub(crate) fn write_annotations(file: &mut PdfFile, elems: &[Packed<AnnotationElem>]) {
+    let annots = file.placeholders(PLACEHOLDER, elems.len());
+    for (i, elem) in elems.iter().enumerate() {
+        let Some(&(annot, page)) = annots.get(&i) else { continue };
+        let placeholder = file.dict(annot).cloned().unwrap_or_default();
+        file.set(annot, annotation_dict(elem, &placeholder, page));
+    }
+}
+
+/// Creates the dictionary of a markup annotation that replaces a placeholder.
+fn annotation_dict(
+    elem: &Packed<AnnotationElem>,
+    placeholder: &Dict,
+    page: Option<Ref>,
+) -> Dict {
+    let styles = StyleChain::default();
+    let kind = elem.kind.get(styles);
+    let subtype = match kind {
+        AnnotationKind::Note => "Text",
+        AnnotationKind::Highlight => "Highlight",
+        AnnotationKind::Underline => "Underline",
+        AnnotationKind::FreeText => "FreeText",
+    };
+
+    let rgb = elem.color.get(styles).to_rgb();
+    let color = [rgb.red, rgb.green, rgb.blue].map(|c| f64::from(c).into()).to_vec();
+
+    // Unlike the placeholder link, annotations are printed.
+    let mut dict = Dict::new()
+        .with("Type", Object::name("Annot"))
+        .with("Subtype", Object::name(subtype))
+        .with("F", 4)
+        .with("Contents", Object::text(&elem.contents))
+        .with("C", Object::Array(color));
+    if let Some(value) = placeholder.get("StructParent") {
+        dict.set("StructParent", value.clone());
+    }
+    if let Some(page) = page {
+        dict.set("P", page);
+    }
+    if let Some(author) = elem.author.get_ref(styles) {
+        dict.set("T", Object::text(author));
+    }
+
+    let rects = rects(placeholder);
+    let bbox = rects
+        .iter()
+        .copied()
+        .reduce(|a, b| [a[0].min(b[0]), a[1].min(b[1]), a[2].max(b[2]), a[3].max(b[3])]);
+    let to_array = |rect: [f64; 4]| Object::Array(rect.map(Object::from).to_vec());
+
+    match kind {
+        AnnotationKind::Note => {
+            // The note's icon is placed at the start of the body.
+            if let Some(&first) = rects.first() {
+                dict.set("Rect", to_array(first));
+            }
+            dict.set("Name", Object::name("Comment"));
+            dict.set("Open", elem.open.get(styles));
+        }
+        AnnotationKind::Highlight | AnnotationKind::Underline => {
+            if let Some(bbox) = bbox {
+                dict.set("Rect", to_array(bbox));
+            }
+
+            // Each quadrilateral starts with the upper edge, as expected by
+            // PDF readers.
+            let quads = rects
+                .iter()
+                .flat_map(|&[x0, y0, x1, y1]| [x0, y1, x1, y1, x0, y0, x1, y0])
+                .map(Object::from)
+                .collect();
+            dict.set("QuadPoints", Object::Array(quads));
+        }
+        AnnotationKind::FreeText => {
+            if let Some(bbox) = bbox {
+                dict.set("Rect", to_array(bbox));
+            }
+            dict.set("DA", Object::text("/Helv 10 Tf 0 g"));
+        }
+    }
+
+    dict
+}
+
+/// Returns the rectangles covered by the placeholder, each as the lower left
+/// and upper right corner.
+fn rects(placeholder: &Dict) -> Vec<[f64; 4]> {
+    let numbers = |key: &str| -> Vec<f64> {
+        placeholder
+            .get(key)
+            .and_then(Object::as_array)
+            .map(|array| array.iter().filter_map(Object::as_f64).collect())
+            .unwrap_or_default()
+    };
+
+    let bounds = |points: &[f64]| {
+        let (xs, ys): (Vec<f64>, Vec<f64>) =
+            points.chunks_exact(2).map(|p| (p[0], p[1])).unzip();
+        let min = |v: &[f64]| v.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = |v: &[f64]| v.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        [min(&xs), min(&ys), max(&xs), max(&ys)]
+    };
+
+    let quads = numbers("QuadPoints");
+    if !quads.is_empty() {
+        return quads.chunks_exact(8).map(bounds).collect();
+    }
+
+    let rect = numbers("Rect");
+    if rect.len() == 4 { vec![bounds(&rect)] } else { vec![] }
+}
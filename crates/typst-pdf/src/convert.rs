@@ -21,22 +21,31 @@ use typst_layout::PagedDocument;
 use typst_library::diag::{
     At, ExpectInternal, SourceDiagnostic, SourceResult, bail, error,
 };
-use typst_library::foundations::{NativeElement, Repr};
+use typst_library::foundations::{NativeElement, Packed, Repr, Smart};
 use typst_library::introspection::{Introspector, Location, PagedPosition, Tag};
 use typst_library::layout::{Abs, Frame, FrameItem, GroupItem, Sides, Size, Transform};
-use typst_library::model::{HeadingElem, LateLinkResolver};
-use typst_library::text::FontInstance;
+use typst_library::model::{Document as _, HeadingElem, LateLinkResolver};
+use typst_library::pdf::AnnotationElem;
+use typst_library::text::{FontInstance, RubyElem};
 use typst_library::visualize::{Geometry, Paint, SpotColorantName};
 use typst_syntax::Span;
 
 use crate::PdfOptions;
+use crate::annotation;
 use crate::attach::attach_files;
+use crate::encrypt;
+use crate::form::{self, Widget};
 use crate::image::handle_image;
+use crate::layer::{self, Layers};
 use crate::link::{LinkAnnotation, handle_link};
-use crate::metadata::build_metadata;
+use crate::metadata::{build_metadata, write_custom};
 use crate::outline::build_outline;
-use crate::page::PageLabelExt;
+use crate::page::{PageLabelExt, PageLabelOverrides};
+use crate::patch;
+use crate::pdfx::{self, PdfX};
+use crate::ruby;
 use crate::shape::handle_shape;
+use crate::sign;
 use crate::tags::{self, GroupId, Tags};
 use crate::text::handle_text;
 use crate::util::{
@@ -51,9 +60,14 @@ pub fn convert(
     anchors: &[(Location, EcoString)],
     link_resolver: Option<Tracked<LateLinkResolver>>,
 ) -> SourceResult<Vec<u8>> {
+    if let Some(encryption) = &options.encryption {
+        encryption.validate(&options.standards).at(Span::detached())?;
+    }
+
     let settings = SerializeSettings {
         compress_content_streams: !options.pretty,
-        no_device_cs: true,
+        // PDF/X-1a only allows device colors.
+        no_device_cs: options.standards.pdfx != Some(PdfX::X1a),
         ascii_compatible: options.pretty,
         xmp_metadata: true,
         cmyk_profile: None,
@@ -83,80 +97,174 @@ pub fn convert(
         tags,
     );
 
+    if let Some(x) = options.standards.pdfx {
+        pdfx::validate_document(&gc, x)?;
+    }
+
     convert_pages(&mut gc, &mut document)?;
     attach_files(&gc, &mut document)?;
+
+    // The output intent is checked after the content, so that problems with
+    // the document are reported even before an output profile is chosen.
+    if let Some(x) = options.standards.pdfx {
+        pdfx::validate_output_intent(options, x)?;
+    }
+
     let (doc_lang, tree) = tags::resolve(&mut gc)?;
 
     document.set_outline(build_outline(&gc));
     document.set_metadata(build_metadata(&gc, doc_lang));
     document.set_tag_tree(tree);
 
-    finish(document, gc, options.standards.config)
+    let widgets = std::mem::take(&mut gc.widgets);
+    let annotations = std::mem::take(&mut gc.markup_annotations);
+    let layers = std::mem::take(&mut gc.layers);
+    let metadata = &typst_document.info().metadata;
+    // Ruby structure elements were only introduced with PDF 1.5.
+    let rubies = options.tagged
+        && !matches!(options.standards.config.version(), PdfVersion::Pdf14)
+        && !typst_document
+            .introspector()
+            .query(&RubyElem::ELEM.select())
+            .is_empty();
+    let pdf = finish(document, gc, options.standards.config)?;
+    if widgets.is_empty()
+        && annotations.is_empty()
+        && layers.is_empty()
+        && metadata.is_empty()
+        && !rubies
+        && options.standards.pdfx.is_none()
+        && options.encryption.is_none()
+        && options.signature.is_none()
+    {
+        return Ok(pdf);
+    }
+
+    let mut pdf = patch::patch(pdf, |file| {
+        if !widgets.is_empty() || options.signature.is_some() {
+            form::write_fields(file, &widgets, options)?;
+        }
+
+        if !annotations.is_empty() {
+            annotation::write_annotations(file, &annotations);
+        }
+
+        if !layers.is_empty() {
+            layer::write_layers(file, &layers);
+        }
+
+        if rubies {
+            ruby::write_ruby(file);
+        }
+
+        if !metadata.is_empty() {
+            write_custom(file, metadata, options.standards.config.version());
+        }
+
+        if let Some(x) = options.standards.pdfx
+            && let Some(intent) = &options.output_intent
+        {
+            pdfx::write_pdfx(file, x, intent);
+        }
+
+        // Encryption must come last as it affects all strings and streams.
+        if let Some(encryption) = &options.encryption {
+            encrypt::encrypt(file, encryption).at(Span::detached())?;
+        }
+
+        Ok(())
+    })?;
+
+    // The byte range can only be filled in once the file's layout is final.
+    if options.signature.is_some() {
+        sign::write_byte_range(&mut pdf).at(Span::detached())?;
+    }
+
+    Ok(pdf)
 }
 
 fn convert_pages(gc: &mut GlobalContext, document: &mut Document) -> SourceResult<()> {
+    let overrides = PageLabelOverrides::new(gc.document);
     for (i, typst_page) in gc.document.pages().iter().enumerate() {
         if gc.page_index_converter.pdf_page_index(i).is_none() {
             // Don't export this page.
             continue;
         }
 
+        let (bleed, marks) = (typst_page.bleed, typst_page.marks);
+        let outset = typst_page.outset();
+
         // PDF 1.4 upwards to 1.7 specifies a minimum page size of 3x3 units.
         // PDF 2.0 doesn't define an explicit limit, but krilla and probably
         // some viewers won't handle pages that have zero sized pages.
         let mut settings = PageSettings::from_wh(
-            (typst_page.frame.width() + typst_page.bleed.left + typst_page.bleed.right)
+            (typst_page.frame.width() + outset.left + outset.right)
                 .to_f32()
                 .max(3.0),
-            (typst_page.frame.height() + typst_page.bleed.top + typst_page.bleed.bottom)
+            (typst_page.frame.height() + outset.top + outset.bottom)
                 .to_f32()
                 .max(3.0),
         )
         .expect_internal("invalid page size")
         .at(Span::detached())?;
 
-        if !typst_page.bleed.is_zero() {
+        if !outset.is_zero() {
             settings = settings.with_trim_box(Rect::from_ltrb(
-                typst_page.bleed.left.to_f32(),
-                typst_page.bleed.top.to_f32(),
-                (typst_page.bleed.left + typst_page.frame.width()).to_f32(),
-                (typst_page.bleed.top + typst_page.frame.height()).to_f32(),
+                outset.left.to_f32(),
+                outset.top.to_f32(),
+                (outset.left + typst_page.frame.width()).to_f32(),
+                (outset.top + typst_page.frame.height()).to_f32(),
             ));
         }
 
-        if let Some(label) = typst_page
-            .numbering
-            .as_ref()
-            .and_then(|num| PageLabel::generate(num, typst_page.number))
-            .or_else(|| {
-                // When some pages were ignored from export, we show a page label with
-                // the correct real (not logical) page number.
-                // This is for consistency with normal output when pages have no numbering
-                // and all are exported: the final PDF page numbers always correspond to
-                // the real (not logical) page numbers. Here, the final PDF page number
-                // will differ, but we can at least use labels to indicate what was
-                // the corresponding real page number in the Typst document.
-                gc.page_index_converter
-                    .has_skipped_pages()
-                    .then(|| PageLabel::arabic((i + 1) as u64))
-            })
-        {
+        if !marks.is_zero() {
+            settings = settings.with_bleed_box(Rect::from_ltrb(
+                marks.left.to_f32(),
+                marks.top.to_f32(),
+                (outset.left + typst_page.frame.width() + bleed.right).to_f32(),
+                (outset.top + typst_page.frame.height() + bleed.bottom).to_f32(),
+            ));
+        }
+
+        let label = match overrides.get(i) {
+            Smart::Custom(label) => Some(label),
+            Smart::Auto => typst_page
+                .numbering
+                .as_ref()
+                .and_then(|num| PageLabel::generate(num, typst_page.number))
+                .or_else(|| {
+                    // When some pages were ignored from export, we show a page label with
+                    // the correct real (not logical) page number.
+                    // This is for consistency with normal output when pages have no numbering
+                    // and all are exported: the final PDF page numbers always correspond to
+                    // the real (not logical) page numbers. Here, the final PDF page number
+                    // will differ, but we can at least use labels to indicate what was
+                    // the corresponding real page number in the Typst document.
+                    gc.page_index_converter
+                        .has_skipped_pages()
+                        .then(|| PageLabel::arabic((i + 1) as u64))
+                }),
+        };
+
+        if let Some(label) = label {
             settings = settings.with_page_label(label);
         }
 
         let mut page = document.start_page_with(settings);
         let mut surface = page.surface();
         let page_idx = gc.page_index_converter.pdf_page_index(i);
-        let mut fc = FrameContext::new(
-            page_idx,
-            typst_page.frame.size() + typst_page.bleed.sum_by_axis(),
-        );
+        let mut fc =
+            FrameContext::new(page_idx, typst_page.frame.size() + outset.sum_by_axis());
+
+        // The printer's marks surround the bleed, which the page's fill
+        // covers.
+        fc.state_mut().pre_concat(Transform::translate(marks.left, marks.top));
 
         tags::page(gc, &mut surface, |gc, surface| {
             handle_frame(
                 &mut fc,
                 &typst_page.frame,
-                typst_page.bleed,
+                bleed,
                 typst_page.fill_or_transparent(),
                 surface,
                 gc,
@@ -224,6 +332,9 @@ pub(crate) struct FrameContext {
     states: Vec<State>,
     /// The link annotations belonging to a Link tag.
     link_annotations: IndexMap<GroupId, SmallVec<[LinkAnnotation; 1]>, FxBuildHasher>,
+    /// The positions of the link annotations that stand in for review
+    /// annotations in `link_annotations`, by the annotation's location.
+    review_annotations: FxHashMap<Location, (GroupId, usize)>,
 }
 
 impl FrameContext {
@@ -232,6 +343,7 @@ impl FrameContext {
             page_idx,
             states: vec![State::new(size)],
             link_annotations: IndexMap::default(),
+            review_annotations: FxHashMap::default(),
         }
     }
 
@@ -272,6 +384,25 @@ impl FrameContext {
         let annotations = self.link_annotations.entry(id).or_default();
         annotations.push(annotation);
     }
+
+    pub(crate) fn get_annotation_for(
+        &mut self,
+        loc: Location,
+    ) -> Option<&mut LinkAnnotation> {
+        let &(id, i) = self.review_annotations.get(&loc)?;
+        self.link_annotations.get_mut(&id)?.get_mut(i)
+    }
+
+    pub(crate) fn push_annotation_for(
+        &mut self,
+        loc: Location,
+        id: GroupId,
+        annotation: LinkAnnotation,
+    ) {
+        let annotations = self.link_annotations.entry(id).or_default();
+        self.review_annotations.insert(loc, (id, annotations.len()));
+        annotations.push(annotation);
+    }
 }
 
 /// Globally needed context for converting a Typst document.
@@ -298,6 +429,12 @@ pub(crate) struct GlobalContext<'a> {
     pub(crate) page_index_converter: PageIndexConverter,
     /// Tagged PDF context.
     pub(crate) tags: Tags,
+    /// The form field widgets placed so far.
+    pub(crate) widgets: Vec<Widget>,
+    /// The review annotations placed so far, once per page they appear on.
+    pub(crate) markup_annotations: Vec<Packed<AnnotationElem>>,
+    /// The layers used so far.
+    pub(crate) layers: Layers,
 }
 
 impl<'a> GlobalContext<'a> {
@@ -320,6 +457,9 @@ impl<'a> GlobalContext<'a> {
             image_spans: FxHashSet::default(),
             page_index_converter,
             tags,
+            widgets: vec![],
+            markup_annotations: vec![],
+            layers: Layers::default(),
         }
     }
 }
@@ -369,6 +509,9 @@ pub(crate) fn handle_frame(
                 handle_image(gc, fc, image, *size, surface, *span)?;
             }
             FrameItem::Link(dest, size) => handle_link(fc, gc, dest, *size)?,
+            FrameItem::Annotation(loc, size) => {
+                annotation::handle_annotation(fc, gc, *loc, *size)?
+            }
             FrameItem::Tag(Tag::Start(_, flags)) => {
                 if flags.tagged {
                     tags::handle_start(gc, fc, surface);
@@ -400,6 +543,11 @@ pub(crate) fn handle_group(
     fc.state_mut().pre_concat(group.transform);
 
     tags::group(gc, fc, surface, group.parent, |gc, fc, surface| {
+        let layered = match &group.layer {
+            Some(layer) => layer::push_layer(gc, surface, layer)?,
+            None => false,
+        };
+
         let clip_path = group
             .clip
             .as_ref()
@@ -421,6 +569,10 @@ pub(crate) fn handle_group(
             surface.pop();
         }
 
+        if layered {
+            surface.pop();
+        }
+
         res
     })?;
 
@@ -0,0 +1,367 @@
+//! Encryption with the standard security handler.
+//!
+//! krilla doesn't support encryption, so the finished file is encrypted in a
+//! post-processing step. We only implement AES-256 encryption as specified in
+//! PDF 2.0 (revision 6 of the standard security handler). The older revisions
+//! are either insecure or deprecated.
+//!
+//! The file encryption key, the salts, and the initialization vectors are
+//! drawn from the operating system's secure random number generator. Hence,
+//! encrypted files are not reproducible.
+
+use aes::cipher::block_padding::{NoPadding, Pkcs7};
+use aes::cipher::{BlockEncrypt, BlockEncryptMut, KeyInit, KeyIvInit};
+use aes::{Aes128, Aes256};
+use ecow::eco_format;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use typst_library::diag::StrResult;
+
+use crate::patch::{Dict, Indirect, Object, PdfFile};
+use crate::{PdfEncryption, PdfPermissions};
+
+/// Encrypts all strings and streams in the file and adds the encryption
+/// dictionary.
+pub(crate) fn encrypt(file: &mut PdfFile, encryption: &PdfEncryption) -> StrResult<()> {
+    ensure_id(file);
+
+    let key = random::<32>()?;
+    let salts = random::<32>()?;
+    let mut cipher = Cipher { key, pool: vec![] };
+    for (_, object) in file.iter_mut() {
+        match object {
+            // The contents of a signature are filled in after export and
+            // must not be encrypted.
+            Indirect::Object(Object::Dict(dict)) if dict.is_type("Sig") => {
+                for (key, value) in dict.iter_mut() {
+                    if *key != *"Contents" {
+                        cipher.encrypt_object(value)?;
+                    }
+                }
+            }
+            Indirect::Object(object) => cipher.encrypt_object(object)?,
+            Indirect::Stream(stream) => {
+                for (_, value) in stream.dict.iter_mut() {
+                    cipher.encrypt_object(value)?;
+                }
+                stream.data = cipher.encrypt(&stream.data)?;
+            }
+        }
+    }
+
+    let permissions = permissions(&encryption.permissions);
+    let user = prepare(&encryption.user_password);
+
+    // Without an owner password, nobody should get full access, so we pick
+    // one that nobody knows.
+    let random_owner;
+    let owner = match &encryption.owner_password {
+        Some(password) => prepare(password),
+        None => {
+            random_owner = random::<32>()?;
+            &random_owner
+        }
+    };
+
+    // Algorithm 8 of ISO 32000-2: The user password's validation data and
+    // the file key, encrypted with the user password.
+    let (validation_salt, key_salt) = (&salts[0..8], &salts[8..16]);
+    let mut u = hash(user, validation_salt, &[]).to_vec();
+    u.extend(validation_salt);
+    u.extend(key_salt);
+    let ue = encrypt_key(&hash(user, key_salt, &[]), &key);
+
+    // Algorithm 9: The same for the owner password, also taking the user
+    // data into account.
+    let (validation_salt, key_salt) = (&salts[16..24], &salts[24..32]);
+    let mut o = hash(owner, validation_salt, &u).to_vec();
+    o.extend(validation_salt);
+    o.extend(key_salt);
+    let oe = encrypt_key(&hash(owner, key_salt, &u), &key);
+
+    // Algorithm 10: The permissions, encrypted with the file key so that
+    // they cannot be tampered with.
+    let mut perms = [0; 16];
+    perms[..4].copy_from_slice(&permissions.to_le_bytes());
+    perms[4..8].fill(0xff);
+    perms[8] = b'T';
+    perms[9..12].copy_from_slice(b"adb");
+    perms[12..].copy_from_slice(&random::<4>()?);
+    let mut block = aes::Block::from(perms);
+    Aes256::new_from_slice(&key).unwrap().encrypt_block(&mut block);
+
+    let filter = Dict::new()
+        .with("Type", Object::name("CryptFilter"))
+        .with("CFM", Object::name("AESV3"))
+        .with("AuthEvent", Object::name("DocOpen"))
+        .with("Length", 32);
+    let dict = Dict::new()
+        .with("Filter", Object::name("Standard"))
+        .with("V", 5)
+        .with("R", 6)
+        .with("Length", 256)
+        .with("CF", Dict::new().with("StdCF", filter))
+        .with("StmF", Object::name("StdCF"))
+        .with("StrF", Object::name("StdCF"))
+        .with("O", Object::Str(o))
+        .with("U", Object::Str(u))
+        .with("OE", Object::Str(oe))
+        .with("UE", Object::Str(ue))
+        .with("P", i64::from(permissions))
+        .with("Perms", Object::Str(block.to_vec()))
+        .with("EncryptMetadata", true);
+
+    let encrypt = file.add(dict);
+    file.trailer_mut().set("Encrypt", encrypt);
+
+    // AES-256 was added to PDF 1.7 by an Adobe extension.
+    if file.version() == b"1.7"
+        && let Some(catalog) = file.catalog().and_then(|r| file.dict_mut(r))
+    {
+        let adobe = Dict::new()
+            .with("BaseVersion", Object::name("1.7"))
+            .with("ExtensionLevel", 8);
+        catalog.set("Extensions", Dict::new().with("ADBE", adobe));
+    }
+
+    Ok(())
+}
+
+/// Adds a document identifier if the file doesn't have one yet. It is
+/// required for encrypted files.
+fn ensure_id(file: &mut PdfFile) {
+    if file.trailer().get("ID").and_then(Object::as_array).is_some() {
+        return;
+    }
+
+    let id = Sha256::digest(file.write())[..16].to_vec();
+    let pair = vec![Object::Str(id.clone()), Object::Str(id)];
+    file.trailer_mut().set("ID", pair);
+}
+
+/// Draws bytes from the operating system's secure random number generator.
+fn random<const N: usize>() -> StrResult<[u8; N]> {
+    let mut buf = [0; N];
+    getrandom::fill(&mut buf)
+        .map_err(|err| eco_format!("failed to generate encryption key ({err})"))?;
+    Ok(buf)
+}
+
+/// Prepares a password for hashing.
+///
+/// The specification requires normalizing the password with SASLprep. For
+/// printable ASCII, which is all that [`PdfEncryption::validate`] permits,
+/// SASLprep doesn't change the password.
+fn prepare(password: &str) -> &[u8] {
+    let bytes = password.as_bytes();
+    &bytes[..bytes.len().min(127)]
+}
+
+/// Computes the value of the `/P` entry.
+fn permissions(permissions: &PdfPermissions) -> i32 {
+    // Bits 7, 8, and 13 to 32 must be set. Bit 10 permits text extraction for
+    // accessibility, which PDF/UA requires and PDF 2.0 deprecates.
+    let mut p = 0xffff_f2c0_u32;
+    let mut permit = |set: bool, bit: u32| {
+        if set {
+            p |= 1 << (bit - 1);
+        }
+    };
+
+    permit(permissions.print, 3);
+    permit(permissions.modify, 4);
+    permit(permissions.copy, 5);
+    permit(permissions.annotate, 6);
+    permit(permissions.fill_forms, 9);
+    permit(permissions.assemble, 11);
+    permit(permissions.print && permissions.print_high_quality, 12);
+    p as i32
+}
+
+/// Algorithm 2.B of ISO 32000-2: Computes a hash of a password.
+fn hash(password: &[u8], salt: &[u8], user: &[u8]) -> [u8; 32] {
+    let mut k = Sha256::new()
+        .chain_update(password)
+        .chain_update(salt)
+        .chain_update(user)
+        .finalize()
+        .to_vec();
+
+    let mut round = 0;
+    loop {
+        let mut k1 = Vec::with_capacity(64 * (password.len() + k.len() + user.len()));
+        for _ in 0..64 {
+            k1.extend(password);
+            k1.extend(&k);
+            k1.extend(user);
+        }
+
+        let e = cbc::Encryptor::<Aes128>::new_from_slices(&k[..16], &k[16..32])
+            .unwrap()
+            .encrypt_padded_vec_mut::<NoPadding>(&k1);
+
+        // The first 16 bytes of `e` as a big-endian number modulo 3. Since
+        // 256 % 3 == 1, this is the same as the sum of the bytes modulo 3.
+        k = match e[..16].iter().map(|&b| u32::from(b)).sum::<u32>() % 3 {
+            0 => Sha256::digest(&e).to_vec(),
+            1 => Sha384::digest(&e).to_vec(),
+            _ => Sha512::digest(&e).to_vec(),
+        };
+
+        round += 1;
+        if round >= 64 && u32::from(e[e.len() - 1]) <= round - 32 {
+            break;
+        }
+    }
+
+    k[..32].try_into().unwrap()
+}
+
+/// Encrypts the file key with a key derived from a password.
+fn encrypt_key(intermediate: &[u8; 32], key: &[u8; 32]) -> Vec<u8> {
+    cbc::Encryptor::<Aes256>::new_from_slices(intermediate, &[0; 16])
+        .unwrap()
+        .encrypt_padded_vec_mut::<NoPadding>(key)
+}
+
+/// Encrypts strings and streams with the file key.
+struct Cipher {
+    key: [u8; 32],
+    /// Random bytes for initialization vectors, drawn in bulk to avoid a
+    /// system call per string.
+    pool: Vec<u8>,
+}
+
+impl Cipher {
+    /// Encrypts all strings in the object.
+    fn encrypt_object(&mut self, object: &mut Object) -> StrResult<()> {
+        match object {
+            Object::Str(data) => *data = self.encrypt(data)?,
+            Object::Array(items) => {
+                for item in items {
+                    self.encrypt_object(item)?;
+                }
+            }
+            Object::Dict(dict) => {
+                for (_, value) in dict.iter_mut() {
+                    self.encrypt_object(value)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Encrypts data, prefixing it with a random initialization vector.
+    fn encrypt(&mut self, data: &[u8]) -> StrResult<Vec<u8>> {
+        if self.pool.len() < 16 {
+            self.pool.extend(random::<4096>()?);
+        }
+
+        let iv = self.pool.split_off(self.pool.len() - 16);
+        let mut out = iv.clone();
+        out.extend(
+            cbc::Encryptor::<Aes256>::new_from_slices(&self.key, &iv)
+                .unwrap()
+                .encrypt_padded_vec_mut::<Pkcs7>(data),
+        );
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::cipher::{BlockDecrypt, BlockDecryptMut};
+
+    use super::*;
+    use crate::patch::Ref;
+
+    #[test]
+    fn test_permissions() {
+        let all = PdfPermissions::default();
+        let none = PdfPermissions {
+            print: false,
+            print_high_quality: false,
+            modify: false,
+            copy: false,
+            annotate: false,
+            fill_forms: false,
+            assemble: false,
+        };
+        assert_eq!(permissions(&all), -4);
+        assert_eq!(permissions(&none), -3392);
+        assert_eq!(permissions(&PdfPermissions { print: true, ..none }), -3388);
+    }
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn decrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Vec<u8> {
+        cbc::Decryptor::<Aes256>::new_from_slices(key, iv)
+            .unwrap()
+            .decrypt_padded_vec_mut::<NoPadding>(data)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_hash() {
+        // Computed with an independent implementation of Algorithm 2.B.
+        assert_eq!(
+            hash(b"user", b"12345678", &[]).to_vec(),
+            hex("33a74805a1940282ca67d2b4938a4f77db6f69c75e92e9f281f0743ef0111571"),
+        );
+        assert_eq!(
+            hash(b"owner", b"abcdefgh", &(0..48).collect::<Vec<u8>>()).to_vec(),
+            hex("e4eb4cb643a70d7b4aa20dfdd1448ec14283e6184d750bb804bb60f7c6a7f762"),
+        );
+        assert_eq!(
+            hash(b"", &[0; 8], &[]).to_vec(),
+            hex("439feba099a63d0d035a1e5fb67ff307329189584956425aff2d3bd3d15edc60"),
+        );
+    }
+
+    #[test]
+    fn test_decrypt() {
+        let mut file = PdfFile::from_objects([
+            Dict::new().with("Type", Object::name("Catalog")).into(),
+            Dict::new().with("Title", Object::Str(b"Hello".to_vec())).into(),
+        ]);
+        let encryption = PdfEncryption {
+            user_password: "user".into(),
+            owner_password: Some("owner".into()),
+            permissions: PdfPermissions { copy: false, ..Default::default() },
+        };
+        encrypt(&mut file, &encryption).unwrap();
+
+        let r = file.trailer().get("Encrypt").and_then(Object::as_ref).unwrap();
+        let dict = file.dict(r).unwrap();
+        let get = |key: &str| dict.get(key).and_then(Object::as_str).unwrap();
+        let (u, ue, o, oe) = (get("U"), get("UE"), get("O"), get("OE"));
+
+        // Algorithm 2.A of ISO 32000-2: Retrieve the file key with the user
+        // password and with the owner password.
+        assert_eq!(hash(b"user", &u[32..40], &[]), u[..32]);
+        assert_ne!(hash(b"owner", &u[32..40], &[]), u[..32]);
+        let key = decrypt(&hash(b"user", &u[40..48], &[]), &[0; 16], ue);
+        assert_eq!(hash(b"owner", &o[32..40], &u[..48]), o[..32]);
+        assert_eq!(decrypt(&hash(b"owner", &o[40..48], &u[..48]), &[0; 16], oe), key);
+
+        // Algorithm 13: The permissions must match the `/P` entry.
+        let mut perms = aes::Block::clone_from_slice(get("Perms"));
+        Aes256::new_from_slice(&key).unwrap().decrypt_block(&mut perms);
+        let p = dict.get("P").and_then(Object::as_int).unwrap();
+        assert_eq!(&perms[9..12], b"adb");
+        assert_eq!(i64::from(i32::from_le_bytes(perms[..4].try_into().unwrap())), p);
+        assert_eq!(p, i64::from(permissions(&encryption.permissions)));
+
+        let title = file.dict(Ref(2)).unwrap().get("Title").unwrap().as_str().unwrap();
+        let plain = cbc::Decryptor::<Aes256>::new_from_slices(&key, &title[..16])
+            .unwrap()
+            .decrypt_padded_vec_mut::<Pkcs7>(&title[16..])
+            .unwrap();
+        assert_eq!(plain, b"Hello");
+    }
+}
@@ -0,0 +1,557 @@
+//! Interactive form fields.
+//!
+//! krilla doesn't support form fields, so during conversion, each field's
+//! widget is written as a link annotation with a placeholder URI. This way,
+//! krilla takes care of positioning and tagging it. After serialization, the
+//! placeholders are turned into proper widget annotations and the fields are
+//! registered in the document's interactive form dictionary.
+
+use krilla::action::{Action, LinkAction};
+use krilla::annotation::Target;
+use rustc_hash::FxHashMap;
+use typst_library::diag::{At, ExpectInternal, SourceResult, bail};
+use typst_library::foundations::StyleChain;
+use typst_library::layout::Size;
+use typst_library::pdf::FormField;
+use typst_syntax::Span;
+
+use crate::convert::{FrameContext, GlobalContext};
+use crate::link::{LinkAnnotation, LinkAnnotationKind, bounding_box};
+use crate::patch::{Dict, Name, Object, PdfFile, Ref, Stream};
+use crate::sign;
+use crate::tags::{self, GroupId};
+use crate::{PdfOptions, PdfSignature};
+
+/// The prefix of the placeholder URIs that mark widget annotations.
+const PLACEHOLDER: &str = "typst-form-widget:";
+
+/// The field flags shared by all kinds of fields.
+const READ_ONLY: i64 = 1 << 0;
+const REQUIRED: i64 = 1 << 1;
+
+/// The flags of text fields.
+const MULTILINE: i64 = 1 << 12;
+const PASSWORD: i64 = 1 << 13;
+
+/// The flags of button fields.
+const NO_TOGGLE_TO_OFF: i64 = 1 << 14;
+const RADIO: i64 = 1 << 15;
+const PUSHBUTTON: i64 = 1 << 16;
+
+/// The flags of choice fields.
+const COMBO: i64 = 1 << 17;
+const EDIT: i64 = 1 << 18;
+
+/// The default appearance of variable text in form fields: Automatically
+/// sized, black Helvetica.
+const DEFAULT_APPEARANCE: &str = "/Helv 0 Tf 0 g";
+
+/// A form field widget that was placed in the document.
+pub(crate) struct Widget {
+    pub field: FormField,
+    pub span: Span,
+}
+
+/// Places the widget of a form field.
+pub(crate) fn handle_field(
+    fc: &mut FrameContext,
+    gc: &mut GlobalContext,
+    field: FormField,
+    size: Size,
+) -> SourceResult<()> {
+    // Signature fields don't need their appearance to be generated by the
+    // reader, so they are fine in PDF/A.
+    let span = field.elem().span();
+    if let Some(archival) = gc.options.archival_validator()
+        && !matches!(field, FormField::Signature(_))
+    {
+        let validator = archival.as_str();
+        bail!(
+            span,
+            "{validator} error: form fields are not supported";
+            hint: "the appearance of form fields is generated by the PDF reader";
+        );
+    }
+
+    // Widgets in tilings would be repeated arbitrarily often.
+    if gc.tags.in_tiling {
+        bail!(span, "form fields are not supported in tilings");
+    }
+
+    let alt: Option<String> = field.tooltip(StyleChain::default()).map(Into::into);
+    if alt.is_none()
+        && let Some(accessibility) = gc.options.accessibility_validator()
+    {
+        let validator = accessibility.as_str();
+        bail!(
+            span,
+            "{validator} error: form field is missing a tooltip";
+            hint: "use the `tooltip` parameter to describe the field's purpose";
+        );
+    }
+
+    let rect = bounding_box(fc, size);
+    let uri = format!("{PLACEHOLDER}{}", gc.widgets.len());
+    let target = Target::Action(Action::Link(LinkAction::new(uri)));
+    gc.widgets.push(Widget { field, span });
+
+    if tags::disabled(gc) {
+        fc.push_link_annotation(
+            GroupId::INVALID,
+            LinkAnnotation {
+                kind: LinkAnnotationKind::Artifact,
+                alt,
+                span,
+                rects: vec![rect],
+                target,
+            },
+        );
+        return Ok(());
+    }
+
+    if gc.tags.tree.parent_artifact().is_some() {
+        if let Some(accessibility) = gc.options.accessibility_validator() {
+            let validator = accessibility.as_str();
+            bail!(span, "{validator} error: PDF artifacts may not contain form fields");
+        }
+
+        fc.push_link_annotation(
+            GroupId::INVALID,
+            LinkAnnotation {
+                kind: LinkAnnotationKind::Artifact,
+                alt,
+                span,
+                rects: vec![rect],
+                target,
+            },
+        );
+        return Ok(());
+    }
+
+    let group_id = (gc.tags.tree.parent_form())
+        .expect_internal("expected form field ancestor in logical tree")
+        .at(span)?;
+    let annot_id = gc.tags.annotations.reserve();
+    fc.push_link_annotation(
+        group_id,
+        LinkAnnotation {
+            kind: LinkAnnotationKind::Tagged(annot_id),
+            alt,
+            span,
+            rects: vec![rect],
+            target,
+        },
+    );
+    gc.tags.tree.groups.get_mut(group_id).push_annotation(annot_id);
+
+    Ok(())
+}
+
+/// Turns the placeholder annotations into widgets and writes the form's
+/// field hierarchy.
+pub(crate) fn write_fields(
+    file: &mut PdfFile,
+    widgets: &[Widget],
+    options: &PdfOptions,
+) -> SourceResult<()> {
+    let annots = file.placeholders(PLACEHOLDER, widgets.len());
+
+    // Group the widgets by field name, in order of first appearance.
+    let mut groups: Vec<Vec<Member>> = vec![];
+    let mut by_name = FxHashMap::default();
+    for (i, widget) in widgets.iter().enumerate() {
+        let Some(&(annot, page)) = annots.get(&i) else { continue };
+        let index = *by_name.entry(widget.field.name()).or_insert_with(|| {
+            groups.push(vec![]);
+            groups.len() - 1
+        });
+
+        let group = &mut groups[index];
+        if let Some(first) = group.first()
+            && std::mem::discriminant(&first.widget.field)
+                != std::mem::discriminant(&widget.field)
+        {
+            bail!(
+                widget.span,
+                "form field `{}` is already used for a different kind of field",
+                widget.field.name();
+                hint: "fields with the same name share their value";
+            );
+        }
+
+        group.push(Member { widget, annot, page });
+    }
+
+    // The signature field that receives the signature placeholder.
+    let signed = match &options.signature {
+        Some(signature) => Some(signed_field(&groups, signature)?),
+        None => None,
+    };
+
+    let mut fields = vec![];
+    let mut pages = vec![];
+    for (i, members) in groups.iter().enumerate() {
+        let mut field = field_dict(members);
+        if signed == Some(i)
+            && let Some(signature) = &options.signature
+        {
+            field.set("V", file.add(sign::signature_dict(signature)));
+        }
+        pages.extend(members.iter().filter_map(|member| member.page));
+
+        // Radio buttons are always the kids of their group's field, other
+        // fields only if they have multiple widgets.
+        let first = &members[0];
+        if members.len() > 1 || matches!(first.widget.field, FormField::Radio(_)) {
+            let kids = members.iter().map(|member| Object::Ref(member.annot)).collect();
+            let parent = file.add(field.with("Kids", Object::Array(kids)));
+            for (i, member) in members.iter().enumerate() {
+                let dict = widget_dict(file, members, i);
+                file.set(member.annot, dict.with("Parent", parent));
+            }
+            fields.push(Object::Ref(parent));
+        } else {
+            let mut dict = widget_dict(file, members, 0);
+            for (key, value) in field.iter() {
+                dict.set(key.clone(), value.clone());
+            }
+            file.set(first.annot, dict);
+            fields.push(Object::Ref(first.annot));
+        }
+    }
+
+    // Let the reader generate the appearance of variable text from the
+    // default resources.
+    let font = file.add(
+        Dict::new()
+            .with("Type", Object::name("Font"))
+            .with("Subtype", Object::name("Type1"))
+            .with("BaseFont", Object::name("Helvetica"))
+            .with("Encoding", Object::name("WinAnsiEncoding")),
+    );
+    let mut form = Dict::new()
+        .with("Fields", Object::Array(fields))
+        .with("DA", Object::text(DEFAULT_APPEARANCE))
+        .with("DR", Dict::new().with("Font", Dict::new().with("Helv", font)));
+
+    // Only fields with variable text or a caption need their appearance to be
+    // generated by the reader.
+    let any = |f: fn(&FormField) -> bool| {
+        groups.iter().any(|members| f(&members[0].widget.field))
+    };
+    if any(|field| {
+        matches!(field, FormField::Text(_) | FormField::Choice(_) | FormField::Button(_))
+    }) {
+        form.set("NeedAppearances", true);
+    }
+
+    // Signal that the document contains signatures. Once it is signed,
+    // changes may only be appended so as not to invalidate the signature.
+    if any(|field| matches!(field, FormField::Signature(_))) {
+        form.set("SigFlags", if signed.is_some() { 3 } else { 1 });
+    }
+
+    let catalog = file.catalog().and_then(|r| file.dict_mut(r));
+    let Some(catalog) = catalog else { return Ok(()) };
+    catalog.set("AcroForm", form);
+
+    // Fields are focused in the logical order if the document is tagged and
+    // in reading order otherwise.
+    let tabs = if options.tagged { "S" } else { "R" };
+    for page in pages {
+        if let Some(dict) = file.dict_mut(page) {
+            dict.set("Tabs", Object::name(tabs));
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds the index of the group of the signature field that receives the
+/// signature placeholder.
+fn signed_field(groups: &[Vec<Member>], signature: &PdfSignature) -> SourceResult<usize> {
+    let position = groups.iter().position(|members| {
+        let field = &members[0].widget.field;
+        matches!(field, FormField::Signature(_))
+            && signature.field.as_ref().is_none_or(|name| name == field.name())
+    });
+
+    match (position, &signature.field) {
+        (Some(i), _) => Ok(i),
+        (None, Some(name)) => bail!(
+            Span::detached(),
+            "document has no signature field named `{name}`";
+            hint: "add it with `pdf.signature-field(\"{name}\")`";
+        ),
+        (None, None) => bail!(
+            Span::detached(),
+            "document has no signature field";
+            hint: "add one with `pdf.signature-field`";
+        ),
+    }
+}
+
+/// A widget that belongs to a field.
+struct Member<'a> {
+    widget: &'a Widget,
+    /// The widget's annotation.
+    annot: Ref,
+    /// The page the widget is on.
+    page: Option<Ref>,
+}
+
+impl Member<'_> {
+    /// Whether this is a checked checkbox or radio button.
+    fn checked(&self) -> bool {
+        let styles = StyleChain::default();
+        match &self.widget.field {
+            FormField::Checkbox(elem) => elem.checked.get(styles),
+            FormField::Radio(elem) => elem.checked.get(styles),
+            _ => false,
+        }
+    }
+}
+
+/// Creates the dictionary of a field with the given widgets, without the
+/// widget-specific entries.
+fn field_dict(members: &[Member]) -> Dict {
+    let styles = StyleChain::default();
+    let field = &members[0].widget.field;
+
+    let mut dict = Dict::new().with("T", Object::text(field.name()));
+    if let Some(tooltip) = field.tooltip(styles) {
+        dict.set("TU", Object::text(tooltip));
+    }
+
+    let mut flags = 0;
+    let mut flag = |set: bool, flag: i64| {
+        if set {
+            flags |= flag;
+        }
+    };
+
+    match field {
+        FormField::Text(elem) => {
+            let value = Object::text(elem.value.get_ref(styles));
+            dict.set("FT", Object::name("Tx"));
+            dict.set("V", value.clone());
+            dict.set("DV", value);
+            dict.set("DA", Object::text(DEFAULT_APPEARANCE));
+            if let Some(max) = elem.max_length.get(styles) {
+                dict.set("MaxLen", max as i64);
+            }
+            flag(elem.multiline.get(styles), MULTILINE);
+            flag(elem.password.get(styles), PASSWORD);
+            flag(elem.required.get(styles), REQUIRED);
+            flag(elem.read_only.get(styles), READ_ONLY);
+        }
+        FormField::Checkbox(elem) => {
+            let checked = members.iter().any(Member::checked);
+            let value = Object::name(if checked { "Yes" } else { "Off" });
+            dict.set("FT", Object::name("Btn"));
+            dict.set("V", value.clone());
+            dict.set("DV", value);
+            flag(elem.required.get(styles), REQUIRED);
+            flag(elem.read_only.get(styles), READ_ONLY);
+        }
+        FormField::Radio(elem) => {
+            let value = members.iter().find(|member| member.checked()).map_or_else(
+                || Object::name("Off"),
+                |member| on_state(member.widget).into(),
+            );
+            dict.set("FT", Object::name("Btn"));
+            dict.set("V", value.clone());
+            dict.set("DV", value);
+            flag(true, RADIO | NO_TOGGLE_TO_OFF);
+            flag(elem.required.get(styles), REQUIRED);
+            flag(elem.read_only.get(styles), READ_ONLY);
+        }
+        FormField::Choice(elem) => {
+            let options =
+                elem.options.iter().map(|option| Object::text(option)).collect();
+            dict.set("FT", Object::name("Ch"));
+            dict.set("Opt", Object::Array(options));
+            dict.set("DA", Object::text(DEFAULT_APPEARANCE));
+            if let Some(value) = elem.value.get_ref(styles) {
+                dict.set("V", Object::text(value));
+                dict.set("DV", Object::text(value));
+            }
+            let list = elem.list.get(styles);
+            flag(!list, COMBO);
+            flag(!list && elem.editable.get(styles), EDIT);
+            flag(elem.required.get(styles), REQUIRED);
+            flag(elem.read_only.get(styles), READ_ONLY);
+        }
+        FormField::Button(elem) => {
+            dict.set("FT", Object::name("Btn"));
+            flag(true, PUSHBUTTON);
+            flag(elem.read_only.get(styles), READ_ONLY);
+        }
+        FormField::Signature(_) => {
+            dict.set("FT", Object::name("Sig"));
+        }
+    }
+
+    if flags != 0 {
+        dict.set("Ff", flags);
+    }
+
+    dict
+}
+
+/// Creates the widget annotation dictionary that replaces the placeholder of
+/// the `i`-th member.
+fn widget_dict(file: &mut PdfFile, members: &[Member], i: usize) -> Dict {
+    let member = &members[i];
+    let placeholder = file.dict(member.annot).cloned().unwrap_or_default();
+
+    // Unlike the placeholder link, widgets are printed.
+    let mut dict = Dict::new()
+        .with("Type", Object::name("Annot"))
+        .with("Subtype", Object::name("Widget"))
+        .with("F", 4);
+    for key in ["Rect", "StructParent"] {
+        if let Some(value) = placeholder.get(key) {
+            dict.set(key, value.clone());
+        }
+    }
+    if let Some(page) = member.page {
+        dict.set("P", page);
+    }
+
+    let rect: Vec<f64> = placeholder
+        .get("Rect")
+        .and_then(Object::as_array)
+        .map(|rect| rect.iter().filter_map(Object::as_f64).collect())
+        .unwrap_or_default();
+    let (width, height) = match rect.as_slice() {
+        [x0, y0, x1, y1] => ((x1 - x0).abs(), (y1 - y0).abs()),
+        _ => (0.0, 0.0),
+    };
+
+    match &member.widget.field {
+        FormField::Checkbox(_) => {
+            let on = appearance(file, width, height, &check_mark(width, height));
+            let off = appearance(file, width, height, "");
+            let checked = members.iter().any(Member::checked);
+            dict.set("AS", Object::name(if checked { "Yes" } else { "Off" }));
+            dict.set(
+                "AP",
+                Dict::new().with("N", Dict::new().with("Yes", on).with("Off", off)),
+            );
+        }
+        FormField::Radio(_) => {
+            let on = appearance(file, width, height, &dot(width, height));
+            let off = appearance(file, width, height, "");
+            let state = on_state(member.widget);
+
+            // Only the first checked button of a group is selected.
+            let selected = members.iter().position(Member::checked) == Some(i);
+            let current =
+                if selected { state.clone().into() } else { Object::name("Off") };
+            dict.set("AS", current);
+            dict.set(
+                "AP",
+                Dict::new().with("N", Dict::new().with(state, on).with("Off", off)),
+            );
+        }
+        FormField::Button(elem) => {
+            dict.set("H", Object::name("P"));
+            if let Some(url) = elem.url.get_ref(StyleChain::default()) {
+                let action = Dict::new()
+                    .with("Type", Object::name("Action"))
+                    .with("S", Object::name("URI"))
+                    .with("URI", Object::Str(url.as_bytes().to_vec()));
+                dict.set("A", action);
+            }
+        }
+        FormField::Signature(elem) => {
+            // The signature's appearance is provided when signing.
+            if elem.visible.get(StyleChain::default()) {
+                let blank = appearance(file, width, height, "");
+                dict.set("AP", Dict::new().with("N", blank));
+            } else {
+                let zero = Object::Array(vec![Object::Int(0); 4]);
+                dict.set("Rect", zero);
+                dict.set("AP", Dict::new().with("N", appearance(file, 0.0, 0.0, "")));
+            }
+        }
+        FormField::Text(_) | FormField::Choice(_) => {}
+    }
+
+    dict
+}
+
+/// The name of a checkbox's or radio button's on state. For radio buttons,
+/// this is the button's value.
+fn on_state(widget: &Widget) -> Name {
+    match &widget.field {
+        FormField::Radio(elem) => Name(elem.value.as_bytes().to_vec()),
+        _ => Name::new("Yes"),
+    }
+}
+
+/// Writes an appearance stream with the given content.
+fn appearance(file: &mut PdfFile, width: f64, height: f64, content: &str) -> Ref {
+    let bbox = vec![0.0.into(), 0.0.into(), width.into(), height.into()];
+    file.add(Stream {
+        dict: Dict::new()
+            .with("Type", Object::name("XObject"))
+            .with("Subtype", Object::name("Form"))
+            .with("BBox", Object::Array(bbox)),
+        data: content.as_bytes().to_vec(),
+    })
+}
+
+/// Draws a check mark that fills a box of the given size.
+fn check_mark(width: f64, height: f64) -> String {
+    let line = width.min(height) * 0.12;
+    format!(
+        "q 0 G {line:.2} w 1 J 1 j {:.2} {:.2} m {:.2} {:.2} l {:.2} {:.2} l S Q",
+        width * 0.22,
+        height * 0.52,
+        width * 0.42,
+        height * 0.28,
+        width * 0.78,
+        height * 0.74,
+    )
+}
+
+/// Draws a filled dot in the center of a box of the given size.
+fn dot(width: f64, height: f64) -> String {
+    // The distance of the Bézier control points for approximating a quarter
+    // circle.
+    const KAPPA: f64 = 0.552_284_75;
+
+    let (x, y) = (width / 2.0, height / 2.0);
+    let r = width.min(height) * 0.25;
+    let k = r * KAPPA;
+    format!(
+        "q 0 g {:.2} {y:.2} m \
+         {:.2} {:.2} {:.2} {:.2} {x:.2} {:.2} c \
+         {:.2} {:.2} {:.2} {:.2} {:.2} {y:.2} c \
+         {:.2} {:.2} {:.2} {:.2} {x:.2} {:.2} c \
+         {:.2} {:.2} {:.2} {:.2} {:.2} {y:.2} c f Q",
+        x + r,
+        x + r,
+        y + k,
+        x + k,
+        y + r,
+        y + r,
+        x - k,
+        y + r,
+        x - r,
+        y + k,
+        x - r,
+        x - r,
+        y - k,
+        x - k,
+        y - r,
+        y - r,
+        x + k,
+        y - r,
+        x + r,
+        y - k,
+        x + r,
+    )
+}
@@ -17,6 +17,7 @@ use typst_syntax::Span;
 use typst_utils::defer;
 
 use crate::convert::{FrameContext, GlobalContext};
+use crate::pdfx;
 use crate::tags;
 use crate::util::{SizeExt, TransformExt};
 
@@ -36,6 +37,8 @@ pub(crate) fn handle_image(
         s.reset_location();
     });
 
+    pdfx::validate_image(gc, image, span)?;
+
     let interpolate = image.scaling() == Smart::Custom(ImageScaling::Smooth);
 
     gc.image_spans.insert(span);
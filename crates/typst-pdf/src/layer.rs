@@ -0,0 +1,445 @@
+//! Optional content, which PDF readers present as layers.
+//!
+//! krilla doesn't support optional content, so layers are marked during
+//! conversion and turned into optional content groups in post-processing. The
+//! contents of a layer are clipped with a sentinel rectangle that is far too
+//! large to clip anything and whose right edge encodes the index of the layer.
+//! After serialization, we find these clipping paths in the content streams
+//! and wrap everything up to the `Q` operator that ends the clip in a marked
+//! content sequence referencing the layer's optional content group.
+//!
+//! PDF 1.4, and thus PDF/A-1, doesn't support optional content. In these
+//! files, visible layers are simply flattened into the page. Hidden layers
+//! are an error because their contents would become visible.
+
+use std::io::{Read, Write};
+
+use ecow::{EcoString, eco_format};
+use flate2::Compression;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use indexmap::IndexMap;
+use krilla::configure::PdfVersion;
+use krilla::geom::{Path, PathBuilder};
+use krilla::paint::FillRule;
+use krilla::surface::Surface;
+use rustc_hash::{FxBuildHasher, FxHashMap};
+use typst_library::diag::{SourceResult, bail};
+use typst_library::pdf::Layer;
+
+use crate::convert::GlobalContext;
+use crate::patch::{Dict, Indirect, Object, PdfFile, Ref, Stream};
+
+/// The distance of the sentinel rectangle's edges from the origin. The right
+/// edge lies further out by one plus the index of the layer.
+const SENTINEL: f32 = 1_000_000.0;
+
+/// The layers of a document by name, with their initial visibility.
+pub(crate) type Layers = IndexMap<EcoString, bool, FxBuildHasher>;
+
+/// Marks the start of a layer's contents. Returns whether a clip path was
+/// pushed, which must be popped once the layer's contents are written.
+pub(crate) fn push_layer(
+    gc: &mut GlobalContext,
+    surface: &mut Surface,
+    layer: &Layer,
+) -> SourceResult<bool> {
+    if matches!(gc.options.standards.config.version(), PdfVersion::Pdf14) {
+        if layer.visible {
+            return Ok(false);
+        }
+
+        if let Some(archival) = gc.options.archival_validator() {
+            let validator = archival.as_str();
+            bail!(
+                layer.span,
+                "{validator} error: hidden layers are not supported";
+                hint: "make the layer visible or use PDF/A-2 or later";
+            );
+        }
+
+        bail!(
+            layer.span,
+            "hidden layers are not supported in PDF 1.4 files";
+            hint: "set the version to PDF 1.5 or later";
+        );
+    }
+
+    let entry = gc.layers.entry(layer.name.clone());
+    let index = entry.index();
+    entry.or_insert(layer.visible);
+
+    let Some(path) = sentinel(index) else { return Ok(false) };
+    surface.push_clip_path(&path, &FillRule::NonZero);
+    Ok(true)
+}
+
+/// Creates the sentinel rectangle for the layer with the given index.
+fn sentinel(index: usize) -> Option<Path> {
+    let right = SENTINEL + 1.0 + index as f32;
+    let mut builder = PathBuilder::new();
+    builder.move_to(-SENTINEL, -SENTINEL);
+    builder.line_to(right, -SENTINEL);
+    builder.line_to(right, SENTINEL);
+    builder.line_to(-SENTINEL, SENTINEL);
+    builder.close();
+    builder.finish()
+}
+
+/// Adds the optional content groups to the file and assigns the contents of
+/// the layers to them.
+pub(crate) fn write_layers(file: &mut PdfFile, layers: &Layers) {
+    let groups: Vec<Ref> = layers
+        .keys()
+        .map(|name| {
+            file.add(
+                Dict::new()
+                    .with("Type", Object::name("OCG"))
+                    .with("Name", Object::text(name)),
+            )
+        })
+        .collect();
+
+    let all: Vec<Object> = groups.iter().copied().map(Object::Ref).collect();
+    let off: Vec<Object> = groups
+        .iter()
+        .zip(layers.values())
+        .filter(|&(_, &visible)| !visible)
+        .map(|(&group, _)| Object::Ref(group))
+        .collect();
+
+    // PDF/A requires the configuration to be named and to list all groups.
+    let config = Dict::new()
+        .with("Name", Object::text("Layers"))
+        .with("Order", all.clone())
+        .with("OFF", off);
+    if let Some(catalog) = file.catalog().and_then(|r| file.dict_mut(r)) {
+        catalog.set("OCProperties", Dict::new().with("OCGs", all).with("D", config));
+    }
+
+    // Page contents use the resources of their page, while form XObjects and
+    // tilings have their own.
+    let mut owners = FxHashMap::default();
+    for page in file.pages() {
+        let contents = match file.dict(page).and_then(|dict| dict.get("Contents")) {
+            Some(Object::Ref(r)) => vec![*r],
+            Some(Object::Array(array)) => {
+                array.iter().filter_map(Object::as_ref).collect()
+            }
+            _ => continue,
+        };
+        for r in contents {
+            owners.insert(r, page);
+        }
+    }
+
+    let streams: Vec<Ref> = file
+        .iter()
+        .filter(|(r, object)| match object {
+            Indirect::Stream(stream) => {
+                owners.contains_key(r)
+                    || stream.dict.get("PatternType").is_some()
+                    || stream
+                        .dict
+                        .get("Subtype")
+                        .and_then(Object::as_name)
+                        .is_some_and(|name| *name == *"Form")
+            }
+            Indirect::Object(_) => false,
+        })
+        .map(|(r, _)| r)
+        .collect();
+
+    for r in streams {
+        let Some(Indirect::Stream(stream)) = file.get_mut(r) else { continue };
+        let Some(content) = decode(stream) else { continue };
+        let Some((marked, used)) = mark(&content) else { continue };
+        let Some(data) = encode(stream, marked) else { continue };
+        stream.data = data;
+
+        let mut properties = Dict::new();
+        for index in used {
+            if let Some(&group) = groups.get(index) {
+                properties.set(eco_format!("oc{index}").as_str(), group);
+            }
+        }
+
+        let owner = owners.get(&r).copied().unwrap_or(r);
+        add_properties(file, owner, properties);
+    }
+}
+
+/// Decodes the data of a content stream.
+fn decode(stream: &Stream) -> Option<Vec<u8>> {
+    match stream.dict.get("Filter") {
+        None => Some(stream.data.clone()),
+        Some(Object::Name(name)) if *name == *"FlateDecode" => {
+            let mut buf = vec![];
+            ZlibDecoder::new(stream.data.as_slice()).read_to_end(&mut buf).ok()?;
+            Some(buf)
+        }
+        _ => None,
+    }
+}
+
+/// Encodes new data for a content stream with the stream's filter.
+fn encode(stream: &Stream, data: Vec<u8>) -> Option<Vec<u8>> {
+    if stream.dict.get("Filter").is_none() {
+        return Some(data);
+    }
+
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+    encoder.write_all(&data).ok()?;
+    encoder.finish().ok()
+}
+
+/// Adds entries to the `/Properties` of the given object's resources.
+fn add_properties(file: &mut PdfFile, owner: Ref, properties: Dict) {
+    let indirect = file
+        .dict(owner)
+        .and_then(|dict| dict.get("Resources"))
+        .and_then(Object::as_ref);
+    let resources = match indirect {
+        Some(r) => file.dict_mut(r),
+        None => file.dict_mut(owner).and_then(|dict| {
+            if dict.get("Resources").is_none() {
+                dict.set("Resources", Dict::new());
+            }
+            dict.get_mut("Resources").and_then(Object::as_dict_mut)
+        }),
+    };
+
+    let Some(resources) = resources else { return };
+    let mut merged = resources
+        .get("Properties")
+        .and_then(Object::as_dict)
+        .cloned()
+        .unwrap_or_default();
+    for (key, value) in properties.iter() {
+        merged.set(key.clone(), value.clone());
+    }
+    resources.set("Properties", merged);
+}
+
+/// Wraps the contents of each layer in a content stream in a marked content
+/// sequence. Returns the new content stream and the indices of the layers it
+/// contains, or `None` if it contains no layers.
+fn mark(content: &[u8]) -> Option<(Vec<u8>, Vec<usize>)> {
+    let mut out = Vec::with_capacity(content.len());
+    let mut used = vec![];
+    let mut copied = 0;
+
+    // The graphics state nesting depth and the depths at which the currently
+    // open layers were started.
+    let mut depth = 0;
+    let mut open: Vec<usize> = vec![];
+
+    // The layer whose sentinel rectangle is currently being constructed.
+    let mut pending = None;
+
+    let mut s = Scanner { data: content, pos: 0 };
+    while let Some((start, token)) = s.next() {
+        let op = match token {
+            Token::Number(value) => {
+                let index = value - f64::from(SENTINEL) - 1.0;
+                if index >= 0.0 && index < f64::from(SENTINEL) && index.fract() == 0.0 {
+                    pending = Some(index as usize);
+                }
+                continue;
+            }
+            Token::Operator(op) => op,
+            Token::Other => continue,
+        };
+
+        match op {
+            b"q" => depth += 1,
+            b"Q" => {
+                if open.last() == Some(&depth) {
+                    open.pop();
+                    out.extend(&content[copied..start]);
+                    out.extend(b"EMC ");
+                    copied = start;
+                }
+                depth = depth.saturating_sub(1);
+            }
+            b"m" | b"l" | b"h" | b"W" | b"W*" => {}
+            b"n" if let Some(index) = pending.take() => {
+                out.extend(&content[copied..s.pos]);
+                out.extend(format!(" /OC /oc{index} BDC").as_bytes());
+                copied = s.pos;
+                open.push(depth);
+                if !used.contains(&index) {
+                    used.push(index);
+                }
+            }
+            _ => pending = None,
+        }
+    }
+
+    if used.is_empty() {
+        return None;
+    }
+
+    out.extend(&content[copied..]);
+    for _ in open {
+        out.extend(b"\nEMC");
+    }
+
+    Some((out, used))
+}
+
+/// A token in a content stream.
+enum Token<'a> {
+    Number(f64),
+    Operator(&'a [u8]),
+    /// Any other operand or a delimiter.
+    Other,
+}
+
+/// Splits a content stream into tokens.
+struct Scanner<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    /// Returns the next token and the offset it starts at.
+    fn next(&mut self) -> Option<(usize, Token<'a>)> {
+        loop {
+            let &b = self.data.get(self.pos)?;
+            if is_whitespace(b) {
+                self.pos += 1;
+            } else if b == b'%' {
+                while self.data.get(self.pos).is_some_and(|&b| b != b'\n' && b != b'\r') {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+
+        let start = self.pos;
+        let first = self.data[start];
+        self.pos += 1;
+
+        let token = match first {
+            b'(' => {
+                self.string();
+                Token::Other
+            }
+            b'<' if self.data.get(self.pos) == Some(&b'<') => {
+                self.pos += 1;
+                Token::Other
+            }
+            b'<' => {
+                while self.data.get(self.pos).is_some_and(|&b| b != b'>') {
+                    self.pos += 1;
+                }
+                self.pos += 1;
+                Token::Other
+            }
+            b'>' => {
+                if self.data.get(self.pos) == Some(&b'>') {
+                    self.pos += 1;
+                }
+                Token::Other
+            }
+            b'[' | b']' | b'{' | b'}' | b')' => Token::Other,
+            b'/' => {
+                self.regular();
+                Token::Other
+            }
+            _ => {
+                self.regular();
+                let word = &self.data[start..self.pos];
+                if matches!(first, b'0'..=b'9' | b'+' | b'-' | b'.') {
+                    std::str::from_utf8(word)
+                        .ok()
+                        .and_then(|word| word.parse().ok())
+                        .map_or(Token::Other, Token::Number)
+                } else {
+                    if word == b"ID" {
+                        self.inline_image();
+                    }
+                    Token::Operator(word)
+                }
+            }
+        };
+
+        Some((start, token))
+    }
+
+    /// Skips the rest of a regular token.
+    fn regular(&mut self) {
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|&b| !is_whitespace(b) && !is_delimiter(b))
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips the rest of a literal string.
+    fn string(&mut self) {
+        let mut depth = 1;
+        while let Some(&b) = self.data.get(self.pos) {
+            self.pos += 1;
+            match b {
+                b'\\' => self.pos += 1,
+                b'(' => depth += 1,
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Skips the data of an inline image, up to its `EI` operator.
+    fn inline_image(&mut self) {
+        let data = self.data;
+        let end = (self.pos..data.len().saturating_sub(1))
+            .find(|&i| {
+                &data[i..i + 2] == b"EI"
+                    && i > 0
+                    && is_whitespace(data[i - 1])
+                    && data.get(i + 2).is_none_or(|&b| is_whitespace(b))
+            })
+            .unwrap_or(data.len());
+        self.pos = end;
+    }
+}
+
+/// Whether a byte is PDF whitespace.
+fn is_whitespace(b: u8) -> bool {
+    matches!(b, b'\0' | b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+/// Whether a byte is a PDF delimiter.
+fn is_delimiter(b: u8) -> bool {
+    matches!(b, b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mark() {
+        let content = b"q -1000000 -1000000 m 1000002 -1000000 l 1000002 1000000 l \
+                        -1000000 1000000 l h W n\nq (a\\)Q) Tj Q\n0 0 m 1 1 l S\nQ\nq Q";
+        let (marked, used) = mark(content).unwrap();
+        assert_eq!(used, vec![1]);
+        assert_eq!(
+            marked,
+            b"q -1000000 -1000000 m 1000002 -1000000 l 1000002 1000000 l \
+              -1000000 1000000 l h W n /OC /oc1 BDC\nq (a\\)Q) Tj Q\n0 0 m 1 1 l S\n\
+              EMC Q\nq Q"
+        );
+        assert!(mark(b"q 0 0 1000002 5 re W n Q").is_none());
+    }
+}
@@ -1,14 +1,22 @@
 //! Exporting Typst documents to PDF.
 
+mod annotation;
 mod attach;
 mod convert;
+mod encrypt;
+mod form;
 mod image;
+mod layer;
 mod link;
 mod metadata;
 mod outline;
 mod page;
 mod paint;
+mod patch;
+mod pdfx;
+mod ruby;
 mod shape;
+mod sign;
 mod tags;
 mod text;
 mod util;
@@ -20,15 +28,17 @@ use std::hash::{Hash, Hasher};
 
 use comemo::Tracked;
 use ecow::{EcoString, eco_format};
-use krilla::configure::Accessibility;
+use krilla::configure::{Accessibility, Archival};
 use serde::{Deserialize, Serialize};
 use typst_layout::PagedDocument;
 use typst_library::diag::{HintedStrResult, HintedString, SourceResult, StrResult, bail};
-use typst_library::foundations::Smart;
+use typst_library::foundations::{Bytes, Smart};
 use typst_library::introspection::Location;
 use typst_library::layout::PageRanges;
 use typst_library::model::LateLinkResolver;
 
+use crate::pdfx::PdfX;
+
 /// Export a document into a PDF file.
 ///
 /// Returns the raw bytes making up the PDF file.
@@ -53,6 +63,30 @@ pub fn pdf_in_bundle(
     convert::convert(document, options, anchors, Some(link_resolver))
 }
 
+/// Signs a PDF file that was exported with a [signature
+/// placeholder](PdfOptions::signature).
+///
+/// The `signer` receives the bytes covered by the signature, that is, the
+/// whole file except for the placeholder itself. It shall return a
+/// DER-encoded, detached CMS signature of these bytes, as required by PAdES.
+/// The signature is written into the placeholder, so the returned file has
+/// the same length as the input.
+pub fn sign(
+    pdf: &[u8],
+    signer: impl FnOnce(&[u8]) -> StrResult<Vec<u8>>,
+) -> StrResult<Vec<u8>> {
+    sign::sign(pdf, signer)
+}
+
+/// Checks that a PDF file survives being parsed and written again by the
+/// post-processing of the PDF export unchanged.
+///
+/// Used by the test suite and fuzzing. Not part of the public API.
+#[doc(hidden)]
+pub fn check_round_trip(pdf: &[u8]) -> StrResult<()> {
+    patch::check_round_trip(pdf)
+}
+
 /// Settings for PDF export.
 #[derive(Debug, Hash)]
 pub struct PdfOptions {
@@ -86,6 +120,16 @@ pub struct PdfOptions {
     pub tagged: bool,
     /// Whether to format the PDF in a human-readable way.
     pub pretty: bool,
+    /// If not `None`, the PDF is encrypted with AES-256 and the given
+    /// passwords and permissions.
+    pub encryption: Option<PdfEncryption>,
+    /// If not `None`, space for a digital signature is reserved in one of the
+    /// document's signature fields. The exported file can then be signed
+    /// with [`sign`].
+    pub signature: Option<PdfSignature>,
+    /// The printing condition the document is prepared for. Required for the
+    /// PDF/X standards.
+    pub output_intent: Option<PdfOutputIntent>,
 }
 
 impl PdfOptions {
@@ -94,6 +138,11 @@ impl PdfOptions {
     pub(crate) fn accessibility_validator(&self) -> Option<Accessibility> {
         self.standards.config.validators().accessibility()
     }
+
+    /// Returns the archival validator. Returns `Some` for the PDF/A standards.
+    pub(crate) fn archival_validator(&self) -> Option<Archival> {
+        self.standards.config.validators().archival()
+    }
 }
 
 impl Default for PdfOptions {
@@ -106,6 +155,9 @@ impl Default for PdfOptions {
             standards: PdfStandards::default(),
             tagged: true,
             pretty: false,
+            encryption: None,
+            signature: None,
+            output_intent: None,
         }
     }
 }
@@ -114,6 +166,8 @@ impl Default for PdfOptions {
 #[derive(Clone)]
 pub struct PdfStandards {
     pub(crate) config: krilla::configure::Configuration,
+    /// The PDF/X standard, which krilla doesn't know about.
+    pub(crate) pdfx: Option<PdfX>,
 }
 
 impl PdfStandards {
@@ -148,6 +202,15 @@ impl PdfStandards {
             Ok(())
         };
 
+        let mut pdfx = None;
+        let mut set_pdfx = |x: PdfX| -> StrResult<()> {
+            if pdfx.is_some() {
+                bail!("choose at most one PDF/X standard");
+            }
+            pdfx = Some(x);
+            Ok(())
+        };
+
         let mut accessibility_validator = None;
         let mut set_accessibility_validator = |ua: Accessibility| -> StrResult<()> {
             if accessibility_validator.is_some() {
@@ -176,6 +239,32 @@ impl PdfStandards {
                 PdfStandard::A_4f => set_archival_validator(Archival::A4F)?,
                 PdfStandard::A_4e => set_archival_validator(Archival::A4E)?,
                 PdfStandard::Ua_1 => set_accessibility_validator(Accessibility::UA1)?,
+                PdfStandard::X_1a => set_pdfx(PdfX::X1a)?,
+                PdfStandard::X_3 => set_pdfx(PdfX::X3)?,
+                PdfStandard::X_4 => set_pdfx(PdfX::X4)?,
+            }
+        }
+
+        // PDF/X requires a specific version and writes its own output intent,
+        // which would conflict with the one of PDF/A.
+        if let Some(x) = pdfx {
+            if let Some(archival) = archival_validator {
+                bail!(
+                    "{} is not compatible with {}",
+                    archival.as_str(),
+                    x.as_str();
+                    hint: "choose either a PDF/A or a PDF/X standard";
+                );
+            }
+
+            match version {
+                Some(v) if v != x.version() => bail!(
+                    "{} is not compatible with {}",
+                    v.as_str(),
+                    x.as_str();
+                    hint: "{} requires version {}", x.as_str(), x.version().as_str();
+                ),
+                _ => version = Some(x.version()),
             }
         }
 
@@ -217,7 +306,7 @@ impl PdfStandards {
                 .with_hints(validators.into_iter().map(version_hint))
         })?;
 
-        Ok(Self { config })
+        Ok(Self { config, pdfx })
     }
 }
 
@@ -255,6 +344,7 @@ impl Default for PdfStandards {
                 .with_version(PdfVersion::Pdf17)
                 .finish()
                 .unwrap(),
+            pdfx: None,
         }
     }
 }
@@ -267,6 +357,7 @@ impl Hash for PdfStandards {
         for validator in self.config.validators() {
             validator.hash(state);
         }
+        self.pdfx.hash(state);
     }
 }
 
@@ -328,4 +419,204 @@ pub enum PdfStandard {
     /// PDF/UA-1.
     #[serde(rename = "ua-1")]
     Ua_1,
+    /// PDF/X-1a:2003.
+    #[serde(rename = "x-1a")]
+    X_1a,
+    /// PDF/X-3:2003.
+    #[serde(rename = "x-3")]
+    X_3,
+    /// PDF/X-4.
+    #[serde(rename = "x-4")]
+    X_4,
+}
+
+/// Settings for encrypting a PDF file.
+///
+/// Encryption is not compatible with PDF/A and requires at least PDF 1.7.
+/// Passwords may only contain printable ASCII characters.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PdfEncryption {
+    /// The password that is required to open the document. If empty, anyone
+    /// can open the document, but the permissions still apply.
+    pub user_password: EcoString,
+    /// The password that grants full access to the document, regardless of
+    /// the permissions.
+    ///
+    /// If `None`, a random password is used, so that nobody gets full access.
+    /// It must not be empty, since PDF readers may then grant full access to
+    /// anyone, making the permissions ineffective.
+    pub owner_password: Option<EcoString>,
+    /// What users that open the document with the user password may do.
+    pub permissions: PdfPermissions,
+}
+
+impl PdfEncryption {
+    /// Checks whether the encryption is compatible with the given standards.
+    pub fn validate(&self, standards: &PdfStandards) -> HintedStrResult<()> {
+        use krilla::configure::PdfVersion;
+
+        if let Some(archival) = standards.config.validators().archival() {
+            bail!(
+                "{} does not allow encryption",
+                archival.as_str();
+                hint: "remove the PDF/A standard or disable encryption";
+            );
+        }
+
+        if let Some(x) = standards.pdfx {
+            bail!(
+                "{} does not allow encryption",
+                x.as_str();
+                hint: "remove the PDF/X standard or disable encryption";
+            );
+        }
+
+        if self
+            .owner_password
+            .as_ref()
+            .is_some_and(|password| password.is_empty())
+        {
+            bail!(
+                "the owner password must not be empty";
+                hint: "omit the owner password to use a random one";
+            );
+        }
+
+        // PDF readers normalize passwords with SASLprep before hashing them,
+        // which only leaves printable ASCII unchanged.
+        if !std::iter::once(&self.user_password)
+            .chain(&self.owner_password)
+            .all(|password| password.chars().all(|c| matches!(c, ' '..='~')))
+        {
+            bail!(
+                "passwords may only contain printable ASCII characters";
+                hint: "use letters without diacritics, digits, and punctuation";
+            );
+        }
+
+        let version = standards.config.version();
+        if matches!(version, PdfVersion::Pdf14 | PdfVersion::Pdf15 | PdfVersion::Pdf16) {
+            bail!(
+                "AES-256 encryption is not supported in {} files",
+                version.as_str();
+                hint: "set the version to PDF 1.7 or PDF 2.0";
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The operations that users of an encrypted PDF are permitted to perform.
+///
+/// All operations are permitted by default. PDF readers are expected, but
+/// cannot be forced, to respect these restrictions. Extracting text for
+/// accessibility purposes is always permitted.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PdfPermissions {
+    /// Whether the document may be printed.
+    pub print: bool,
+    /// Whether the document may be printed at full quality. When `false`,
+    /// but `print` is `true`, only a low-resolution version may be printed.
+    pub print_high_quality: bool,
+    /// Whether the document's contents may be modified.
+    pub modify: bool,
+    /// Whether text and graphics may be copied.
+    pub copy: bool,
+    /// Whether annotations may be added or modified and form fields may be
+    /// filled in.
+    pub annotate: bool,
+    /// Whether form fields may be filled in, even if `annotate` is `false`.
+    pub fill_forms: bool,
+    /// Whether pages may be inserted, rotated, or deleted.
+    pub assemble: bool,
+}
+
+impl Default for PdfPermissions {
+    fn default() -> Self {
+        Self {
+            print: true,
+            print_high_quality: true,
+            modify: true,
+            copy: true,
+            annotate: true,
+            fill_forms: true,
+            assemble: true,
+        }
+    }
+}
+
+/// Settings for reserving space for a digital signature.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PdfSignature {
+    /// The name of the signature field to sign. If `None`, the first signature
+    /// field in the document is used.
+    pub field: Option<EcoString>,
+    /// The number of bytes reserved for the CMS signature. It must be large
+    /// enough to hold the signature including the certificate chain and
+    /// possibly a timestamp.
+    pub size: usize,
+    /// The reason for signing, like an approval of the document.
+    pub reason: Option<EcoString>,
+    /// The physical location where the document is signed.
+    pub location: Option<EcoString>,
+    /// Information that lets recipients contact the signer.
+    pub contact_info: Option<EcoString>,
+}
+
+impl Default for PdfSignature {
+    fn default() -> Self {
+        Self {
+            field: None,
+            size: 16384,
+            reason: None,
+            location: None,
+            contact_info: None,
+        }
+    }
+}
+
+/// The printing condition that a PDF/X file is prepared for.
+///
+/// Typst doesn't ship any output profiles since the right one depends on the
+/// printing process. Print providers usually specify which one to use.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PdfOutputIntent {
+    /// The ICC profile of the output device, like the `FOGRA39` or `GRACoL`
+    /// profile of a printing press.
+    pub profile: Bytes,
+    /// The name of the printing condition in the ICC's registry of
+    /// characterized printing conditions, like `FOGRA39`. If `None`, the
+    /// condition is written as `Custom` and only described by the profile.
+    pub condition: Option<EcoString>,
+}
+
+impl PdfOutputIntent {
+    /// Checks whether the output intent is compatible with the given
+    /// standards.
+    pub fn validate(&self, standards: &PdfStandards) -> HintedStrResult<()> {
+        let Some(x) = standards.pdfx else {
+            bail!(
+                "an output intent is only written for the PDF/X standards";
+                hint: "add a PDF/X standard or remove the output profile";
+            );
+        };
+
+        let Some(components) = pdfx::profile_components(&self.profile) else {
+            bail!(
+                "output profile is not a valid ICC output device profile";
+                hint: "use the profile of a printer or printing process";
+            );
+        };
+
+        if x == PdfX::X1a && components != 4 {
+            bail!(
+                "{} requires a CMYK output profile",
+                x.as_str();
+                hint: "use the profile of a CMYK printing process, like FOGRA39";
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -1,10 +1,19 @@
+use ecow::{EcoString, eco_format};
+use krilla::configure::PdfVersion;
 use krilla::metadata::{Metadata, TextDirection};
 use typst_library::foundations::{Datetime, Smart};
 use typst_library::layout::Dir;
-use typst_library::model::Document;
+use typst_library::model::{Document, DocumentMetadata};
 use typst_library::text::Locale;
 
 use crate::convert::GlobalContext;
+use crate::patch::{Dict, Indirect, Object, PdfFile, Ref};
+
+/// The namespaces of the predefined XMP schemas that custom metadata can use.
+const NAMESPACES: &[(&str, &str)] = &[
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("xmpRights", "http://ns.adobe.com/xap/1.0/rights/"),
+];
 
 pub(crate) fn build_metadata(gc: &GlobalContext, doc_lang: Option<Locale>) -> Metadata {
     // Always write a language, PDF/UA-1 implicitly requires a document language
@@ -98,6 +107,213 @@ pub fn creation_date(gc: &GlobalContext) -> Option<krilla::metadata::DateTime> {
     Some(kd)
 }
 
+/// The namespace of Adobe's PDF extension schema, which holds the custom
+/// entries of the document information dictionary in XMP metadata.
+const PDFX: &str = "http://ns.adobe.com/pdfx/1.3/";
+
+/// Writes the document's custom metadata into the document information
+/// dictionary and the XMP metadata.
+///
+/// PDF 2.0 deprecates the document information dictionary and PDF/A-4 only
+/// permits a few entries in it. There, custom entries are written to the XMP
+/// metadata only.
+pub(crate) fn write_custom(
+    file: &mut PdfFile,
+    metadata: &DocumentMetadata,
+    version: PdfVersion,
+) {
+    let mut namespaces = metadata.namespaces.clone();
+    let mut properties = metadata.properties.clone();
+    if version == PdfVersion::Pdf20 {
+        if !metadata.info.is_empty() {
+            namespaces.push(("pdfx".into(), PDFX.into()));
+        }
+        for (key, value) in &metadata.info {
+            properties.push(("pdfx".into(), xmp_name(key), value.clone()));
+        }
+    } else if !metadata.info.is_empty() {
+        let info = info_dict(file);
+        if let Some(dict) = file.dict_mut(info) {
+            for (key, value) in &metadata.info {
+                dict.set(key.as_str(), Object::text(value));
+            }
+        }
+    }
+
+    if properties.is_empty() {
+        return;
+    }
+
+    let uri = |prefix: &str| {
+        NAMESPACES
+            .iter()
+            .copied()
+            .chain(namespaces.iter().map(|(p, uri)| (p.as_str(), uri.as_str())))
+            .find(|&(p, _)| p == prefix)
+            .map(|(_, uri)| uri)
+    };
+
+    let mut prefixes: Vec<&str> = vec![];
+    for (prefix, _, _) in &properties {
+        if !prefixes.contains(&prefix.as_str()) {
+            prefixes.push(prefix);
+        }
+    }
+
+    let mut description = String::from("<rdf:Description rdf:about=\"\"");
+    for &prefix in &prefixes {
+        let uri = escape(uri(prefix).unwrap_or_default());
+        description.push_str(&format!(" xmlns:{prefix}=\"{uri}\""));
+    }
+    description.push('>');
+    for (prefix, name, value) in &properties {
+        let value = escape(value);
+        let value = match (prefix.as_str(), name.as_str()) {
+            ("dc", "rights") | ("xmpRights", "UsageTerms") => format!(
+                "<rdf:Alt><rdf:li xml:lang=\"x-default\">{value}</rdf:li></rdf:Alt>"
+            ),
+            ("dc", "contributor" | "publisher" | "relation" | "type")
+            | ("xmpRights", "Owner") => {
+                format!("<rdf:Bag><rdf:li>{value}</rdf:li></rdf:Bag>")
+            }
+            _ => value,
+        };
+        description.push_str(&format!("<{prefix}:{name}>{value}</{prefix}:{name}>"));
+    }
+    description.push_str("</rdf:Description>");
+    insert_xmp(file, &description);
+
+    // PDF/A requires a description of all custom schemas.
+    let mut schemas = String::new();
+    for (prefix, uri) in &namespaces {
+        if !prefixes.contains(&prefix.as_str()) {
+            continue;
+        }
+
+        schemas.push_str(&format!(
+            "<rdf:li rdf:parseType=\"Resource\">\
+             <pdfaSchema:schema>Custom metadata</pdfaSchema:schema>\
+             <pdfaSchema:namespaceURI>{}</pdfaSchema:namespaceURI>\
+             <pdfaSchema:prefix>{prefix}</pdfaSchema:prefix>\
+             <pdfaSchema:property><rdf:Seq>",
+            escape(uri),
+        ));
+        for (_, name, _) in properties.iter().filter(|(p, _, _)| p == prefix) {
+            schemas.push_str(&format!(
+                "<rdf:li rdf:parseType=\"Resource\">\
+                 <pdfaProperty:name>{name}</pdfaProperty:name>\
+                 <pdfaProperty:valueType>Text</pdfaProperty:valueType>\
+                 <pdfaProperty:category>external</pdfaProperty:category>\
+                 <pdfaProperty:description>{name}</pdfaProperty:description>\
+                 </rdf:li>"
+            ));
+        }
+        schemas.push_str("</rdf:Seq></pdfaSchema:property></rdf:li>");
+    }
+
+    if !schemas.is_empty() {
+        insert_extension_schemas(file, &schemas);
+    }
+}
+
+/// Returns the document information dictionary, adding it if the file doesn't
+/// have one yet.
+pub(crate) fn info_dict(file: &mut PdfFile) -> Ref {
+    match file.trailer().get("Info").and_then(Object::as_ref) {
+        Some(info) => info,
+        None => {
+            let info = file.add(Dict::new());
+            file.trailer_mut().set("Info", info);
+            info
+        }
+    }
+}
+
+/// Adds a description to the file's XMP metadata.
+pub(crate) fn insert_xmp(file: &mut PdfFile, description: &str) {
+    let Some(data) = xmp_data(file) else { return };
+    let marker = b"</rdf:RDF>";
+    if let Some(end) = data.windows(marker.len()).rposition(|w| w == marker) {
+        data.splice(end..end, description.bytes());
+    }
+}
+
+/// Adds PDF/A extension schemas to the file's XMP metadata, either to the
+/// existing list of schemas or in a new description.
+fn insert_extension_schemas(file: &mut PdfFile, schemas: &str) {
+    let Some(data) = xmp_data(file) else { return };
+    let marker = b"<pdfaExtension:schemas>";
+    let bag = b"<rdf:Bag>";
+    if let Some(start) = data.windows(marker.len()).position(|w| w == marker)
+        && let Some(offset) = data[start..].windows(bag.len()).position(|w| w == bag)
+    {
+        let at = start + offset + bag.len();
+        data.splice(at..at, schemas.bytes());
+        return;
+    }
+
+    insert_xmp(
+        file,
+        &format!(
+            "<rdf:Description rdf:about=\"\" \
+             xmlns:pdfaExtension=\"http://www.aiim.org/pdfa/ns/extension/\" \
+             xmlns:pdfaSchema=\"http://www.aiim.org/pdfa/ns/schema#\" \
+             xmlns:pdfaProperty=\"http://www.aiim.org/pdfa/ns/property#\">\
+             <pdfaExtension:schemas><rdf:Bag>{schemas}</rdf:Bag></pdfaExtension:schemas>\
+             </rdf:Description>"
+        ),
+    );
+}
+
+/// The data of the file's XMP metadata stream, if it is unfiltered.
+fn xmp_data(file: &mut PdfFile) -> Option<&mut Vec<u8>> {
+    let metadata = file
+        .catalog()
+        .and_then(|r| file.dict(r))
+        .and_then(|dict| dict.get("Metadata"))
+        .and_then(Object::as_ref)?;
+    let Some(Indirect::Stream(stream)) = file.get_mut(metadata) else { return None };
+    stream.dict.get("Filter").is_none().then_some(&mut stream.data)
+}
+
+/// Escapes text for use in XML, dropping characters that XML doesn't allow,
+/// like most control characters.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            '\0'..='\x1f' | '\u{fffe}' | '\u{ffff}' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Turns the key of a custom document information entry into an XMP property
+/// name. Like Adobe's tools, we encode each byte of a character that isn't
+/// allowed in the name as U+2182 followed by two hex digits.
+fn xmp_name(key: &str) -> EcoString {
+    let mut name = EcoString::new();
+    for (i, c) in key.chars().enumerate() {
+        let valid = c.is_ascii_alphabetic()
+            || c == '_'
+            || (i > 0 && (c.is_ascii_digit() || matches!(c, '-' | '.')));
+        if valid {
+            name.push(c);
+        } else {
+            for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                name.push_str(&eco_format!("\u{2182}{byte:02X}"));
+            }
+        }
+    }
+    name
+}
+
 /// A timestamp with timezone information.
 #[derive(Debug, Copy, Clone, Hash)]
 pub struct Timestamp {
@@ -147,6 +363,21 @@ pub enum Timezone {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a < b & \"c\""), "a &lt; b &amp; &quot;c&quot;");
+        assert_eq!(escape("line\nbreak\ttab"), "line\nbreak\ttab");
+        assert_eq!(escape("bell\x07 nul\0 \u{ffff}ok"), "bell nul ok");
+    }
+
+    #[test]
+    fn test_xmp_name() {
+        assert_eq!(xmp_name("Department"), "Department");
+        assert_eq!(xmp_name("Job Number"), "Job\u{2182}20Number");
+        assert_eq!(xmp_name("1st-Draft"), "\u{2182}31st-Draft");
+        assert_eq!(xmp_name("Größe"), "Gr\u{2182}C3\u{2182}B6\u{2182}C3\u{2182}9Fe");
+    }
+
     #[test]
     fn test_timestamp_new_local() {
         let dummy_datetime = Datetime::from_ymd_hms(2024, 12, 17, 10, 10, 10).unwrap();
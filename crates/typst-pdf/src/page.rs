@@ -1,7 +1,11 @@
 use std::num::NonZeroU32;
 
 use krilla::page::{NumberingStyle, PageLabel};
+use typst_layout::PagedDocument;
+use typst_library::foundations::{NativeElement, Packed, Smart, StyleChain};
+use typst_library::introspection::Introspector;
 use typst_library::model::Numbering;
+use typst_library::pdf::{PageLabelElem, PageLabelStyle};
 
 pub(crate) trait PageLabelExt {
     /// Create a new `PageLabel` from a `Numbering` applied to a page
@@ -60,3 +64,61 @@ impl PageLabelExt for PageLabel {
         )
     }
 }
+
+/// The page labels set with `pdf.page-label`.
+pub(crate) struct PageLabelOverrides(Vec<(usize, Packed<PageLabelElem>)>);
+
+impl PageLabelOverrides {
+    /// Collects the page label elements along with the index of the page
+    /// they are on.
+    pub(crate) fn new(document: &PagedDocument) -> Self {
+        let introspector = document.introspector();
+        let mut overrides: Vec<_> = introspector
+            .query(&PageLabelElem::ELEM.select())
+            .iter()
+            .filter_map(|elem| {
+                let page = introspector.page(elem.location()?)?;
+                let elem = elem.to_packed::<PageLabelElem>()?.clone();
+                Some((page.get() - 1, elem))
+            })
+            .collect();
+
+        // Elements are in document order, but a float may end up on an
+        // earlier page than preceding content. The sort is stable, so the
+        // last element on a page still wins.
+        overrides.sort_by_key(|(page, _)| *page);
+        Self(overrides)
+    }
+
+    /// The label for the page at the given index, or `Smart::Auto` if it
+    /// should be derived from the page numbering.
+    pub(crate) fn get(&self, i: usize) -> Smart<PageLabel> {
+        let Some((first, elem)) = self.0.iter().rev().find(|(page, _)| *page <= i) else {
+            return Smart::Auto;
+        };
+
+        let styles = StyleChain::default();
+        let Smart::Custom(style) = elem.style.get(styles) else {
+            return Smart::Auto;
+        };
+
+        let prefix = elem.prefix.get_cloned(styles).map(Into::into);
+        let offset = style.and_then(|_| {
+            let number = elem.start.get(styles).get() + (i - first);
+            number.try_into().ok().and_then(NonZeroU32::new)
+        });
+
+        Smart::Custom(PageLabel::new(style.map(numbering_style), prefix, offset))
+    }
+}
+
+/// Converts a page label style into krilla's representation.
+fn numbering_style(style: PageLabelStyle) -> NumberingStyle {
+    match style {
+        PageLabelStyle::Arabic => NumberingStyle::Arabic,
+        PageLabelStyle::LowerRoman => NumberingStyle::LowerRoman,
+        PageLabelStyle::UpperRoman => NumberingStyle::UpperRoman,
+        PageLabelStyle::LowerAlpha => NumberingStyle::LowerAlpha,
+        PageLabelStyle::UpperAlpha => NumberingStyle::UpperAlpha,
+    }
+}
@@ -0,0 +1,636 @@
+//! Post-processing of serialized PDF files.
+//!
+//! Some PDF features, like interactive form fields, are not supported by
+//! krilla. For these, we parse the file that krilla wrote back into its
+//! objects, amend them, and serialize the file again. Only the subset of PDF
+//! that krilla produces is supported: a single classic cross-reference table
+//! and no object streams. krilla writes files like this for all PDF versions.
+//!
+//! This is not a second PDF writer. All content, resources, and the structure
+//! tree still come from krilla and their objects are passed through
+//! unchanged, except for the entries that a post-processing step explicitly
+//! modifies. Many of these features, like encryption and signatures, act on
+//! the serialized file as a whole and would need krilla to expose hooks into
+//! its serialization. Until it does, the round trip is the smallest change
+//! that works. Files that don't need any post-processing are not touched.
+//!
+//! The test suite checks that every exported file survives the round trip
+//! with [`check_round_trip`] and the parser is fuzzed with the same function.
+
+mod object;
+
+pub use self::object::{Dict, Name, Object, Ref, Stream};
+
+use std::collections::BTreeMap;
+
+use ecow::eco_format;
+use rustc_hash::FxHashMap;
+use typst_library::diag::{SourceResult, StrResult, bail};
+use typst_syntax::Span;
+
+use self::object::{Parser, write_dict, write_object};
+
+/// The largest object number that PDF readers must support.
+const MAX_OBJECT_NUMBER: u32 = 8_388_607;
+
+/// An indirect object in a PDF file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Indirect {
+    Object(Object),
+    Stream(Stream),
+}
+
+impl Indirect {
+    /// The object's dictionary, if it is a dictionary or a stream.
+    pub fn dict(&self) -> Option<&Dict> {
+        match self {
+            Self::Object(object) => object.as_dict(),
+            Self::Stream(stream) => Some(&stream.dict),
+        }
+    }
+
+    /// Mutable access to the object's dictionary.
+    pub fn dict_mut(&mut self) -> Option<&mut Dict> {
+        match self {
+            Self::Object(object) => object.as_dict_mut(),
+            Self::Stream(stream) => Some(&mut stream.dict),
+        }
+    }
+}
+
+impl From<Object> for Indirect {
+    fn from(object: Object) -> Self {
+        Self::Object(object)
+    }
+}
+
+impl From<Dict> for Indirect {
+    fn from(dict: Dict) -> Self {
+        Self::Object(Object::Dict(dict))
+    }
+}
+
+impl From<Stream> for Indirect {
+    fn from(stream: Stream) -> Self {
+        Self::Stream(stream)
+    }
+}
+
+/// A parsed PDF file.
+#[derive(Debug, Clone)]
+pub struct PdfFile {
+    /// The version from the file header, like `1.7`.
+    version: Vec<u8>,
+    /// The file's indirect objects by object number.
+    objects: BTreeMap<u32, Indirect>,
+    /// The trailer dictionary.
+    trailer: Dict,
+}
+
+impl PdfFile {
+    /// Parses a PDF file written by krilla.
+    pub fn parse(data: &[u8]) -> StrResult<Self> {
+        let version = data
+            .strip_prefix(b"%PDF-")
+            .and_then(|rest| rest.split(|b| b.is_ascii_whitespace()).next())
+            .ok_or("missing file header")?
+            .to_vec();
+
+        let tail = &data[data.len().saturating_sub(64)..];
+        let start = find_last(tail, b"startxref").ok_or("missing `startxref`")?
+            + data.len()
+            - tail.len();
+        let mut p = Parser::new(data, start + b"startxref".len());
+        let xref = usize::try_from(p.unsigned()?).map_err(|_| "invalid `startxref`")?;
+        if xref >= data.len() {
+            return Err("invalid `startxref`".into());
+        }
+
+        let mut p = Parser::new(data, xref);
+        p.expect(b"xref")?;
+
+        let mut offsets = BTreeMap::new();
+        loop {
+            p.skip_ws();
+            if p.eat(b"trailer") {
+                break;
+            }
+            let first = p.unsigned()?;
+            let count = p.unsigned()?;
+            for id in first..first.saturating_add(count) {
+                let offset = p.unsigned()?;
+                p.unsigned()?;
+                p.skip_ws();
+                if p.eat(b"n") {
+                    let id = u32::try_from(id)
+                        .ok()
+                        .filter(|&id| id <= MAX_OBJECT_NUMBER)
+                        .ok_or("invalid object number")?;
+                    offsets.insert(id, offset as usize);
+                } else if !p.eat(b"f") {
+                    return Err(eco_format!("invalid entry for object {id}"));
+                }
+            }
+        }
+
+        p.expect(b"<<")?;
+        let trailer = p.dict()?;
+        if trailer.get("Prev").is_some() || trailer.get("Encrypt").is_some() {
+            return Err("unsupported trailer".into());
+        }
+
+        let mut objects = BTreeMap::new();
+        for (&id, &offset) in &offsets {
+            let object = parse_indirect(data, id, offset, &offsets)
+                .map_err(|err| eco_format!("failed to parse object {id} ({err})"))?;
+            objects.insert(id, object);
+        }
+
+        Ok(Self { version, objects, trailer })
+    }
+
+    /// Creates a file from objects that are numbered from one, with the first
+    /// one as the catalog.
+    #[cfg(test)]
+    pub fn from_objects(objects: impl IntoIterator<Item = Indirect>) -> Self {
+        Self {
+            version: b"1.7".to_vec(),
+            objects: (1..).zip(objects).collect(),
+            trailer: Dict::new().with("Root", Ref(1)),
+        }
+    }
+
+    /// Returns the indirect object with the given reference.
+    pub fn get(&self, r: Ref) -> Option<&Indirect> {
+        self.objects.get(&r.0)
+    }
+
+    /// Returns mutable access to the indirect object with the given reference.
+    pub fn get_mut(&mut self, r: Ref) -> Option<&mut Indirect> {
+        self.objects.get_mut(&r.0)
+    }
+
+    /// Returns the dictionary of the object with the given reference.
+    pub fn dict(&self, r: Ref) -> Option<&Dict> {
+        self.get(r)?.dict()
+    }
+
+    /// Returns mutable access to the dictionary of the object with the given
+    /// reference.
+    pub fn dict_mut(&mut self, r: Ref) -> Option<&mut Dict> {
+        self.get_mut(r)?.dict_mut()
+    }
+
+    /// Resolves an object that may be a reference.
+    pub fn resolve<'a>(&'a self, object: &'a Object) -> Option<&'a Object> {
+        match object {
+            Object::Ref(r) => match self.get(*r)? {
+                Indirect::Object(object) => Some(object),
+                Indirect::Stream(_) => None,
+            },
+            object => Some(object),
+        }
+    }
+
+    /// Iterates over all indirect objects.
+    pub fn iter(&self) -> impl Iterator<Item = (Ref, &Indirect)> {
+        self.objects.iter().map(|(&id, object)| (Ref(id), object))
+    }
+
+    /// Iterates mutably over all indirect objects.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Ref, &mut Indirect)> {
+        self.objects.iter_mut().map(|(&id, object)| (Ref(id), object))
+    }
+
+    /// Adds a new indirect object and returns its reference.
+    pub fn add(&mut self, object: impl Into<Indirect>) -> Ref {
+        let id = self.objects.last_key_value().map_or(1, |(&id, _)| id + 1);
+        self.objects.insert(id, object.into());
+        Ref(id)
+    }
+
+    /// Replaces the indirect object with the given reference.
+    pub fn set(&mut self, r: Ref, object: impl Into<Indirect>) {
+        self.objects.insert(r.0, object.into());
+    }
+
+    /// The version from the file header, like `1.7`.
+    pub fn version(&self) -> &[u8] {
+        &self.version
+    }
+
+    /// The trailer dictionary.
+    pub fn trailer(&self) -> &Dict {
+        &self.trailer
+    }
+
+    /// Mutable access to the trailer dictionary.
+    pub fn trailer_mut(&mut self) -> &mut Dict {
+        &mut self.trailer
+    }
+
+    /// The reference to the document catalog.
+    pub fn catalog(&self) -> Option<Ref> {
+        self.trailer.get("Root")?.as_ref()
+    }
+
+    /// The references to all pages, in order.
+    pub fn pages(&self) -> Vec<Ref> {
+        let mut pages = vec![];
+        if let Some(root) = self.catalog()
+            && let Some(tree) = self.dict(root).and_then(|d| d.get("Pages")?.as_ref())
+        {
+            self.collect_pages(tree, &mut pages, 0);
+        }
+        pages
+    }
+
+    fn collect_pages(&self, node: Ref, pages: &mut Vec<Ref>, depth: usize) {
+        let Some(dict) = self.dict(node) else { return };
+        if dict.is_type("Page") {
+            pages.push(node);
+        } else if let Some(kids) = dict.get("Kids").and_then(Object::as_array)
+            && depth < 64
+        {
+            for kid in kids.iter().filter_map(Object::as_ref) {
+                self.collect_pages(kid, pages, depth + 1);
+            }
+        }
+    }
+
+    /// Finds the link annotations whose URI consists of the given prefix and
+    /// an index below `count`. Returns a map from the indices to the
+    /// annotations and the pages they are on.
+    ///
+    /// Features that krilla doesn't support are written as such placeholder
+    /// links, so that krilla still takes care of positioning and tagging them.
+    pub fn placeholders(
+        &self,
+        prefix: &str,
+        count: usize,
+    ) -> FxHashMap<usize, (Ref, Option<Ref>)> {
+        let mut pages = FxHashMap::default();
+        for page in self.pages() {
+            let Some(annots) = self
+                .dict(page)
+                .and_then(|dict| dict.get("Annots"))
+                .and_then(|annots| self.resolve(annots))
+                .and_then(Object::as_array)
+            else {
+                continue;
+            };
+
+            for annot in annots.iter().filter_map(Object::as_ref) {
+                pages.insert(annot, page);
+            }
+        }
+
+        let mut placeholders = FxHashMap::default();
+        for (r, object) in self.iter() {
+            let Some(dict) = object.dict() else { continue };
+            if dict
+                .get("Subtype")
+                .and_then(Object::as_name)
+                .is_none_or(|n| *n != *"Link")
+            {
+                continue;
+            }
+
+            let Some(index) = dict
+                .get("A")
+                .and_then(|action| self.resolve(action))
+                .and_then(Object::as_dict)
+                .and_then(|action| action.get("URI"))
+                .and_then(Object::as_str)
+                .and_then(|uri| uri.strip_prefix(prefix.as_bytes()))
+                .and_then(|index| std::str::from_utf8(index).ok())
+                .and_then(|index| index.parse::<usize>().ok())
+                .filter(|&index| index < count)
+            else {
+                continue;
+            };
+
+            placeholders.insert(index, (r, pages.get(&r).copied()));
+        }
+
+        placeholders
+    }
+
+    /// Serializes the file.
+    pub fn write(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        buf.extend(b"%PDF-");
+        buf.extend(&self.version);
+        buf.extend(b"\n%\x80\x80\x80\x80\n\n");
+
+        let size = self.objects.last_key_value().map_or(1, |(&id, _)| id + 1);
+        let mut offsets = vec![None; size as usize];
+        for (&id, object) in &self.objects {
+            offsets[id as usize] = Some(buf.len());
+            buf.extend(format!("{id} 0 obj\n").as_bytes());
+            match object {
+                Indirect::Object(object) => write_object(&mut buf, object),
+                Indirect::Stream(stream) => {
+                    let mut dict = stream.dict.clone();
+                    dict.set("Length", stream.data.len() as i64);
+                    write_dict(&mut buf, &dict);
+                    buf.extend(b"\nstream\n");
+                    buf.extend(&stream.data);
+                    buf.extend(b"\nendstream");
+                }
+            }
+            buf.extend(b"\nendobj\n\n");
+        }
+
+        let xref = buf.len();
+        buf.extend(format!("xref\n0 {size}\n").as_bytes());
+        for offset in offsets {
+            match offset {
+                Some(offset) => {
+                    buf.extend(format!("{offset:010} 00000 n\r\n").as_bytes())
+                }
+                None => buf.extend(b"0000000000 65535 f\r\n"),
+            }
+        }
+
+        let mut trailer = self.trailer.clone();
+        trailer.set("Size", i64::from(size));
+        buf.extend(b"trailer\n");
+        write_dict(&mut buf, &trailer);
+        buf.extend(format!("\nstartxref\n{xref}\n%%EOF").as_bytes());
+        buf
+    }
+}
+
+/// Checks that a file written by krilla or by post-processing survives being
+/// parsed and written again without any change to its objects.
+pub fn check_round_trip(data: &[u8]) -> StrResult<()> {
+    let file = PdfFile::parse(data)?;
+    let written = file.write();
+    let reparsed = PdfFile::parse(&written)
+        .map_err(|err| eco_format!("failed to parse written file ({err})"))?;
+
+    if reparsed.version != file.version {
+        return Err("version changed in round trip".into());
+    }
+
+    // The size is always recomputed when writing.
+    let mut trailer = file.trailer.clone();
+    if let Some(size) = reparsed.trailer.get("Size") {
+        trailer.set("Size", size.clone());
+    }
+    if reparsed.trailer != trailer {
+        return Err("trailer changed in round trip".into());
+    }
+
+    if let Some((&id, _)) = file
+        .objects
+        .iter()
+        .find(|&(id, object)| reparsed.objects.get(id) != Some(object))
+    {
+        return Err(eco_format!("object {id} changed in round trip"));
+    }
+
+    if reparsed.objects.len() != file.objects.len() {
+        return Err("number of objects changed in round trip".into());
+    }
+
+    if reparsed.write() != written {
+        return Err("writing is not deterministic".into());
+    }
+
+    Ok(())
+}
+
+/// Applies a modification to a serialized PDF file.
+pub fn patch(
+    data: Vec<u8>,
+    f: impl FnOnce(&mut PdfFile) -> SourceResult<()>,
+) -> SourceResult<Vec<u8>> {
+    let mut file = match PdfFile::parse(&data) {
+        Ok(file) => file,
+        Err(err) => bail!(
+            Span::detached(),
+            "failed to post-process PDF file ({err})";
+            hint: "please report this as a bug";
+        ),
+    };
+    f(&mut file)?;
+    Ok(file.write())
+}
+
+/// Parses the indirect object with the given number at the given offset.
+fn parse_indirect(
+    data: &[u8],
+    id: u32,
+    offset: usize,
+    offsets: &BTreeMap<u32, usize>,
+) -> StrResult<Indirect> {
+    if offset >= data.len() {
+        return Err("invalid offset".into());
+    }
+
+    let mut p = Parser::new(data, offset);
+    if p.object_header()? != id {
+        return Err("mismatched object number".into());
+    }
+
+    let object = p.object()?;
+    p.skip_ws();
+    if !p.eat(b"stream") {
+        p.expect(b"endobj")?;
+        return Ok(Indirect::Object(object));
+    }
+
+    let Object::Dict(dict) = object else {
+        return Err("stream without dictionary".into());
+    };
+
+    // The stream data starts after the end-of-line marker.
+    if !p.eat(b"\r\n") && !p.eat(b"\n") {
+        p.eat(b"\r");
+    }
+    let start = p.pos();
+
+    let length = match dict.get("Length") {
+        Some(Object::Int(length)) => *length,
+        Some(Object::Ref(r)) => {
+            let offset = offsets.get(&r.0).ok_or("invalid stream length")?;
+            let mut p = Parser::new(data, *offset);
+            p.object_header()?;
+            p.object()?.as_int().ok_or("invalid stream length")?
+        }
+        _ => return Err("missing stream length".into()),
+    };
+
+    let end = usize::try_from(length)
+        .ok()
+        .and_then(|length| start.checked_add(length))
+        .filter(|&end| end <= data.len())
+        .ok_or("invalid stream length")?;
+
+    let mut p = Parser::new(data, end);
+    p.expect(b"endstream")?;
+    p.expect(b"endobj")?;
+
+    // The length is written directly when the file is serialized again.
+    let mut dict = dict;
+    dict.set("Length", length);
+
+    Ok(Indirect::Stream(Stream { dict, data: data[start..end].to_vec() }))
+}
+
+/// Finds the last occurrence of `needle` in `haystack`.
+fn find_last(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use ecow::EcoVec;
+    use typst_layout::{Page, PagedDocument};
+    use typst_library::foundations::{Content, Smart};
+    use typst_library::layout::{Abs, Frame, FrameItem, Point, Sides, Size};
+    use typst_library::model::DocumentInfo;
+    use typst_library::visualize::{Color, Geometry};
+
+    use super::*;
+    use crate::{PdfEncryption, PdfOptions, PdfPermissions, PdfStandard, PdfStandards};
+
+    const MINIMAL: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595.5 842] \
+        /Contents 4 0 R /Annots [] >>\nendobj\n\
+        4 0 obj\n<< /Length 5 >>\nstream\nq Q\n\nendstream\nendobj\n";
+
+    fn minimal() -> Vec<u8> {
+        let mut data = MINIMAL.to_vec();
+        let offsets: Vec<_> = (1..=4)
+            .map(|id| find_last(&data, format!("\n{id} 0 obj").as_bytes()).unwrap() + 1)
+            .collect();
+        let xref = data.len();
+        data.extend(b"xref\n0 5\n0000000000 65535 f\r\n");
+        for offset in offsets {
+            data.extend(format!("{offset:010} 00000 n\r\n").as_bytes());
+        }
+        data.extend(b"trailer\n<< /Size 5 /Root 1 0 R >>\nstartxref\n");
+        data.extend(format!("{xref}\n%%EOF").as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_and_write() {
+        let file = PdfFile::parse(&minimal()).unwrap();
+        assert_eq!(file.catalog(), Some(Ref(1)));
+        assert_eq!(file.pages(), vec![Ref(3)]);
+
+        let page = file.dict(Ref(3)).unwrap();
+        assert_eq!(
+            page.get("MediaBox"),
+            Some(&Object::Array(vec![
+                Object::Int(0),
+                Object::Int(0),
+                Object::Real(595.5),
+                Object::Int(842),
+            ]))
+        );
+
+        let Some(Indirect::Stream(stream)) = file.get(Ref(4)) else { panic!() };
+        assert_eq!(stream.data, b"q Q\n\n");
+
+        // A written file parses to the same objects.
+        let written = file.write();
+        let reparsed = PdfFile::parse(&written).unwrap();
+        assert_eq!(reparsed.objects, file.objects);
+        assert_eq!(reparsed.trailer.get("Root"), Some(&Object::Ref(Ref(1))));
+    }
+
+    #[test]
+    fn test_parse_objects() {
+        let mut p =
+            Parser::new(b"<< /A#20B (a\\(b\\)\\101) /C <4869> /D [1 -2 3 0 R] >>", 0);
+        let object = p.object().unwrap();
+        let dict = object.as_dict().unwrap();
+        assert_eq!(dict.get("A B"), Some(&Object::Str(b"a(b)A".to_vec())));
+        assert_eq!(dict.get("C"), Some(&Object::Str(b"Hi".to_vec())));
+        assert_eq!(
+            dict.get("D"),
+            Some(&Object::Array(vec![
+                Object::Int(1),
+                Object::Int(-2),
+                Object::Ref(Ref(3))
+            ]))
+        );
+
+        let mut buf = vec![];
+        write_object(&mut buf, &object);
+        assert_eq!(buf, b"<< /A#20B (a\\(b\\)A) /C (Hi) /D [1 -2 3 0 R] >>");
+    }
+
+    #[test]
+    fn test_reject_deep_nesting() {
+        let data = [b'['; 10_000];
+        assert!(Parser::new(&data, 0).object().is_err());
+        assert!(Parser::new(&data, data.len() + 1).object().is_err());
+    }
+
+    /// Creates a document with a few pages of rectangles.
+    fn document() -> PagedDocument {
+        let pages = [10.0, 20.0, 30.0]
+            .into_iter()
+            .map(|width| {
+                let mut frame = Frame::hard(Size::splat(Abs::pt(100.0)));
+                let size = Size::new(Abs::pt(width), Abs::pt(10.0));
+                let shape = Geometry::Rect(size).filled(Color::BLACK);
+                frame.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+                Page {
+                    frame,
+                    bleed: Sides::splat(Abs::zero()),
+                    marks: Sides::splat(Abs::zero()),
+                    fill: Smart::Auto,
+                    numbering: None,
+                    supplement: Content::empty(),
+                    number: 1,
+                }
+            })
+            .collect::<EcoVec<_>>();
+        PagedDocument::new(pages, DocumentInfo::default())
+    }
+
+    #[test]
+    fn test_round_trip_krilla_output() {
+        let document = document();
+        for version in [
+            PdfStandard::V_1_4,
+            PdfStandard::V_1_5,
+            PdfStandard::V_1_6,
+            PdfStandard::V_1_7,
+            PdfStandard::V_2_0,
+        ] {
+            let encrypted = matches!(version, PdfStandard::V_1_7 | PdfStandard::V_2_0);
+            for (tagged, pretty, encrypt) in [
+                (false, false, false),
+                (true, false, false),
+                (true, true, false),
+                (true, false, encrypted),
+            ] {
+                let options = PdfOptions {
+                    standards: PdfStandards::new(&[version]).unwrap(),
+                    tagged,
+                    pretty,
+                    encryption: encrypt.then(|| PdfEncryption {
+                        user_password: "user".into(),
+                        owner_password: Some("owner".into()),
+                        permissions: PdfPermissions::default(),
+                    }),
+                    ..Default::default()
+                };
+                let pdf = crate::pdf(&document, &options).unwrap();
+                if let Err(err) = check_round_trip(&pdf) {
+                    panic!("{version:?} (tagged: {tagged}, pretty: {pretty}): {err}");
+                }
+            }
+        }
+    }
+}
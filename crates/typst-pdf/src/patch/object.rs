@@ -0,0 +1,600 @@
+//! A minimal model of PDF objects with parsing and serialization.
+
+use std::io::Write as _;
+
+use ecow::{EcoString, eco_format};
+use typst_library::diag::StrResult;
+
+/// A direct PDF object.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Object {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Real(f64),
+    Str(Vec<u8>),
+    Name(Name),
+    Array(Vec<Object>),
+    Dict(Dict),
+    Ref(Ref),
+}
+
+impl Object {
+    /// Creates a text string, encoded in PDFDocEncoding if it is printable
+    /// ASCII and UTF-16BE otherwise.
+    pub fn text(text: &str) -> Self {
+        if text.bytes().all(|b| (0x20..0x7f).contains(&b)) {
+            Self::Str(text.as_bytes().to_vec())
+        } else {
+            let mut buf = vec![0xfe, 0xff];
+            for unit in text.encode_utf16() {
+                buf.extend(unit.to_be_bytes());
+            }
+            Self::Str(buf)
+        }
+    }
+
+    /// Creates a name.
+    pub fn name(name: &str) -> Self {
+        Self::Name(Name::new(name))
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        if let Self::Int(v) = self { Some(*v) } else { None }
+    }
+
+    /// The value of an integer or real number.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::Int(v) => Some(v as f64),
+            Self::Real(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_name(&self) -> Option<&Name> {
+        if let Self::Name(v) = self { Some(v) } else { None }
+    }
+
+    pub fn as_str(&self) -> Option<&[u8]> {
+        if let Self::Str(v) = self { Some(v) } else { None }
+    }
+
+    pub fn as_array(&self) -> Option<&[Object]> {
+        if let Self::Array(v) = self { Some(v) } else { None }
+    }
+
+    pub fn as_dict(&self) -> Option<&Dict> {
+        if let Self::Dict(v) = self { Some(v) } else { None }
+    }
+
+    pub fn as_dict_mut(&mut self) -> Option<&mut Dict> {
+        if let Self::Dict(v) = self { Some(v) } else { None }
+    }
+
+    pub fn as_ref(&self) -> Option<Ref> {
+        if let Self::Ref(v) = self { Some(*v) } else { None }
+    }
+}
+
+impl From<bool> for Object {
+    fn from(v: bool) -> Self {
+        Self::Bool(v)
+    }
+}
+
+impl From<i64> for Object {
+    fn from(v: i64) -> Self {
+        Self::Int(v)
+    }
+}
+
+impl From<f64> for Object {
+    fn from(v: f64) -> Self {
+        Self::Real(v)
+    }
+}
+
+impl From<Name> for Object {
+    fn from(v: Name) -> Self {
+        Self::Name(v)
+    }
+}
+
+impl From<Vec<Object>> for Object {
+    fn from(v: Vec<Object>) -> Self {
+        Self::Array(v)
+    }
+}
+
+impl From<Dict> for Object {
+    fn from(v: Dict) -> Self {
+        Self::Dict(v)
+    }
+}
+
+impl From<Ref> for Object {
+    fn from(v: Ref) -> Self {
+        Self::Ref(v)
+    }
+}
+
+/// A reference to an indirect object.
+///
+/// Generation numbers are not modelled since a freshly written file only
+/// contains objects of generation zero.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Ref(pub u32);
+
+/// A PDF name, without the leading slash.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Name(pub Vec<u8>);
+
+impl Name {
+    pub fn new(name: &str) -> Self {
+        Self(name.as_bytes().to_vec())
+    }
+}
+
+impl From<&str> for Name {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl PartialEq<str> for Name {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+/// A PDF dictionary that preserves the order of its entries.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Dict(Vec<(Name, Object)>);
+
+impl Dict {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Object> {
+        self.0.iter().find(|(k, _)| *k == *key).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Object> {
+        self.0.iter_mut().find(|(k, _)| *k == *key).map(|(_, v)| v)
+    }
+
+    /// Sets the value of an entry, replacing an existing one.
+    pub fn set(&mut self, key: impl Into<Name>, value: impl Into<Object>) {
+        let (key, value) = (key.into(), value.into());
+        match self.0.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, slot)) => *slot = value,
+            None => self.0.push((key, value)),
+        }
+    }
+
+    /// Builder-style variant of [`set`](Self::set).
+    pub fn with(mut self, key: impl Into<Name>, value: impl Into<Object>) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Name, &Object)> {
+        self.0.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Name, &mut Object)> {
+        self.0.iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    /// Whether the dictionary has a `/Type` entry with the given name.
+    pub fn is_type(&self, ty: &str) -> bool {
+        self.get("Type")
+            .and_then(Object::as_name)
+            .is_some_and(|name| *name == *ty)
+    }
+}
+
+/// A stream object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stream {
+    /// The stream dictionary. Its `/Length` is updated on serialization.
+    pub dict: Dict,
+    /// The raw, possibly filtered, stream data.
+    pub data: Vec<u8>,
+}
+
+/// The maximum nesting depth of arrays and dictionaries, which protects the
+/// recursive parser from overflowing its stack.
+const MAX_DEPTH: usize = 256;
+
+/// A parser for PDF objects.
+pub struct Parser<'a> {
+    data: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(data: &'a [u8], pos: usize) -> Self {
+        Self { data, pos: pos.min(data.len()), depth: 0 }
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Parses a direct object.
+    pub fn object(&mut self) -> StrResult<Object> {
+        self.skip_ws();
+        match self.peek() {
+            Some(b'/') => {
+                self.pos += 1;
+                Ok(Object::Name(self.name()))
+            }
+            Some(b'(') => {
+                self.pos += 1;
+                self.literal_string().map(Object::Str)
+            }
+            Some(b'<') if self.data.get(self.pos + 1) == Some(&b'<') => {
+                self.pos += 2;
+                self.nested(Self::dict).map(Object::Dict)
+            }
+            Some(b'<') => {
+                self.pos += 1;
+                self.hex_string().map(Object::Str)
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                self.nested(Self::array).map(Object::Array)
+            }
+            Some(b'0'..=b'9' | b'+' | b'-' | b'.') => self.number_or_ref(),
+            _ => match self.keyword() {
+                b"true" => Ok(Object::Bool(true)),
+                b"false" => Ok(Object::Bool(false)),
+                b"null" => Ok(Object::Null),
+                other => Err(self.error(&eco_format!(
+                    "unexpected `{}`",
+                    String::from_utf8_lossy(other)
+                ))),
+            },
+        }
+    }
+
+    /// Parses an indirect object header like `12 0 obj` and returns the
+    /// object number.
+    pub fn object_header(&mut self) -> StrResult<u32> {
+        let id = self.unsigned()?;
+        self.unsigned()?;
+        self.expect(b"obj")?;
+        u32::try_from(id).map_err(|_| self.error("invalid object number"))
+    }
+
+    /// Parses the dictionary contents after the opening `<<`.
+    pub fn dict(&mut self) -> StrResult<Dict> {
+        let mut dict = Dict::new();
+        loop {
+            self.skip_ws();
+            if self.eat(b">>") {
+                return Ok(dict);
+            }
+            if !self.eat(b"/") {
+                return Err(self.error("expected name"));
+            }
+            let key = self.name();
+            let value = self.object()?;
+            dict.0.push((key, value));
+        }
+    }
+
+    /// Parses the array contents after the opening `[`.
+    fn array(&mut self) -> StrResult<Vec<Object>> {
+        let mut items = vec![];
+        loop {
+            self.skip_ws();
+            if self.eat(b"]") {
+                return Ok(items);
+            }
+            items.push(self.object()?);
+        }
+    }
+
+    /// Parses a nested array or dictionary, enforcing the maximum depth.
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> StrResult<T>) -> StrResult<T> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("too deeply nested"));
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Parses an unsigned integer.
+    pub fn unsigned(&mut self) -> StrResult<u64> {
+        self.skip_ws();
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.data[start..self.pos])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| self.error("expected integer"))
+    }
+
+    /// Consumes the given keyword or fails.
+    pub fn expect(&mut self, keyword: &[u8]) -> StrResult<()> {
+        self.skip_ws();
+        if self.eat(keyword) {
+            Ok(())
+        } else {
+            Err(self
+                .error(&eco_format!("expected `{}`", String::from_utf8_lossy(keyword))))
+        }
+    }
+
+    /// Consumes the given bytes if they come next.
+    pub fn eat(&mut self, bytes: &[u8]) -> bool {
+        if self.data.get(self.pos..).is_some_and(|rest| rest.starts_with(bytes)) {
+            self.pos += bytes.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Skips whitespace and comments.
+    pub fn skip_ws(&mut self) {
+        while let Some(b) = self.peek() {
+            if is_whitespace(b) {
+                self.pos += 1;
+            } else if b == b'%' {
+                while self.peek().is_some_and(|b| b != b'\n' && b != b'\r') {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn number_or_ref(&mut self) -> StrResult<Object> {
+        let token = self.keyword();
+        let text =
+            std::str::from_utf8(token).map_err(|_| self.error("invalid number"))?;
+        if text.contains('.') {
+            return text
+                .parse()
+                .map(Object::Real)
+                .map_err(|_| self.error("invalid number"));
+        }
+
+        let int: i64 = text.parse().map_err(|_| self.error("invalid number"))?;
+
+        // Check for a reference like `12 0 R`.
+        let after = self.pos;
+        if int >= 0 && token[0].is_ascii_digit() {
+            self.skip_ws();
+            if self.peek().is_some_and(|b| b.is_ascii_digit()) && self.unsigned().is_ok()
+            {
+                self.skip_ws();
+                if self.keyword() == b"R" {
+                    return u32::try_from(int)
+                        .map(|id| Object::Ref(Ref(id)))
+                        .map_err(|_| self.error("invalid object number"));
+                }
+            }
+        }
+
+        self.pos = after;
+        Ok(Object::Int(int))
+    }
+
+    fn name(&mut self) -> Name {
+        let mut name = vec![];
+        while let Some(b) = self.peek() {
+            if is_whitespace(b) || is_delimiter(b) {
+                break;
+            }
+            self.pos += 1;
+            if b == b'#'
+                && let Some(hex) = self.data.get(self.pos..self.pos + 2)
+                && let Some(byte) = decode_hex_pair(hex[0], hex[1])
+            {
+                name.push(byte);
+                self.pos += 2;
+            } else {
+                name.push(b);
+            }
+        }
+        Name(name)
+    }
+
+    fn literal_string(&mut self) -> StrResult<Vec<u8>> {
+        let mut buf = vec![];
+        let mut depth = 1;
+        loop {
+            let Some(b) = self.peek() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += 1;
+            match b {
+                b'(' => {
+                    depth += 1;
+                    buf.push(b);
+                }
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(buf);
+                    }
+                    buf.push(b);
+                }
+                b'\\' => {
+                    let Some(c) = self.peek() else { continue };
+                    self.pos += 1;
+                    match c {
+                        b'n' => buf.push(b'\n'),
+                        b'r' => buf.push(b'\r'),
+                        b't' => buf.push(b'\t'),
+                        b'b' => buf.push(0x08),
+                        b'f' => buf.push(0x0c),
+                        b'0'..=b'7' => {
+                            let mut value = u32::from(c - b'0');
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(d @ b'0'..=b'7') => {
+                                        value = value * 8 + u32::from(d - b'0');
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            buf.push(value as u8);
+                        }
+                        // A backslash at the end of a line continues the
+                        // string on the next line.
+                        b'\r' => {
+                            self.eat(b"\n");
+                        }
+                        b'\n' => {}
+                        other => buf.push(other),
+                    }
+                }
+                other => buf.push(other),
+            }
+        }
+    }
+
+    fn hex_string(&mut self) -> StrResult<Vec<u8>> {
+        let mut digits = vec![];
+        loop {
+            let Some(b) = self.peek() else {
+                return Err(self.error("unterminated hex string"));
+            };
+            self.pos += 1;
+            match b {
+                b'>' => break,
+                b if b.is_ascii_hexdigit() => digits.push(b),
+                b if is_whitespace(b) => {}
+                _ => return Err(self.error("invalid hex string")),
+            }
+        }
+        if digits.len() % 2 == 1 {
+            digits.push(b'0');
+        }
+        Ok(digits
+            .chunks(2)
+            .map(|pair| decode_hex_pair(pair[0], pair[1]).unwrap())
+            .collect())
+    }
+
+    fn keyword(&mut self) -> &'a [u8] {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| !is_whitespace(b) && !is_delimiter(b)) {
+            self.pos += 1;
+        }
+        &self.data[start..self.pos]
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> EcoString {
+        eco_format!("{message} at offset {}", self.pos)
+    }
+}
+
+/// Serializes an object.
+pub fn write_object(buf: &mut Vec<u8>, object: &Object) {
+    match object {
+        Object::Null => buf.extend(b"null"),
+        Object::Bool(v) => buf.extend(if *v { &b"true"[..] } else { b"false" }),
+        Object::Int(v) => buf.extend(v.to_string().as_bytes()),
+        Object::Real(v) => write_real(buf, *v),
+        Object::Str(v) => write_string(buf, v),
+        Object::Name(v) => write_name(buf, v),
+        Object::Array(items) => {
+            buf.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    buf.push(b' ');
+                }
+                write_object(buf, item);
+            }
+            buf.push(b']');
+        }
+        Object::Dict(dict) => write_dict(buf, dict),
+        Object::Ref(Ref(id)) => buf.extend(format!("{id} 0 R").as_bytes()),
+    }
+}
+
+/// Serializes a dictionary.
+pub fn write_dict(buf: &mut Vec<u8>, dict: &Dict) {
+    buf.extend(b"<<");
+    for (key, value) in dict.iter() {
+        buf.push(b' ');
+        write_name(buf, key);
+        buf.push(b' ');
+        write_object(buf, value);
+    }
+    buf.extend(b" >>");
+}
+
+fn write_real(buf: &mut Vec<u8>, value: f64) {
+    // The `Display` implementation writes the shortest representation that
+    // parses back to the same value and never uses an exponent, which PDF
+    // doesn't support. A decimal point keeps the number a real.
+    let value = if value.is_finite() { value } else { 0.0 };
+    let start = buf.len();
+    write!(buf, "{value}").unwrap();
+    if !buf[start..].contains(&b'.') {
+        buf.extend(b".0");
+    }
+}
+
+fn write_name(buf: &mut Vec<u8>, name: &Name) {
+    buf.push(b'/');
+    for &b in &name.0 {
+        if b <= b' ' || b >= 0x7f || b == b'#' || is_delimiter(b) {
+            buf.extend(format!("#{b:02X}").as_bytes());
+        } else {
+            buf.push(b);
+        }
+    }
+}
+
+fn write_string(buf: &mut Vec<u8>, value: &[u8]) {
+    if value.iter().all(|&b| (0x20..0x7f).contains(&b)) {
+        buf.push(b'(');
+        for &b in value {
+            if matches!(b, b'(' | b')' | b'\\') {
+                buf.push(b'\\');
+            }
+            buf.push(b);
+        }
+        buf.push(b')');
+    } else {
+        buf.push(b'<');
+        for b in value {
+            buf.extend(format!("{b:02X}").as_bytes());
+        }
+        buf.push(b'>');
+    }
+}
+
+fn is_whitespace(b: u8) -> bool {
+    matches!(b, b'\0' | b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+fn is_delimiter(b: u8) -> bool {
+    matches!(b, b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%')
+}
+
+fn decode_hex_pair(a: u8, b: u8) -> Option<u8> {
+    let digit = |c: u8| (c as char).to_digit(16);
+    Some((digit(a)? * 16 + digit(b)?) as u8)
+}
@@ -0,0 +1,340 @@
+//! The PDF/X standards for print production.
+//!
+//! krilla doesn't know about PDF/X, so we validate the document's colors
+//! ourselves during conversion and add the required page boxes, output
+//! intent, and metadata in post-processing. The output profile of the intent
+//! must be supplied by the user since it depends on the printing process.
+
+use std::io::Write;
+
+use flate2::Compression;
+use flate2::write::ZlibEncoder;
+use krilla::configure::PdfVersion;
+use typst_library::diag::{At, SourceResult, bail};
+use typst_library::model::Document;
+use typst_library::visualize::{
+    Color, ColorSpace, ExchangeFormat, Gradient, Image, ImageKind, Paint,
+    ProcessColorSpace, RasterFormat,
+};
+use typst_syntax::Span;
+
+use crate::convert::GlobalContext;
+use crate::metadata::{creation_date, info_dict, insert_xmp};
+use crate::patch::{Dict, Object, PdfFile, Stream};
+use crate::{PdfOptions, PdfOutputIntent};
+
+/// A PDF/X standard.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub(crate) enum PdfX {
+    /// PDF/X-1a:2003, which only allows CMYK, gray, and spot colors.
+    X1a,
+    /// PDF/X-3:2003, which also allows color-managed RGB.
+    X3,
+    /// PDF/X-4, which also allows transparency and layers.
+    X4,
+}
+
+impl PdfX {
+    /// The name of the standard.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::X1a => "PDF/X-1a",
+            Self::X3 => "PDF/X-3",
+            Self::X4 => "PDF/X-4",
+        }
+    }
+
+    /// The PDF version the standard is based on.
+    pub(crate) fn version(self) -> PdfVersion {
+        match self {
+            Self::X1a | Self::X3 => PdfVersion::Pdf14,
+            Self::X4 => PdfVersion::Pdf16,
+        }
+    }
+
+    /// Whether the standard allows RGB colors.
+    fn allows_rgb(self) -> bool {
+        self != Self::X1a
+    }
+
+    /// Whether the standard allows transparency.
+    fn allows_transparency(self) -> bool {
+        self == Self::X4
+    }
+}
+
+/// Checks that the document can be exported with the PDF/X standard.
+pub(crate) fn validate_document(gc: &GlobalContext, x: PdfX) -> SourceResult<()> {
+    let prefix = x.as_str();
+    if gc.document.info().title.is_none() {
+        bail!(
+            Span::detached(),
+            "{prefix} error: missing document title";
+            hint: "set the title with `set document(title: [...])`";
+        );
+    }
+
+    if creation_date(gc).is_none() {
+        bail!(
+            Span::detached(),
+            "{prefix} error: missing document date";
+            hint: "set the date of the document";
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that an output intent was supplied for the PDF/X standard.
+pub(crate) fn validate_output_intent(options: &PdfOptions, x: PdfX) -> SourceResult<()> {
+    let Some(intent) = &options.output_intent else {
+        bail!(
+            Span::detached(),
+            "{} error: missing output intent",
+            x.as_str();
+            hint: "provide the ICC profile of the printing process to print with";
+            hint: "when using the CLI, pass it with `--pdf-output-profile`";
+        );
+    };
+
+    intent.validate(&options.standards).at(Span::detached())
+}
+
+/// Reads the number of color components of an ICC output device profile from
+/// its header. Returns `None` if the data isn't such a profile or its color
+/// space can't be used for an output intent.
+pub(crate) fn profile_components(profile: &[u8]) -> Option<i64> {
+    let header = profile.get(..128)?;
+    if &header[36..40] != b"acsp" || &header[12..16] != b"prtr" {
+        return None;
+    }
+
+    match &header[16..20] {
+        b"GRAY" => Some(1),
+        b"RGB " => Some(3),
+        b"CMYK" => Some(4),
+        _ => None,
+    }
+}
+
+/// Checks that a fill or stroke is allowed by the PDF/X standard, if any.
+pub(crate) fn validate_paint(
+    gc: &GlobalContext,
+    paint: &Paint,
+    span: Span,
+) -> SourceResult<()> {
+    let Some(x) = gc.options.standards.pdfx else { return Ok(()) };
+    let prefix = x.as_str();
+    match paint {
+        Paint::Solid(color) => validate_color(x, color, span),
+        Paint::Gradient(gradient) => {
+            if !x.allows_rgb() && is_rgb(&gradient.space()) {
+                bail!(
+                    span,
+                    "{prefix} error: gradients must not use an RGB color space";
+                    hint: "set the gradient's `space` to `cmyk` or `luma`";
+                );
+            }
+
+            gradient_colors(gradient).try_for_each(|color| validate_color(x, color, span))
+        }
+        // The tiling's contents are validated separately.
+        Paint::Tiling(_) => Ok(()),
+    }
+}
+
+/// Checks that a color is allowed by the PDF/X standard.
+fn validate_color(x: PdfX, color: &Color, span: Span) -> SourceResult<()> {
+    let prefix = x.as_str();
+    if !x.allows_rgb() && is_rgb(&color.space()) {
+        bail!(
+            span,
+            "{prefix} error: RGB colors are not supported";
+            hint: "use `cmyk` or `luma` colors instead";
+        );
+    }
+
+    if !x.allows_transparency() && color.alpha().is_some_and(|alpha| alpha < 1.0) {
+        bail!(
+            span,
+            "{prefix} error: the used fill or stroke has transparency";
+            hint: "try exporting with PDF/X-4, which supports transparency";
+            hint: "or don't use colors with transparency in this export mode";
+        );
+    }
+
+    Ok(())
+}
+
+/// The colors of a gradient's stops.
+fn gradient_colors(gradient: &Gradient) -> impl Iterator<Item = &Color> {
+    gradient.stops_ref().iter().map(|(color, _)| color)
+}
+
+/// Whether colors in the given color space end up as RGB in the PDF.
+fn is_rgb(space: &ColorSpace) -> bool {
+    match space {
+        ColorSpace::Process(space) => {
+            !matches!(space, ProcessColorSpace::Cmyk | ProcessColorSpace::D65Gray)
+        }
+        ColorSpace::Spot(colorant) => !matches!(
+            colorant.fallback.space(),
+            ProcessColorSpace::Cmyk | ProcessColorSpace::D65Gray
+        ),
+    }
+}
+
+/// Checks that an image is allowed by the PDF/X standard, if any.
+pub(crate) fn validate_image(
+    gc: &GlobalContext,
+    image: &Image,
+    span: Span,
+) -> SourceResult<()> {
+    let Some(x) = gc.options.standards.pdfx else { return Ok(()) };
+    let prefix = x.as_str();
+    match image.kind() {
+        ImageKind::Raster(raster) => {
+            let color = raster.dynamic().color();
+            if !x.allows_transparency() && color.has_alpha() {
+                bail!(
+                    span,
+                    "{prefix} error: the image contains transparency";
+                    hint: "try exporting with PDF/X-4, which supports transparency";
+                    hint: "or convert the image to a non-transparent one";
+                );
+            }
+
+            // JPEGs are embedded as-is, so CMYK JPEGs stay CMYK. All other
+            // images are embedded as gray or RGB.
+            let gray = color.channel_count() <= 2;
+            let cmyk =
+                matches!(raster.format(), RasterFormat::Exchange(ExchangeFormat::Jpg))
+                    && jpeg_components(raster.data()) == Some(4);
+            if !x.allows_rgb() && !gray && !cmyk {
+                bail!(
+                    span,
+                    "{prefix} error: RGB images are not supported";
+                    hint: "convert the image to a CMYK JPEG or to grayscale";
+                );
+            }
+        }
+        ImageKind::Svg(_) if !x.allows_rgb() => bail!(
+            span,
+            "{prefix} error: SVG images are not supported";
+            hint: "SVG images always use RGB colors";
+            hint: "convert the image to a CMYK JPEG or embed it as a PDF";
+        ),
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Reads the number of color components from a JPEG's frame header.
+fn jpeg_components(data: &[u8]) -> Option<u8> {
+    let mut i = 2;
+    while i + 4 <= data.len() {
+        if data[i] != 0xff {
+            return None;
+        }
+
+        let marker = data[i + 1];
+        let len = usize::from(u16::from_be_bytes([data[i + 2], data[i + 3]]));
+        if matches!(marker, 0xc0..=0xcf) && !matches!(marker, 0xc4 | 0xc8 | 0xcc) {
+            return data.get(i + 9).copied();
+        }
+
+        i += 2 + len;
+    }
+    None
+}
+
+/// Adds the page boxes, the output intent, and the metadata required by the
+/// PDF/X standard.
+pub(crate) fn write_pdfx(file: &mut PdfFile, x: PdfX, intent: &PdfOutputIntent) {
+    // Every page needs a trim box. Unless there are printer's marks, the bleed
+    // box is the whole page since the media box already includes the bleed.
+    for page in file.pages() {
+        let Some(dict) = file.dict_mut(page) else { continue };
+        let Some(media_box) = dict.get("MediaBox").cloned() else { continue };
+        if dict.get("TrimBox").is_none() {
+            dict.set("TrimBox", media_box.clone());
+        }
+        if dict.get("BleedBox").is_none() {
+            dict.set("BleedBox", media_box);
+        }
+    }
+
+    // The profile was validated before conversion.
+    let Some(n) = profile_components(&intent.profile) else { return };
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+    let data = match encoder.write_all(&intent.profile).and_then(|_| encoder.finish()) {
+        Ok(data) => data,
+        Err(_) => return,
+    };
+    let profile = file.add(Stream {
+        dict: Dict::new().with("N", n).with("Filter", Object::name("FlateDecode")),
+        data,
+    });
+
+    // Printing conditions that aren't registered are described by the
+    // embedded profile alone.
+    let mut dict = Dict::new()
+        .with("Type", Object::name("OutputIntent"))
+        .with("S", Object::name("GTS_PDFX"))
+        .with("DestOutputProfile", profile);
+    match &intent.condition {
+        Some(condition) => {
+            dict.set("OutputConditionIdentifier", Object::text(condition));
+            dict.set("RegistryName", Object::text("http://www.color.org"));
+            dict.set("Info", Object::text(condition));
+        }
+        None => {
+            dict.set("OutputConditionIdentifier", Object::text("Custom"));
+            dict.set("Info", Object::text("Custom"));
+        }
+    }
+    let intent = file.add(dict);
+    if let Some(catalog) = file.catalog().and_then(|r| file.dict_mut(r)) {
+        catalog.set("OutputIntents", vec![Object::Ref(intent)]);
+    }
+
+    let version = match x {
+        PdfX::X1a => "PDF/X-1:2003",
+        PdfX::X3 => "PDF/X-3:2003",
+        PdfX::X4 => "PDF/X-4",
+    };
+    write_info(file, x, version);
+    write_xmp(file, version);
+}
+
+/// Adds the PDF/X keys to the document information dictionary.
+fn write_info(file: &mut PdfFile, x: PdfX, version: &str) {
+    let info = info_dict(file);
+    let Some(dict) = file.dict_mut(info) else { return };
+    dict.set("GTS_PDFXVersion", Object::text(version));
+    if x == PdfX::X1a {
+        dict.set("GTS_PDFXConformance", Object::text("PDF/X-1a:2003"));
+    }
+    dict.set("Trapped", Object::name("False"));
+    if dict.get("ModDate").is_none()
+        && let Some(date) = dict.get("CreationDate").cloned()
+    {
+        dict.set("ModDate", date);
+    }
+}
+
+/// Adds the PDF/X version and the trapping state to the XMP metadata.
+fn write_xmp(file: &mut PdfFile, version: &str) {
+    let description = format!(
+        "<rdf:Description rdf:about=\"\" \
+         xmlns:pdfxid=\"http://www.npes.org/pdfx/ns/id/\" \
+         xmlns:pdfx=\"http://ns.adobe.com/pdfx/1.3/\" \
+         xmlns:pdf=\"http://ns.adobe.com/pdf/1.3/\">\
+         <pdfxid:GTS_PDFXVersion>{version}</pdfxid:GTS_PDFXVersion>\
+         <pdfx:GTS_PDFXVersion>{version}</pdfx:GTS_PDFXVersion>\
+         <pdf:Trapped>False</pdf:Trapped>\
+         </rdf:Description>"
+    );
+    insert_xmp(file, &description);
+}
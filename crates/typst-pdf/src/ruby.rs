@@ -0,0 +1,206 @@
+//! Ruby structure elements.
+//!
+//! krilla doesn't support the `Ruby`, `RB`, and `RT` structure types, so
+//! rubies, their base text, and their annotations are tagged as spans with
+//! special IDs during conversion. After serialization, we find these spans and
+//! change their structure type. A `Ruby` element holds exactly one base text
+//! and one annotation. Rubies with one annotation per character thus stay a
+//! span that receives one `Ruby` element per pair of base and annotation.
+
+use std::collections::BTreeMap;
+use std::io::Write as _;
+
+use krilla::tagging as kt;
+use smallvec::SmallVec;
+
+use crate::patch::{Dict, Object, PdfFile, Ref};
+
+/// A part of a ruby that is marked by the ID of a span.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum RubyPart {
+    /// The whole ruby.
+    Ruby,
+    /// A piece of the base text.
+    Base,
+    /// The annotation of a piece of the base text.
+    Text,
+}
+
+impl RubyPart {
+    /// Creates the ID that marks this part on the span with the given number.
+    pub(crate) fn id(self, n: u32) -> kt::TagId {
+        // 32 bytes is the maximum length the ID string can have.
+        let mut buf = SmallVec::<[u8; 32]>::new();
+        _ = write!(&mut buf, "{}{n}", self.prefix());
+        kt::TagId::from(buf)
+    }
+
+    /// Determines the part from the ID of a structure element.
+    fn from_id(id: &[u8]) -> Option<Self> {
+        [Self::Ruby, Self::Base, Self::Text]
+            .into_iter()
+            .find(|part| id.starts_with(part.prefix().as_bytes()))
+    }
+
+    /// The prefix of the IDs that mark this part. Table cell IDs start with a
+    /// digit, so they can't be confused with these.
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Ruby => "ruby-",
+            Self::Base => "rb-",
+            Self::Text => "rt-",
+        }
+    }
+}
+
+/// Turns the marked spans into `Ruby`, `RB`, and `RT` structure elements.
+///
+/// Parts whose ruby didn't end up with alternating base texts and annotations
+/// as its children stay spans, which is always valid.
+pub(crate) fn write_ruby(file: &mut PdfFile) {
+    let parts: BTreeMap<Ref, RubyPart> = file
+        .iter()
+        .filter_map(|(r, object)| {
+            let id = object.dict()?.get("ID")?.as_str()?;
+            Some((r, RubyPart::from_id(id)?))
+        })
+        .collect();
+
+    for (&ruby, _) in parts.iter().filter(|(_, part)| **part == RubyPart::Ruby) {
+        let Some(kids) = pairs(file, ruby, &parts) else { continue };
+        if let [base, text] = kids[..] {
+            set_type(file, ruby, "Ruby");
+            set_type(file, base, "RB");
+            set_type(file, text, "RT");
+            continue;
+        }
+
+        let Some(span) = file.dict(ruby) else { continue };
+        let mut elem = Dict::new();
+        for key in ["Type", "NS"] {
+            if let Some(value) = span.get(key) {
+                elem.set(key, value.clone());
+            }
+        }
+
+        let mut rubies = Vec::with_capacity(kids.len() / 2);
+        for pair in kids.chunks(2) {
+            let r = file.add(
+                elem.clone()
+                    .with("S", Object::name("Ruby"))
+                    .with("P", ruby)
+                    .with("K", pair.iter().copied().map(Object::Ref).collect::<Vec<_>>()),
+            );
+            for (&kid, ty) in pair.iter().zip(["RB", "RT"]) {
+                set_type(file, kid, ty);
+                if let Some(dict) = file.dict_mut(kid) {
+                    dict.set("P", r);
+                }
+            }
+            rubies.push(Object::Ref(r));
+        }
+
+        if let Some(dict) = file.dict_mut(ruby) {
+            dict.set("K", rubies);
+        }
+    }
+}
+
+/// The children of a ruby if they alternate between base texts and
+/// annotations, starting with a base text.
+fn pairs(file: &PdfFile, ruby: Ref, parts: &BTreeMap<Ref, RubyPart>) -> Option<Vec<Ref>> {
+    let kids: Vec<Ref> = match file.dict(ruby)?.get("K")? {
+        Object::Array(kids) => kids.iter().map(Object::as_ref).collect::<Option<_>>()?,
+        kid => vec![kid.as_ref()?],
+    };
+
+    let valid = !kids.is_empty()
+        && kids.len().is_multiple_of(2)
+        && kids.chunks(2).all(|pair| {
+            parts.get(&pair[0]) == Some(&RubyPart::Base)
+                && parts.get(&pair[1]) == Some(&RubyPart::Text)
+        });
+
+    valid.then_some(kids)
+}
+
+/// Sets the structure type of a structure element.
+fn set_type(file: &mut PdfFile, r: Ref, ty: &str) {
+    if let Some(dict) = file.dict_mut(r) {
+        dict.set("S", Object::name(ty));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patch::Indirect;
+
+    /// Creates a span structure element with the given ID.
+    fn span(parent: u32, id: &str, kids: &[u32]) -> Indirect {
+        let kids: Vec<_> = kids.iter().map(|&kid| Object::Ref(Ref(kid))).collect();
+        Dict::new()
+            .with("Type", Object::name("StructElem"))
+            .with("S", Object::name("Span"))
+            .with("P", Ref(parent))
+            .with("ID", Object::Str(id.as_bytes().to_vec()))
+            .with("K", kids)
+            .into()
+    }
+
+    fn ty(file: &PdfFile, r: u32) -> &str {
+        let name = file.dict(Ref(r)).unwrap().get("S").unwrap().as_name().unwrap();
+        std::str::from_utf8(&name.0).unwrap()
+    }
+
+    #[test]
+    fn test_single_pair() {
+        let mut file = PdfFile::from_objects([
+            Dict::new().with("Type", Object::name("Catalog")).into(),
+            span(1, "ruby-0", &[3, 4]),
+            span(2, "rb-1", &[]),
+            span(2, "rt-2", &[]),
+        ]);
+        write_ruby(&mut file);
+        assert_eq!(ty(&file, 2), "Ruby");
+        assert_eq!(ty(&file, 3), "RB");
+        assert_eq!(ty(&file, 4), "RT");
+        assert_eq!(file.dict(Ref(3)).unwrap().get("P"), Some(&Object::Ref(Ref(2))));
+    }
+
+    #[test]
+    fn test_multiple_pairs() {
+        let mut file = PdfFile::from_objects([
+            Dict::new().with("Type", Object::name("Catalog")).into(),
+            span(1, "ruby-0", &[3, 4, 5, 6]),
+            span(2, "rb-1", &[]),
+            span(2, "rt-2", &[]),
+            span(2, "rb-3", &[]),
+            span(2, "rt-4", &[]),
+        ]);
+        write_ruby(&mut file);
+        assert_eq!(ty(&file, 2), "Span");
+        assert_eq!(
+            file.dict(Ref(2)).unwrap().get("K"),
+            Some(&Object::Array(vec![Object::Ref(Ref(7)), Object::Ref(Ref(8))]))
+        );
+        assert_eq!(ty(&file, 7), "Ruby");
+        assert_eq!(ty(&file, 8), "Ruby");
+        assert_eq!(ty(&file, 5), "RB");
+        assert_eq!(ty(&file, 6), "RT");
+        assert_eq!(file.dict(Ref(5)).unwrap().get("P"), Some(&Object::Ref(Ref(8))));
+        assert_eq!(file.dict(Ref(8)).unwrap().get("P"), Some(&Object::Ref(Ref(2))));
+    }
+
+    #[test]
+    fn test_unexpected_kids() {
+        let mut file = PdfFile::from_objects([
+            Dict::new().with("Type", Object::name("Catalog")).into(),
+            span(1, "ruby-0", &[3]),
+            span(2, "rb-1", &[]),
+        ]);
+        write_ruby(&mut file);
+        assert_eq!(ty(&file, 2), "Span");
+        assert_eq!(ty(&file, 3), "Span");
+    }
+}
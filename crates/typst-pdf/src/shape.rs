@@ -8,7 +8,7 @@ use typst_utils::defer;
 
 use crate::convert::{FrameContext, GlobalContext};
 use crate::util::{AbsExt, TransformExt, convert_path};
-use crate::{paint, tags};
+use crate::{paint, pdfx, tags};
 
 #[typst_macros::time(name = "handle shape")]
 pub(crate) fn handle_shape(
@@ -31,6 +31,7 @@ pub(crate) fn handle_shape(
 
     if let Some(path) = convert_geometry(&shape.geometry) {
         let fill = if let Some(paint) = &shape.fill {
+            pdfx::validate_paint(gc, paint, span)?;
             Some(paint::convert_fill(
                 gc,
                 paint,
@@ -49,6 +50,7 @@ pub(crate) fn handle_shape(
         });
 
         let stroke = if let Some(stroke) = &stroke {
+            pdfx::validate_paint(gc, &stroke.paint, span)?;
             let stroke = paint::convert_stroke(
                 gc,
                 stroke,
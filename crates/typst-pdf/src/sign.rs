@@ -0,0 +1,172 @@
+//! Digital signatures.
+//!
+//! A signature is reserved in three steps: While post-processing, the chosen
+//! signature field receives a signature dictionary whose `/Contents` string
+//! is filled with zeros and whose `/ByteRange` is a placeholder of fixed
+//! width. Once the file is serialized, the byte range is filled in so that it
+//! covers the whole file except for the `/Contents` string. Signing then only
+//! needs to sign the covered bytes and write the signature into the string,
+//! without changing any offsets.
+
+use typst_library::diag::{StrResult, bail};
+
+use crate::PdfSignature;
+use crate::patch::{Dict, Object};
+
+/// The value of the byte range entries before the file is serialized. It has
+/// enough digits for any realistic file size.
+const PLACEHOLDER: i64 = 9_999_999_999;
+
+/// Creates the signature dictionary with the placeholders.
+pub(crate) fn signature_dict(signature: &PdfSignature) -> Dict {
+    let range = [0, PLACEHOLDER, PLACEHOLDER, PLACEHOLDER].map(Object::Int).to_vec();
+    let mut dict = Dict::new()
+        .with("Type", Object::name("Sig"))
+        .with("Filter", Object::name("Adobe.PPKLite"))
+        .with("SubFilter", Object::name("ETSI.CAdES.detached"))
+        .with("ByteRange", Object::Array(range))
+        .with("Contents", Object::Str(vec![0; signature.size]));
+    for (key, value) in [
+        ("Reason", &signature.reason),
+        ("Location", &signature.location),
+        ("ContactInfo", &signature.contact_info),
+    ] {
+        if let Some(value) = value {
+            dict.set(key, Object::text(value));
+        }
+    }
+    dict
+}
+
+/// Fills in the byte range of the serialized file's signature dictionary.
+pub(crate) fn write_byte_range(pdf: &mut [u8]) -> StrResult<()> {
+    let placeholder = format!("/ByteRange [0 {PLACEHOLDER} {PLACEHOLDER} {PLACEHOLDER}]");
+    let Some(start) = find(pdf, placeholder.as_bytes(), 0) else {
+        bail!("failed to find the signature's byte range");
+    };
+
+    let end = start + placeholder.len();
+    let (open, close) = contents(pdf, end)?;
+    let mut range = format!("/ByteRange [0 {open} {close} {}]", pdf.len() - close);
+    if range.len() > placeholder.len() {
+        bail!("file is too large to be signed");
+    }
+
+    // Pad with spaces so that no offsets change.
+    while range.len() < placeholder.len() {
+        range.push(' ');
+    }
+
+    pdf[start..end].copy_from_slice(range.as_bytes());
+    Ok(())
+}
+
+/// Signs a file with a signature placeholder.
+pub(crate) fn sign(
+    pdf: &[u8],
+    signer: impl FnOnce(&[u8]) -> StrResult<Vec<u8>>,
+) -> StrResult<Vec<u8>> {
+    let marker = b"/ByteRange [";
+    let mut from = 0;
+    let (open, close) = loop {
+        let Some(start) = find(pdf, marker, from) else {
+            bail!("file does not contain an unsigned signature placeholder");
+        };
+
+        from = start + marker.len();
+        if let Some(range) = byte_range(pdf, from)
+            && pdf[range.0 + 1..range.1 - 1].iter().all(|&b| b == b'0')
+        {
+            break range;
+        }
+    };
+
+    let mut data = pdf[..open].to_vec();
+    data.extend(&pdf[close..]);
+    let signature = signer(&data)?;
+
+    let reserved = (close - open - 2) / 2;
+    if signature.len() > reserved {
+        bail!(
+            "signature is too large ({} bytes, but only {reserved} are reserved)",
+            signature.len(),
+        );
+    }
+
+    let mut signed = pdf.to_vec();
+    let hex: String = signature.iter().map(|b| format!("{b:02X}")).collect();
+    signed[open + 1..open + 1 + hex.len()].copy_from_slice(hex.as_bytes());
+    Ok(signed)
+}
+
+/// Parses a filled-in byte range that starts at the given offset, directly
+/// after the opening bracket. Returns the offsets of the delimiters of the
+/// `/Contents` string if the range is valid.
+fn byte_range(pdf: &[u8], start: usize) -> Option<(usize, usize)> {
+    let end = start + pdf[start..].iter().position(|&b| b == b']')?;
+    let text = std::str::from_utf8(&pdf[start..end]).ok()?;
+    let numbers: Vec<usize> = text
+        .split_ascii_whitespace()
+        .map(|n| n.parse().ok())
+        .collect::<Option<_>>()?;
+    let &[0, open, close, rest] = numbers.as_slice() else { return None };
+    (open + 2 <= close
+        && close.checked_add(rest) == Some(pdf.len())
+        && pdf[open] == b'<'
+        && pdf[close - 1] == b'>')
+        .then_some((open, close))
+}
+
+/// Finds the `/Contents` string after the given offset and returns the offset
+/// of its opening delimiter and the offset after its closing delimiter.
+fn contents(pdf: &[u8], from: usize) -> StrResult<(usize, usize)> {
+    let marker = b"/Contents <";
+    let Some(start) = find(pdf, marker, from) else {
+        bail!("failed to find the signature's contents");
+    };
+
+    let open = start + marker.len() - 1;
+    let Some(len) = pdf[open..].iter().position(|&b| b == b'>') else {
+        bail!("failed to find the end of the signature's contents");
+    };
+
+    Ok((open, open + len + 1))
+}
+
+/// Finds the first occurrence of `needle` in `haystack`, starting at `from`.
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|i| from + i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        let mut pdf = format!(
+            "%PDF-1.7\n1 0 obj\n<< /Type /Sig /ByteRange [0 {PLACEHOLDER} {PLACEHOLDER} \
+             {PLACEHOLDER}] /Contents <0000> >>\nendobj\n%%EOF"
+        )
+        .into_bytes();
+        write_byte_range(&mut pdf).unwrap();
+
+        let open = find(&pdf, b"<0000>", 0).unwrap();
+        let close = open + 6;
+        let range = format!("[0 {open} {close} {}]", pdf.len() - close);
+        assert!(find(&pdf, range.as_bytes(), 0).is_some());
+
+        let signed = sign(&pdf, |data| {
+            assert_eq!(data.len(), pdf.len() - 6);
+            assert!(find(data, b"/Contents  >>", 0).is_some());
+            Ok(vec![0xab])
+        })
+        .unwrap();
+        assert_eq!(&signed[open..close], b"<AB00>");
+        assert!(sign(&signed, |_| Ok(vec![])).is_err());
+    }
+}
@@ -195,7 +195,8 @@ impl Groups {
             GroupKind::Par(_) => GroupKind::Par(None),
             GroupKind::TextAttr(attr) => GroupKind::TextAttr(attr.clone()),
             GroupKind::Standard(old, _) => {
-                let tag = self.tags.get(*old).clone();
+                // IDs must be unique, so only the first part keeps it.
+                let tag = self.tags.get(*old).clone().with_id(None);
                 let new = self.tags.push(tag);
                 GroupKind::Standard(new, None)
             }
@@ -24,7 +24,7 @@ use typst_library::diag::{
     At, ExpectInternal, SourceDiagnostic, SourceResult, assert_internal, bail, error,
     panic_internal,
 };
-use typst_library::foundations::{Content, ContextElem};
+use typst_library::foundations::{Content, ContextElem, StyleChain};
 use typst_library::introspection::Location;
 use typst_library::layout::{
     Frame, FrameItem, FrameParent, GridCell, GridElem, GroupItem, HideElem, Inherit,
@@ -34,17 +34,20 @@ use typst_library::math::EquationElem;
 use typst_library::model::{
     Document, EmphElem, EnumElem, FigureCaption, FigureElem, FootnoteElem, FootnoteEntry,
     HeadingElem, LinkMarker, ListElem, Outlinable, OutlineEntry, ParElem, QuoteElem,
-    StrongElem, TableCell, TableElem, TermsElem, TitleElem,
+    SidenoteElem, SidenoteEntry, StrongElem, TableCell, TableElem, TermsElem, TitleElem,
+};
+use typst_library::pdf::{
+    AnnotationElem, ArtifactElem, FormField, PdfMarkerTag, PdfMarkerTagKind,
 };
-use typst_library::pdf::{ArtifactElem, PdfMarkerTag, PdfMarkerTagKind};
 use typst_library::text::{
-    HighlightElem, OverlineElem, RawElem, RawLine, StrikeElem, SubElem, SuperElem,
-    UnderlineElem,
+    HighlightElem, OverlineElem, RawElem, RawLine, RubyElem, StrikeElem, SubElem,
+    SuperElem, UnderlineElem,
 };
 use typst_library::visualize::ImageElem;
 use typst_syntax::Span;
 
 use crate::PdfOptions;
+use crate::ruby::RubyPart;
 use crate::tags::GroupId;
 use crate::tags::context::{Ctx, FigureCtx, GridCtx, ListCtx, OutlineCtx, TableCtx};
 use crate::tags::groups::{BreakOpportunity, BreakPriority, GroupKind, Groups};
@@ -256,6 +259,7 @@ fn visit_frame(tree: &mut TreeBuilder, frame: &Frame) -> SourceResult<()> {
             FrameItem::Shape(..) => (),
             FrameItem::Image(..) => (),
             FrameItem::Link(..) => (),
+            FrameItem::Annotation(..) => (),
         }
     }
     Ok(())
@@ -270,6 +274,7 @@ fn visit_frame(tree: &mut TreeBuilder, frame: &Frame) -> SourceResult<()> {
 /// Currently the frame parent is only set for:
 /// - place elements [`PlaceElem`]
 /// - footnote entries [`FootnoteEntry`]
+/// - sidenote entries [`SidenoteEntry`]
 /// - broken table/grid cells [`TableCell`]/[`GridCell`]
 fn visit_group_frame(tree: &mut TreeBuilder, group: &GroupItem) -> SourceResult<()> {
     let Some(parent) = group.parent else {
@@ -374,9 +379,17 @@ fn progress_tree_start(tree: &mut TreeBuilder, elem: &Content) -> GroupId {
                 push_group(tree, elem, GroupKind::TermsItemBody(None, None))
             }
             PdfMarkerTagKind::Label => push_tag(tree, elem, Tag::Lbl),
+            PdfMarkerTagKind::RubyBase => push_ruby(tree, elem, RubyPart::Base),
+            PdfMarkerTagKind::RubyText => push_ruby(tree, elem, RubyPart::Text),
         }
     } else if let Some(link) = elem.to_packed::<LinkMarker>() {
         push_group(tree, elem, GroupKind::Link(link.clone(), None))
+    } else if let Some(field) = FormField::from_content(elem) {
+        let alt = field.tooltip(StyleChain::default()).map(Into::into);
+        push_tag(tree, elem, Tag::Form.with_alt_text(alt))
+    } else if let Some(annot) = elem.to_packed::<AnnotationElem>() {
+        let alt = Some(annot.contents.to_string());
+        push_tag(tree, elem, Tag::Annot.with_alt_text(alt))
     } else if let Some(_) = elem.to_packed::<TitleElem>() {
         push_tag(tree, elem, Tag::Title)
     } else if let Some(entry) = elem.to_packed::<OutlineEntry>() {
@@ -483,6 +496,10 @@ fn progress_tree_start(tree: &mut TreeBuilder, elem: &Content) -> GroupId {
         push_located(tree, elem, GroupKind::LogicalParent(elem.clone()))
     } else if let Some(_) = elem.to_packed::<FootnoteEntry>() {
         push_tag(tree, elem, Tag::Note)
+    } else if let Some(_) = elem.to_packed::<SidenoteElem>() {
+        push_located(tree, elem, GroupKind::LogicalParent(elem.clone()))
+    } else if let Some(_) = elem.to_packed::<SidenoteEntry>() {
+        push_tag(tree, elem, Tag::Note)
     } else if let Some(quote) = elem.to_packed::<QuoteElem>() {
         // TODO: should the attribution be handled somehow?
         if quote.block.val() {
@@ -490,6 +507,8 @@ fn progress_tree_start(tree: &mut TreeBuilder, elem: &Content) -> GroupId {
         } else {
             push_tag(tree, elem, Tag::InlineQuote)
         }
+    } else if let Some(_) = elem.to_packed::<RubyElem>() {
+        push_ruby(tree, elem, RubyPart::Ruby)
     } else if let Some(raw) = elem.to_packed::<RawElem>() {
         if raw.block.val() {
             push_group(tree, elem, GroupKind::CodeBlock(None))
@@ -543,6 +562,14 @@ fn push_tag(tree: &mut TreeBuilder, elem: &Content, tag: impl Into<TagKind>) ->
     push_group(tree, elem, GroupKind::Standard(id, None))
 }
 
+/// Pushes a span that is turned into a ruby structure element during
+/// post-processing.
+fn push_ruby(tree: &mut TreeBuilder, elem: &Content, part: RubyPart) -> GroupId {
+    let id = tree.groups.tags.push(Tag::Span);
+    tree.groups.tags.set(id, Tag::Span.with_id(Some(part.id(id.get()))));
+    push_group(tree, elem, GroupKind::Standard(id, None))
+}
+
 fn push_text_attr(tree: &mut TreeBuilder, elem: &Content, attr: TextAttr) -> GroupId {
     push_group(tree, elem, GroupKind::TextAttr(attr))
 }
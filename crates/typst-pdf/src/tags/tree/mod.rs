@@ -7,7 +7,7 @@ use crate::tags::tree::build::TreeBuilder;
 use crate::tags::tree::text::TextAttrs;
 use ecow::EcoVec;
 use krilla::surface::Surface;
-use krilla::tagging::{Artifact, ContentTag, Tag};
+use krilla::tagging::{Artifact, ContentTag, Tag, TagKind};
 use rustc_hash::FxHashMap;
 use smallvec::SmallVec;
 use typst_layout::PagedDocument;
@@ -64,6 +64,40 @@ impl Tree {
         None
     }
 
+    /// Find the lowest form field ancestor in the tree.
+    pub fn parent_form(&self) -> Option<GroupId> {
+        let mut current = self.current();
+
+        while current != GroupId::INVALID {
+            let group = self.groups.get(current);
+            if let GroupKind::Standard(tag, _) = group.kind
+                && let TagKind::Form(_) = self.groups.tags.get(tag)
+            {
+                return Some(current);
+            }
+            current = group.parent;
+        }
+
+        None
+    }
+
+    /// Find the lowest annotation ancestor in the tree.
+    pub fn parent_annot(&self) -> Option<GroupId> {
+        let mut current = self.current();
+
+        while current != GroupId::INVALID {
+            let group = self.groups.get(current);
+            if let GroupKind::Standard(tag, _) = group.kind
+                && let TagKind::Annot(_) = self.groups.tags.get(tag)
+            {
+                return Some(current);
+            }
+            current = group.parent;
+        }
+
+        None
+    }
+
     /// Find the highest artifact ancestor in the tree.
     pub fn parent_artifact(&self) -> Option<Artifact> {
         let (_, ty) = self.state.current_artifact?;
@@ -12,7 +12,7 @@ use typst_utils::defer;
 
 use crate::convert::{FrameContext, GlobalContext};
 use crate::util::{AbsExt, TransformExt, display_font};
-use crate::{paint, tags};
+use crate::{paint, pdfx, tags};
 
 #[typst_macros::time(name = "handle text")]
 pub(crate) fn handle_text(
@@ -24,6 +24,12 @@ pub(crate) fn handle_text(
     let mut handle = tags::text(gc, fc, surface, t);
     let surface = handle.surface();
 
+    let span = t.glyphs.first().map_or(Span::detached(), |glyph| glyph.span.0);
+    pdfx::validate_paint(gc, &t.fill, span)?;
+    if let Some(stroke) = &t.stroke {
+        pdfx::validate_paint(gc, &stroke.paint, span)?;
+    }
+
     let font = convert_font(gc, t.font.clone())?;
     let fill = paint::convert_fill(
         gc,
@@ -19,9 +19,15 @@ use typst_utils::Scalar;
 /// the resulting `tiny-skia` pixel buffer.
 #[typst_macros::time(name = "render")]
 pub fn render(page: &Page, opts: &RenderOptions) -> sk::Pixmap {
-    let bleed = if opts.render_bleed { page.bleed } else { Sides::default() };
+    // Printer's marks are only useful together with the bleed they surround.
+    let (bleed, marks) = if opts.render_bleed || !page.marks.is_zero() {
+        (page.bleed, page.marks)
+    } else {
+        (Sides::default(), Sides::default())
+    };
 
-    let size = page.frame.size() + bleed.sum_by_axis();
+    let outset = bleed.zip(marks).map(|(bleed, marks)| bleed + marks);
+    let size = page.frame.size() + outset.sum_by_axis();
     let pixel_per_pt = opts.pixel_per_pt.get() as f32;
     let pxw = (pixel_per_pt * size.x.to_f32()).round().max(1.0) as u32;
     let pxh = (pixel_per_pt * size.y.to_f32()).round().max(1.0) as u32;
@@ -32,15 +38,19 @@ pub fn render(page: &Page, opts: &RenderOptions) -> sk::Pixmap {
     let mut canvas = sk::Pixmap::new(pxw, pxh).unwrap();
 
     if let Some(fill) = page.fill_or_white() {
-        if let Paint::Solid(color) = fill {
+        if let Paint::Solid(color) = &fill
+            && marks.is_zero()
+        {
             canvas.fill(paint::to_sk_color(color.to_process()));
         } else {
-            let rect = Geometry::Rect(size).filled(fill);
+            let rect =
+                Geometry::Rect(page.frame.size() + bleed.sum_by_axis()).filled(fill);
+            let state = state.pre_translate(Point { x: marks.left, y: marks.top });
             shape::render_shape(&mut canvas, state, &rect);
         }
     }
 
-    let state = state.pre_translate(Point { x: bleed.left, y: bleed.top });
+    let state = state.pre_translate(Point { x: outset.left, y: outset.top });
 
     render_frame(&mut canvas, state, &page.frame);
 
@@ -99,7 +109,8 @@ pub struct RenderOptions {
     /// circumstances, such as when preparing documents for print, it may be
     /// desirable to include content beyond these bounds to account for bleed
     /// margins. This field allows expanding the rendered area to include such
-    /// bleed.
+    /// bleed. Pages with printer's marks always include the bleed and the
+    /// marks.
     pub render_bleed: bool,
 }
 
@@ -199,6 +210,7 @@ fn render_frame(canvas: &mut sk::Pixmap, state: State, frame: &Frame) {
                 image::render_image(canvas, state.pre_translate(*pos), image, *size);
             }
             FrameItem::Link(_, _) => {}
+            FrameItem::Annotation(_, _) => {}
             FrameItem::Tag(_) => {}
         }
     }
@@ -206,6 +218,11 @@ fn render_frame(canvas: &mut sk::Pixmap, state: State, frame: &Frame) {
 
 /// Render a group frame with optional transform and clipping into the canvas.
 fn render_group(canvas: &mut sk::Pixmap, state: State, pos: Point, group: &GroupItem) {
+    // Hidden PDF layers are not shown in other formats.
+    if group.layer.as_ref().is_some_and(|layer| !layer.visible) {
+        return;
+    }
+
     let sk_transform = to_sk_transform(&group.transform);
     let state = match group.frame.kind() {
         FrameKind::Soft => state.pre_translate(pos).pre_concat(sk_transform),
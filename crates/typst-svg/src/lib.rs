@@ -153,9 +153,14 @@ pub fn svg_merged(document: &PagedDocument, opts: &SvgOptions, gap: Abs) -> Stri
 }
 
 fn page_bleed(page: &Page, opts: &SvgOptions) -> (Size, Transform) {
-    let bleed = if opts.render_bleed { page.bleed } else { Sides::default() };
-    let size = page.frame.size() + bleed.sum_by_axis();
-    let ts = Transform::translate(bleed.left, bleed.top);
+    // Printer's marks are only useful together with the bleed they surround.
+    let outset = if opts.render_bleed || !page.marks.is_zero() {
+        page.outset()
+    } else {
+        Sides::default()
+    };
+    let size = page.frame.size() + outset.sum_by_axis();
+    let ts = Transform::translate(outset.left, outset.top);
     (size, ts)
 }
 
@@ -178,7 +183,8 @@ pub struct SvgOptions {
     /// circumstances, such as when preparing documents for print, it may be
     /// desirable to include content beyond these bounds to account for bleed
     /// margins. This field allows expanding the document area to include such
-    /// bleed.
+    /// bleed. Pages with printer's marks always include the bleed and the
+    /// marks.
     pub render_bleed: bool,
     /// Whether to format the SVG in a human-readable way.
     pub pretty: bool,
@@ -318,7 +324,7 @@ impl<'a> SVGRenderer<'a> {
                     self.render_image(svg, &state, image, size);
                 }
                 FrameItem::Link(dest, size) => self.render_link(svg, &state, dest, *size),
-                FrameItem::Tag(_) => {}
+                FrameItem::Annotation(..) | FrameItem::Tag(_) => {}
             }
         }
     }
@@ -326,6 +332,11 @@ impl<'a> SVGRenderer<'a> {
     /// Render a group. If the group has `clips` set to true, a clip path will
     /// be created.
     fn render_group(&mut self, svg: &mut SvgElem, state: &State, group: &GroupItem) {
+        // Hidden PDF layers are not shown in other formats.
+        if group.layer.as_ref().is_some_and(|layer| !layer.visible) {
+            return;
+        }
+
         let mut svg = svg.lazy_elem("g");
 
         let state = match group.frame.kind() {
@@ -0,0 +1,747 @@
+use std::error;
+use std::fmt::{self, Formatter};
+
+use crate::{
+    LinkedNode, SyntaxKind, SyntaxMode, SyntaxNode, is_newline, parse, split_newlines,
+};
+
+/// Configures how [`format`] lays out source code.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FormatConfig {
+    /// The number of spaces per indentation level in code.
+    pub indent: usize,
+    /// The column after which parenthesized lists, like function arguments,
+    /// are wrapped onto multiple lines.
+    pub max_width: usize,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self { indent: 2, max_width: 80 }
+    }
+}
+
+/// An error that can occur in [`format`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FormatError {
+    /// The source contains syntax errors.
+    Erroneous,
+    /// Formatting the source would have changed its meaning.
+    Unsound,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Erroneous => write!(f, "source contains syntax errors"),
+            Self::Unsound => {
+                write!(f, "formatting would change the meaning of the source")
+            }
+        }
+    }
+}
+
+impl error::Error for FormatError {}
+
+/// Format Typst markup.
+///
+/// In code, the spacing between tokens is normalized (e.g. `let x=1` becomes
+/// `let x = 1`), blocks and parenthesized lists are re-indented, and lists
+/// that are too long for a line are wrapped with one item per line. Since
+/// whitespace is significant in markup and math, only runs of spaces are
+/// collapsed and trailing whitespace is removed there. Lines in content
+/// blocks move along with the code around them. All lines end like the first
+/// line of the original text.
+///
+/// The result is parsed again and compared with the original syntax tree, so
+/// formatting never changes what a document means. If the full treatment
+/// would (e.g. because moving a content block changes how its lists nest),
+/// only the spacing within lines is normalized instead.
+pub fn format(text: &str, config: &FormatConfig) -> Result<String, FormatError> {
+    let root = parse(text);
+    if root.diagnosis().errors {
+        return Err(FormatError::Erroneous);
+    }
+
+    for conservative in [false, true] {
+        let mut printer = Printer {
+            text,
+            config,
+            conservative,
+            out: String::with_capacity(text.len()),
+            indent: 0,
+            shift: 0,
+            wrap: true,
+        };
+
+        printer.node(&LinkedNode::new(&root));
+        let output = printer.finish();
+        if equivalent(&root, &parse(&output)) {
+            return Ok(normalize_newlines(&output, text));
+        }
+    }
+
+    Err(FormatError::Unsound)
+}
+
+/// Uses the line ending of the first line in the original text for all lines
+/// of the formatted text, including those in raw blocks and comments.
+fn normalize_newlines(output: &str, text: &str) -> String {
+    let crlf = text.find('\n').is_some_and(|i| text[..i].ends_with('\r'));
+    let output = output.replace("\r\n", "\n");
+    if crlf { output.replace('\n', "\r\n") } else { output }
+}
+
+/// Lays out a syntax tree.
+struct Printer<'a> {
+    /// The original source text.
+    text: &'a str,
+    /// The layout configuration.
+    config: &'a FormatConfig,
+    /// Whether to keep lines where they are and only normalize the spacing
+    /// within them.
+    conservative: bool,
+    /// The formatted text.
+    out: String,
+    /// The indentation of new lines in code.
+    indent: usize,
+    /// By how many columns lines in markup and math move.
+    shift: isize,
+    /// Whether lists that are too long may be wrapped. This is not the case
+    /// for code embedded in the middle of a line of markup.
+    wrap: bool,
+}
+
+impl Printer<'_> {
+    /// Print a node and its descendants.
+    fn node(&mut self, node: &LinkedNode) {
+        match node.kind() {
+            // Raw text is whitespace-sensitive.
+            SyntaxKind::Raw => self.out.push_str(&node.full_text()),
+            SyntaxKind::CodeBlock if !self.conservative => self.code_block(node),
+            SyntaxKind::ContentBlock => self.content_block(node),
+            SyntaxKind::Args
+            | SyntaxKind::Params
+            | SyntaxKind::Array
+            | SyntaxKind::Dict
+            | SyntaxKind::Destructuring
+            | SyntaxKind::Parenthesized
+                if !self.conservative
+                    && node.children().next().map(|c| c.kind())
+                        == Some(SyntaxKind::LeftParen) =>
+            {
+                self.group(node);
+            }
+            _ if node.get().children().len() == 0 => self.out.push_str(node.leaf_text()),
+            _ => self.children(node),
+        }
+    }
+
+    /// Print the children of a node, normalizing the space between them.
+    fn children(&mut self, node: &LinkedNode) {
+        let code = node.mode_after() == Some(SyntaxMode::Code);
+        let mut prev = None;
+        let mut space = None;
+        for child in node.children() {
+            if matches!(child.kind(), SyntaxKind::Space | SyntaxKind::Parbreak) {
+                space = Some(child);
+                continue;
+            }
+
+            if prev.is_some() || space.is_some() {
+                self.space(
+                    node.kind(),
+                    code,
+                    prev.as_ref(),
+                    space.take().as_ref(),
+                    Some(&child),
+                );
+            }
+
+            if !code && prev.as_ref().is_some_and(|p| p.kind() == SyntaxKind::Hash) {
+                let wrap = self.column() == self.line_indent() + 1;
+                let prev_wrap = std::mem::replace(&mut self.wrap, wrap);
+                self.node(&child);
+                self.wrap = prev_wrap;
+            } else {
+                self.node(&child);
+            }
+
+            prev = Some(child);
+        }
+
+        if space.is_some() {
+            self.space(node.kind(), code, prev.as_ref(), space.as_ref(), None);
+        }
+    }
+
+    /// Print the space between two siblings, which may also be missing
+    /// entirely.
+    fn space(
+        &mut self,
+        parent: SyntaxKind,
+        code: bool,
+        prev: Option<&LinkedNode>,
+        space: Option<&LinkedNode>,
+        next: Option<&LinkedNode>,
+    ) {
+        let text = space.map_or("", |space| space.leaf_text().as_str());
+        let code =
+            space.map_or(code, |space| space.mode_after() == Some(SyntaxMode::Code));
+        let lines = split_newlines(text);
+        if !code || (lines.len() > 1 && self.conservative) {
+            if space.is_some() {
+                self.markup_space(&lines);
+            }
+            return;
+        }
+
+        if lines.len() > 1 {
+            // Statements start at the current indentation, while continued
+            // expressions (like method chains) hang one level deeper.
+            let indent = match (parent, next.map(|next| next.kind())) {
+                (SyntaxKind::Code | SyntaxKind::Conditional, _)
+                | (_, Some(SyntaxKind::RightParen)) => self.indent,
+                _ => self.indent + self.config.indent,
+            };
+            self.newline(lines.len() - 1, indent);
+            return;
+        }
+
+        let (Some(prev), Some(next)) = (prev, next) else {
+            if space.is_some() {
+                self.out.push(' ');
+            }
+            return;
+        };
+
+        match spacing(parent, prev.kind(), next.kind()) {
+            Some(true) => self.out.push(' '),
+            Some(false) => {}
+            None if space.is_some() => self.out.push(' '),
+            None => {}
+        }
+    }
+
+    /// Print whitespace in markup or math. Only collapses spaces and strips
+    /// trailing whitespace since lines must stay where they are.
+    fn markup_space(&mut self, lines: &[&str]) {
+        let [.., last] = lines else { return };
+        if lines.len() == 1 {
+            self.out.push(' ');
+        } else if self.shift == 0 {
+            self.newline(lines.len() - 1, 0);
+            self.out.push_str(last);
+        } else {
+            let width = last.chars().count() as isize + self.shift;
+            self.newline(lines.len() - 1, width.max(0) as usize);
+        }
+    }
+
+    /// Print a code block, putting each statement on its own line if the
+    /// block spans multiple lines.
+    fn code_block(&mut self, node: &LinkedNode) {
+        let broken = node.children().any(|child| match child.kind() {
+            SyntaxKind::Code => child.children().any(|c| {
+                c.kind() == SyntaxKind::LineComment
+                    || (c.kind() == SyntaxKind::Space && has_newline(c.leaf_text()))
+            }),
+            SyntaxKind::Space => has_newline(child.leaf_text()),
+            SyntaxKind::LineComment => true,
+            _ => false,
+        });
+
+        let inner = node
+            .children()
+            .filter(|child| {
+                !matches!(
+                    child.kind(),
+                    SyntaxKind::LeftBrace | SyntaxKind::RightBrace | SyntaxKind::Space
+                )
+            })
+            .filter(|child| !child.is_empty())
+            .collect::<Vec<_>>();
+
+        if inner.is_empty() {
+            self.out.push_str("{}");
+            return;
+        }
+
+        let base = self.line_indent();
+        let prev_indent = std::mem::replace(&mut self.indent, base + self.config.indent);
+        let prev_wrap = self.wrap;
+        self.wrap |= broken;
+        self.out.push('{');
+
+        let mut newlines = 1;
+        for child in node.children() {
+            match child.kind() {
+                SyntaxKind::LeftBrace | SyntaxKind::RightBrace => {}
+                SyntaxKind::Space => newlines = newline_count(child.leaf_text()),
+                _ if child.is_empty() => {}
+                _ => {
+                    if !broken {
+                        self.out.push(' ');
+                    } else if newlines == 0 {
+                        self.out.push(' ');
+                    } else {
+                        self.newline(newlines, self.indent);
+                    }
+                    self.node(&child);
+                    newlines = 0;
+                }
+            }
+        }
+
+        if broken {
+            self.newline(1, base);
+        } else {
+            self.out.push(' ');
+        }
+
+        self.out.push('}');
+        self.indent = prev_indent;
+        self.wrap = prev_wrap;
+    }
+
+    /// Print a content block.
+    ///
+    /// If the block's content starts on a new line, the content is indented
+    /// one level deeper than the line with the opening bracket. Otherwise,
+    /// the content's lines move along with the first one. In both cases,
+    /// their relative indentation stays the same since it matters for lists.
+    fn content_block(&mut self, node: &LinkedNode) {
+        let base = self.line_indent();
+        let lines = split_newlines(&self.text[node.range()]);
+        let block = !self.conservative && lines.len() > 1 && lines[0].trim_end() == "[";
+        let inner = lines
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|&(i, line)| {
+                !line.trim().is_empty() && (i + 1 < lines.len() || line.trim() != "]")
+            })
+            .map(|(_, line)| indentation(line))
+            .min();
+
+        let shift = match inner {
+            _ if self.conservative => 0,
+            Some(inner) if block => (base + self.config.indent) as isize - inner as isize,
+            _ => base as isize - self.original_indent(node.offset()) as isize,
+        };
+
+        let prev_shift = std::mem::replace(&mut self.shift, shift);
+        for child in node.children() {
+            if child.kind() == SyntaxKind::RightBracket
+                && block
+                && self.column() == self.line_indent()
+            {
+                // Align the closing bracket with the line of the opening one.
+                self.newline(0, base);
+            }
+            self.node(&child);
+        }
+        self.shift = prev_shift;
+    }
+
+    /// Print a parenthesized list of items (or a single parenthesized
+    /// expression). The list is laid out on a single line unless it already
+    /// spans multiple lines, contains comments, or is too long.
+    fn group(&mut self, node: &LinkedNode) {
+        let children = node.children().collect::<Vec<_>>();
+        let Some(close) =
+            children.iter().position(|c| c.kind() == SyntaxKind::RightParen)
+        else {
+            self.children(node);
+            return;
+        };
+
+        let inner = &children[1..close];
+        let items = inner
+            .iter()
+            .filter(|c| !matches!(c.kind(), SyntaxKind::Space | SyntaxKind::Comma))
+            .collect::<Vec<_>>();
+
+        let list = node.kind() != SyntaxKind::Parenthesized;
+        if node.kind() == SyntaxKind::Dict
+            && items.iter().any(|item| item.kind() == SyntaxKind::Colon)
+        {
+            // The empty dictionary `(:)`.
+            self.out.push_str("(:)");
+        } else if items.is_empty() {
+            self.out.push_str("()");
+        } else if inner.iter().any(|c| match c.kind() {
+            SyntaxKind::Space => has_newline(c.leaf_text()),
+            SyntaxKind::LineComment | SyntaxKind::BlockComment => true,
+            _ => false,
+        }) {
+            self.broken(inner, list, false);
+        } else {
+            // A trailing comma distinguishes `(a,)` from `(a)`.
+            let trailing =
+                matches!(node.kind(), SyntaxKind::Array | SyntaxKind::Destructuring)
+                    && items.len() == 1
+                    && inner.iter().any(|c| c.kind() == SyntaxKind::Comma);
+
+            // Nested lists only wrap if this one fits on its line.
+            let wrap = list && self.wrap && items.len() > 1;
+            let prev_wrap = self.wrap;
+            self.wrap &= !wrap;
+            let start = self.out.len();
+            self.out.push('(');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    self.out.push_str(", ");
+                }
+                self.node(item);
+            }
+            if trailing {
+                self.out.push(',');
+            }
+            self.out.push(')');
+            self.wrap = prev_wrap;
+
+            if wrap
+                && self.column() > self.config.max_width
+                && !self.out[start..].contains(is_newline)
+            {
+                self.out.truncate(start);
+                self.broken(inner, list, true);
+            }
+        }
+
+        for child in &children[close + 1..] {
+            self.node(child);
+        }
+    }
+
+    /// Print a parenthesized group across multiple lines.
+    ///
+    /// If `split` is true, each item gets its own line. Otherwise, items that
+    /// were on the same line stay there, so that rows of tables and grids are
+    /// kept intact.
+    fn broken(&mut self, inner: &[LinkedNode], list: bool, split: bool) {
+        let base = self.line_indent();
+        let prev_indent = std::mem::replace(&mut self.indent, base + self.config.indent);
+        self.out.push('(');
+
+        let mut first = true;
+        let mut newlines = 0;
+        for child in inner {
+            match child.kind() {
+                SyntaxKind::Space => newlines = newline_count(child.leaf_text()),
+                SyntaxKind::Comma => {}
+                kind => {
+                    let comment = matches!(
+                        kind,
+                        SyntaxKind::LineComment | SyntaxKind::BlockComment
+                    );
+                    if !first && newlines == 0 && (comment || !split) {
+                        self.out.push(' ');
+                    } else {
+                        self.newline(
+                            if first { 1 } else { newlines.max(1) },
+                            self.indent,
+                        );
+                    }
+
+                    self.node(child);
+                    if list && !comment {
+                        self.out.push(',');
+                    }
+
+                    first = false;
+                    newlines = 0;
+                }
+            }
+        }
+
+        self.newline(1, base);
+        self.out.push(')');
+        self.indent = prev_indent;
+    }
+
+    /// Start new lines, dropping trailing whitespace on the current one.
+    /// Consecutive blank lines are collapsed into one.
+    fn newline(&mut self, count: usize, indent: usize) {
+        let len = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(len);
+        for _ in 0..count.min(2) {
+            self.out.push('\n');
+        }
+        for _ in 0..indent {
+            self.out.push(' ');
+        }
+    }
+
+    /// The column of the end of the formatted text.
+    fn column(&self) -> usize {
+        let start = self.out.rfind(is_newline).map_or(0, |i| i + 1);
+        self.out[start..].chars().count()
+    }
+
+    /// The indentation of the last line of the formatted text.
+    fn line_indent(&self) -> usize {
+        let start = self.out.rfind(is_newline).map_or(0, |i| i + 1);
+        indentation(&self.out[start..])
+    }
+
+    /// The indentation of the line in the original text that contains the
+    /// given offset.
+    fn original_indent(&self, offset: usize) -> usize {
+        let start = self.text[..offset].rfind(is_newline).map_or(0, |i| i + 1);
+        indentation(&self.text[start..])
+    }
+
+    /// Finish printing and return the formatted text.
+    fn finish(mut self) -> String {
+        let len = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(len);
+        self.out
+    }
+}
+
+/// Whether there must be a space (`Some(true)`), must not be a space
+/// (`Some(false)`), or the existing spacing is kept (`None`) between two
+/// adjacent nodes in code.
+fn spacing(parent: SyntaxKind, prev: SyntaxKind, next: SyntaxKind) -> Option<bool> {
+    let colon = matches!(
+        parent,
+        SyntaxKind::Named
+            | SyntaxKind::Keyed
+            | SyntaxKind::ModuleImport
+            | SyntaxKind::ShowRule
+    );
+
+    match (prev, next) {
+        (_, SyntaxKind::Comma | SyntaxKind::Semicolon) => Some(false),
+        (SyntaxKind::LeftParen, _) | (_, SyntaxKind::RightParen) => Some(false),
+        (SyntaxKind::Comma | SyntaxKind::Semicolon, _) => Some(true),
+        (_, SyntaxKind::Colon) if colon => Some(false),
+        (SyntaxKind::Colon, _) if colon => Some(true),
+        _ if is_assignment(prev) || is_assignment(next) => Some(true),
+        (_, SyntaxKind::CodeBlock | SyntaxKind::ContentBlock)
+            if matches!(
+                parent,
+                SyntaxKind::Conditional | SyntaxKind::WhileLoop | SyntaxKind::ForLoop
+            ) =>
+        {
+            Some(true)
+        }
+        _ if parent == SyntaxKind::Binary
+            && (is_binary_op(prev) || is_binary_op(next)) =>
+        {
+            Some(true)
+        }
+        _ => None,
+    }
+}
+
+/// Whether the kind is `=`, `=>`, or a compound assignment operator.
+fn is_assignment(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Eq
+            | SyntaxKind::PlusEq
+            | SyntaxKind::HyphEq
+            | SyntaxKind::StarEq
+            | SyntaxKind::SlashEq
+            | SyntaxKind::Arrow
+    )
+}
+
+/// Whether the kind is a token of a binary operator other than an
+/// assignment.
+fn is_binary_op(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Plus
+            | SyntaxKind::Minus
+            | SyntaxKind::Star
+            | SyntaxKind::Slash
+            | SyntaxKind::EqEq
+            | SyntaxKind::ExclEq
+            | SyntaxKind::Lt
+            | SyntaxKind::LtEq
+            | SyntaxKind::Gt
+            | SyntaxKind::GtEq
+            | SyntaxKind::And
+            | SyntaxKind::Or
+            | SyntaxKind::Not
+            | SyntaxKind::In
+    )
+}
+
+/// Whether two syntax trees have the same meaning.
+///
+/// Whitespace in code is ignored, as are commas between the items of
+/// parenthesized lists. Whitespace in markup and math must be present in
+/// both trees, but may differ in size.
+fn equivalent(a: &SyntaxNode, b: &SyntaxNode) -> bool {
+    let mut xs = vec![];
+    let mut ys = vec![];
+    flatten(&LinkedNode::new(a), &mut xs);
+    flatten(&LinkedNode::new(b), &mut ys);
+    xs == ys
+}
+
+/// A piece of a flattened syntax tree.
+#[derive(Debug, Eq, PartialEq)]
+enum Piece<'a> {
+    /// The start of an inner node.
+    Enter(SyntaxKind),
+    /// The end of an inner node.
+    Exit,
+    /// A leaf node with its text.
+    Leaf(SyntaxKind, &'a str),
+    /// Whitespace in markup or math.
+    Space,
+}
+
+/// Flatten a syntax tree into pieces, dropping insignificant whitespace.
+fn flatten<'a>(node: &LinkedNode<'a>, pieces: &mut Vec<Piece<'a>>) {
+    let text = node.get().leaf_text().as_str();
+    match node.kind() {
+        SyntaxKind::Space if node.mode_after() == Some(SyntaxMode::Code) => {}
+        SyntaxKind::Space => {
+            if pieces.last() != Some(&Piece::Space) {
+                pieces.push(Piece::Space);
+            }
+        }
+        SyntaxKind::Parbreak => pieces.push(Piece::Leaf(SyntaxKind::Parbreak, "")),
+        SyntaxKind::Comma
+            if matches!(
+                node.parent_kind(),
+                Some(
+                    SyntaxKind::Args
+                        | SyntaxKind::Params
+                        | SyntaxKind::Array
+                        | SyntaxKind::Dict
+                        | SyntaxKind::Destructuring
+                )
+            ) => {}
+        // Text in markup may contain spaces, which are equivalent to
+        // separate space nodes.
+        SyntaxKind::Text if node.parent_kind() != Some(SyntaxKind::Raw) => {
+            for (i, word) in text.split(' ').enumerate() {
+                if i > 0 && pieces.last() != Some(&Piece::Space) {
+                    pieces.push(Piece::Space);
+                }
+                if !word.is_empty() {
+                    pieces.push(Piece::Leaf(SyntaxKind::Text, word));
+                }
+            }
+        }
+        kind if node.get().children().len() == 0 => pieces.push(Piece::Leaf(kind, text)),
+        kind => {
+            pieces.push(Piece::Enter(kind));
+            for child in node.children() {
+                flatten(&child, pieces);
+            }
+            pieces.push(Piece::Exit);
+        }
+    }
+}
+
+/// Whether the text contains a newline.
+fn has_newline(text: &str) -> bool {
+    text.contains(is_newline)
+}
+
+/// The number of newlines in the text.
+fn newline_count(text: &str) -> usize {
+    split_newlines(text).len() - 1
+}
+
+/// The number of leading whitespace characters in a line.
+fn indentation(line: &str) -> usize {
+    line.chars().take_while(|c| matches!(c, ' ' | '\t')).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn test(text: &str, expected: &str) {
+        let config = FormatConfig::default();
+        let formatted = format(text, &config).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(format(&formatted, &config).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_markup() {
+        test("Hello   world  \nagain", "Hello world\nagain");
+        test("a\n\n\n\nb", "a\n\nb");
+        test("- a\n  - b\n\n  c", "- a\n  - b\n\n  c");
+        test("$ x  +   y $", "$ x + y $");
+        test("```\n  a   b  \n```", "```\n  a   b  \n```");
+    }
+
+    #[test]
+    fn test_format_spacing() {
+        test("#let x=1", "#let x = 1");
+        test("#let f(x)=x", "#let f(x) = x");
+        test("#f(a : 1 , b:2)[c]", "#f(a: 1, b: 2)[c]");
+        test("#let d = ( \"a\" :1)", "#let d = (\"a\": 1)");
+        test("#show heading:it=>it", "#show heading: it => it");
+        test("#show:rest=>rest", "#show: rest => rest");
+        test("#import \"a.typ\" : x ,y", "#import \"a.typ\": x, y");
+        test("#import \"a.typ\": (\nx,\n  y\n  )", "#import \"a.typ\": (\n  x,\n  y\n)");
+        test("#(1+2*3)", "#(1 + 2 * 3)");
+        test("#(a not   in b)", "#(a not in b)");
+        test("#(x,) #(x) #( )", "#(x,) #(x) #()");
+        test("#(:) #{ }", "#(:) #{}");
+    }
+
+    #[test]
+    fn test_format_code_block() {
+        test("#{let x=1;x}", "#{ let x = 1; x }");
+        test("#for x in (1,2){x}", "#for x in (1, 2) { x }");
+        test("#{\n      let x = 1\n\n\n      x\n    }", "#{\n  let x = 1\n\n  x\n}");
+        test("#if x {\n    a\n} else {\n        b\n}", "#if x {\n  a\n} else {\n  b\n}");
+        test("#{\n  a // note\n}", "#{\n  a // note\n}");
+    }
+
+    #[test]
+    fn test_format_wrapping() {
+        test("#f(\n  a,b)", "#f(\n  a, b,\n)");
+        test("#f(\n  a,\nb)", "#f(\n  a,\n  b,\n)");
+        test("#f(a, // one\n  b)", "#f(\n  a, // one\n  b,\n)");
+        test(
+            "#figure(image(\"a-very-long-file-name.png\"), caption: [A rather long caption here])",
+            "#figure(\n  image(\"a-very-long-file-name.png\"),\n  caption: [A rather long caption here],\n)",
+        );
+        test(
+            "#test(a + duration(hours: 1, minutes: -60), datetime(hour: 12, minute: 0, second: 0))",
+            "#test(\n  a + duration(hours: 1, minutes: -60),\n  datetime(hour: 12, minute: 0, second: 0),\n)",
+        );
+        test(
+            "Text #link(\"https://example.com/a/rather/long/url/that/goes/on\")[and a long body]",
+            "Text #link(\"https://example.com/a/rather/long/url/that/goes/on\")[and a long body]",
+        );
+        test("#let x = (\n1 +\n2\n)", "#let x = (\n  1 +\n    2\n)");
+        test("#{\n  a\n  .b()\n      .c()\n}", "#{\n  a\n    .b()\n    .c()\n}");
+    }
+
+    #[test]
+    fn test_format_content_block() {
+        test("#{\n    [\n        Hello\n    ]\n}", "#{\n  [\n    Hello\n  ]\n}");
+        test("#{\n    [- a\n       b]\n}", "#{\n  [- a\n     b]\n}");
+    }
+
+    #[test]
+    fn test_format_newlines() {
+        test("a  \r\nb\r\n", "a\r\nb\r\n");
+        test("#{\r\nlet x=1\r\n}", "#{\r\n  let x = 1\r\n}");
+        test("a\r\n```\r\nx\ny\r\n```", "a\r\n```\r\nx\r\ny\r\n```");
+        test("a\n```\r\nx\r\n```", "a\n```\nx\n```");
+    }
+
+    #[test]
+    fn test_format_erroneous() {
+        let config = FormatConfig::default();
+        assert_eq!(format("#f(", &config), Err(FormatError::Erroneous));
+    }
+}
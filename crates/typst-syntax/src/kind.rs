@@ -1,9 +1,11 @@
+use serde::{Deserialize, Serialize};
+
 use crate::SyntaxMode;
 
 /// A syntactical building block of a Typst file.
 ///
 /// Can be created by the lexer or by the parser.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum SyntaxKind {
     /// The end of token stream.
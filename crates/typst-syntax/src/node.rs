@@ -326,6 +326,14 @@ impl SyntaxNode {
         (errors, warnings)
     }
 
+    /// Assign spans for the file with the given id to the node and all its
+    /// descendants, like for a freshly parsed file.
+    ///
+    /// Returns `false` if the tree has too many nodes to be numbered.
+    pub fn renumber(&mut self, id: FileId) -> bool {
+        self.numberize(id, Span::FULL).is_ok()
+    }
+
     /// Set a synthetic span for the node and all its descendants, and add hints
     /// with the original indices to any syntax errors or warnings.
     pub fn synthesize(&mut self, span: Span) {
@@ -548,7 +556,7 @@ impl SyntaxNode {
     }
 
     /// Whether this is a leaf node.
-    pub(super) fn is_leaf(&self) -> bool {
+    pub fn is_leaf(&self) -> bool {
         matches!(self.node_ref(), NodeRef::Leaf(_))
         // TODO: Should we also treat non-empty errors as leaves?
     }
@@ -179,4 +179,13 @@ mod test {
         assert_eq!(get(root, SubRange::new(0, 10)), "= head <la");
         assert_eq!(get(root, SubRange::new(3, 14)), "ead <label>");
     }
+
+    #[test]
+    fn test_source_renumber() {
+        let text = "= Heading\n#let x = 1 + *strong*";
+        let source = Source::detached(text);
+        let mut root = crate::parse(text);
+        assert!(root.renumber(source.id()));
+        assert_eq!(&root, source.root());
+    }
 }
@@ -13,7 +13,7 @@ PDF files focus on accurately describing documents visually, but also have facil
 == Command Line <command-line>
 PDF is Typst's default export format. Running the `compile` or `watch` subcommand without specifying a format will create a PDF. When exporting to PDF, you have the following configuration options:
 
-- Which @pdf:pdf-standards[PDF standards] Typst should enforce conformance with by specifying `--pdf-standard` followed by one or multiple comma-separated standards. Valid standards are `1.4`, `1.5`, `1.6`, `1.7`, `2.0`, `a-1b`, `a-1a`, `a-2b`, `a-2u`, `a-2a`, `a-3b`, `a-3u`, `a-3a`, `a-4`, `a-4f`, `a-4e`, and `ua-1`. By default, Typst outputs PDF-1.7-compliant files.
+- Which @pdf:pdf-standards[PDF standards] Typst should enforce conformance with by specifying `--pdf-standard` followed by one or multiple comma-separated standards. Valid standards are `1.4`, `1.5`, `1.6`, `1.7`, `2.0`, `a-1b`, `a-1a`, `a-2b`, `a-2u`, `a-2a`, `a-3b`, `a-3u`, `a-3a`, `a-4`, `a-4f`, `a-4e`, `ua-1`, `x-1a`, `x-3`, and `x-4`. By default, Typst outputs PDF-1.7-compliant files.
 
 - You can disable PDF tagging completely with `--no-pdf-tags`. By default, Typst will always write _Tagged PDF_ to provide a baseline level of accessibility. Using this flag, you can turn tags off. This will make your file inaccessible and prevent conformance with accessible conformance levels of PDF/A and all parts of PDF/UA.
 
@@ -29,7 +29,7 @@ Click the quick download button at the top right to export a PDF with default se
 = PDF standards <pdf-standards>
 The International Standards Organization (ISO) has published the base PDF standard and various standards that extend it to make PDFs more suitable for specific use-cases. By default, Typst exports PDF 1.7 files. Adobe Acrobat 8 and later as well as all other commonly used PDF viewers are compatible with this PDF version.
 
-Some features of Typst may not be available depending on the PDF standard you choose. You can combine compatible PDF/A and PDF/UA standards. Notably, PDF/UA-1 and PDF/A-4 are mutually incompatible since the former requires PDF 1.7 or earlier and the latter requires PDF 2.0.
+Some features of Typst may not be available depending on the PDF standard you choose. You can combine compatible PDF/A and PDF/UA standards as well as compatible PDF/X and PDF/UA standards. Notably, PDF/UA-1 and PDF/A-4 are mutually incompatible since the former requires PDF 1.7 or earlier and the latter requires PDF 2.0.
 
 == PDF versions <pdf-versions>
 Typst supports five different PDF versions: 1.4, 1.5, 1.6, 1.7 (default), and 2.0. You can choose each of these versions for your document export. However, based on the features you used there may be a minimum version. Likewise, the standards you target can limit which versions you can choose (see below for more).
@@ -103,6 +103,17 @@ Now, you only need to choose a *conformance level* (the lowercase letter at the
 
 When choosing between exporting PDF/A and regular PDF, keep in mind that PDF/A files contain additional metadata, and that some readers will prevent the user from modifying a PDF/A file.
 
+== PDF/X <pdf-x>
+Typst optionally supports emitting PDF/X-conformant files. PDF/X files are meant for the exchange of print-ready documents with a print shop. Besides the rules of the chosen standard, they declare the printing condition they were prepared for and the final size of their pages. Currently, Typst supports these PDF/X standards:
+
+- *PDF/X-1a:2003:* Based on PDF 1.4, this is the most restrictive standard. All colors must be @color.cmyk[CMYK], @color.luma[grayscale], or @color.spot[spot colors] with such a fallback. Raster images must be grayscale or CMYK JPEGs, and SVG images are not allowed since their colors are always RGB. Transparency is not allowed either.
+
+- *PDF/X-3:2003:* Based on PDF 1.4, this standard additionally allows RGB colors and images. They are converted to the printing condition by the print shop. Transparency is not allowed.
+
+- *PDF/X-4:* Based on PDF 1.6, this standard additionally allows transparency and @pdf.layer[layers].
+
+All PDF/X standards require the document to have a title and a date. Typst marks the area of each page without its @page.bleed[bleed] as the _trim box_ and the whole page as the _bleed box._ The output intent of the file declares the printing condition the document was prepared for. Since it depends on the printing process, you must provide the ICC profile of that condition, usually obtained from your print shop, with `--pdf-output-profile`. If the condition is registered with the ICC, like `FOGRA39`, also pass its name with `--pdf-output-condition`. PDF/X-1a requires a CMYK profile. PDF/X cannot be combined with PDF/A or encryption.
+
 = PDF-specific functionality <pdf-specific-functionality>
 Typst exposes PDF-specific functionality in the global `pdf` module. See below for the definitions it contains.
 
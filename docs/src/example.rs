@@ -196,6 +196,7 @@ fn drop_tags(frame: &mut Frame) {
         }
         FrameItem::Tag(_) => false,
         FrameItem::Link(..) => false,
+        FrameItem::Annotation(..) => false,
         _ => true,
     });
 }
@@ -1,11 +1,13 @@
 use std::fmt::Write;
 
+use ecow::{EcoString, eco_format};
 use typst::World;
 use typst::foundations::Smart;
 use typst::introspection::{Location, Tag};
 use typst::layout::{Frame, FrameItem};
 use typst::model::{Document, DocumentInfo};
 use typst_layout::PagedDocument;
+use typst_pdf::{PdfEncryption, PdfOptions, PdfPermissions, PdfSignature};
 
 use crate::collect::Test;
 use crate::world::TestWorld;
@@ -47,6 +49,15 @@ pub fn check(test: &Test, world: &TestWorld, doc: Option<&PagedDocument>) -> Str
                 sink.push_str("missing document");
             }
         }
+        "pdf-signature-field" => {
+            if let Some(doc) = doc {
+                if let Err(message) = check_signature(doc) {
+                    sink.push_str(&message);
+                }
+            } else {
+                sink.push_str("missing document");
+            }
+        }
         _ => {}
     }
     sink
@@ -93,3 +104,73 @@ fn check_balanced(doc: &PagedDocument) -> Result<(), &'static str> {
     let mut stack = Vec::new();
     doc.pages().iter().try_for_each(|page| visit(&mut stack, &page.frame))
 }
+
+/// Exports the document with a signature placeholder, signs it, and checks
+/// that the byte range covers everything except for the signature.
+fn check_signature(doc: &PagedDocument) -> Result<(), EcoString> {
+    const SIZE: usize = 64;
+
+    let encryption = PdfEncryption {
+        user_password: "".into(),
+        owner_password: Some("owner".into()),
+        permissions: PdfPermissions::default(),
+    };
+
+    for encryption in [None, Some(encryption)] {
+        let encrypted = encryption.is_some();
+        let options = PdfOptions {
+            signature: Some(PdfSignature {
+                field: Some("client".into()),
+                size: SIZE,
+                reason: Some("Approval".into()),
+                location: None,
+                contact_info: None,
+            }),
+            encryption,
+            ..Default::default()
+        };
+
+        let pdf = typst_pdf::pdf(doc, &options)
+            .map_err(|_| eco_format!("failed to export (encrypted: {encrypted})"))?;
+
+        let mut covered = vec![];
+        let signed = typst_pdf::sign(&pdf, |data| {
+            covered = data.to_vec();
+            Ok(vec![0xab; SIZE])
+        })?;
+
+        let [start, open, close, rest] = byte_range(&signed)
+            .ok_or_else(|| eco_format!("missing byte range (encrypted: {encrypted})"))?;
+        let mut expected = signed[..open].to_vec();
+        expected.extend(&signed[close..]);
+
+        let contents = format!("<{}>", "AB".repeat(SIZE));
+        if start != 0
+            || close.checked_add(rest) != Some(signed.len())
+            || signed.get(open..close) != Some(contents.as_bytes())
+            || covered != expected
+            || signed.len() != pdf.len()
+        {
+            return Err(eco_format!(
+                "byte range doesn't line up with the signature (encrypted: {encrypted})"
+            ));
+        }
+
+        typst_pdf::check_round_trip(&signed)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the byte range of the first signature in a PDF file.
+fn byte_range(pdf: &[u8]) -> Option<[usize; 4]> {
+    let marker = b"/ByteRange [";
+    let start = pdf.windows(marker.len()).position(|w| w == marker)? + marker.len();
+    let end = start + pdf[start..].iter().position(|&b| b == b']')?;
+    let numbers: Vec<usize> = std::str::from_utf8(&pdf[start..end])
+        .ok()?
+        .split_ascii_whitespace()
+        .map(|n| n.parse().ok())
+        .collect::<Option<_>>()?;
+    numbers.try_into().ok()
+}
@@ -343,7 +343,11 @@ impl HashOutputType for Pdf {
 
 fn generate_pdf(doc: &PagedDocument, standards: &[PdfStandard]) -> SourceResult<Vec<u8>> {
     let options = pdf_options(standards)?;
-    typst_pdf::pdf(doc, &options)
+    let pdf = typst_pdf::pdf(doc, &options)?;
+    typst_pdf::check_round_trip(&pdf)
+        .map_err(|err| eco_format!("PDF does not survive post-processing ({err})"))
+        .at(Span::detached())?;
+    Ok(pdf)
 }
 
 fn pdf_options(standards: &[PdfStandard]) -> SourceResult<PdfOptions> {
@@ -255,6 +255,35 @@ B
 #set block(breakable: false)
 #for i in range(10){block(height: 3pt * (1+i/5))}
 
+--- columns-balanced-overflow-unbreakable paged ---
+// With the average height, the unbreakable blocks don't fit into the
+// columns. Balancing then searches for a larger height that works instead of
+// pushing the last block onto the next page.
+#set page(width: 100pt, height: 150pt, margin: 5pt, columns: 2)
+#set columns(balanced: true)
+#set block(width: 100%, breakable: false, spacing: 2pt)
+#block(height: 40pt, fill: aqua)
+#block(height: 40pt, fill: eastern)
+#block(height: 40pt, fill: teal)
+
+--- columns-balanced-overflow-widow paged ---
+// Widow and orphan prevention can also make the average height too small.
+#set page(width: 120pt, height: 200pt, margin: 5pt, columns: 2)
+#set columns(balanced: true)
+#set par(justify: true)
+#lorem(20)
+
+#lorem(4)
+
+--- columns-balanced-overflow-full-height paged ---
+// If no smaller height works, the columns are as high as the region.
+#set page(width: 100pt, height: 90pt, margin: 5pt, columns: 2)
+#set columns(balanced: true)
+#set block(width: 100%, breakable: false, spacing: 2pt)
+#block(height: 70pt, fill: aqua)
+#block(height: 10pt, fill: eastern)
+#block(height: 70pt, fill: teal)
+
 --- columns-balanced-tables paged ---
 // Check that balancing is repeated when
 // the height changes due to the repeated header
@@ -321,6 +321,78 @@ C
 // Error: 2-34 vertical floating placement must be `auto`, `top`, or `bottom`
 #place(right, float: true)[Hello]
 
+--- place-wrap-left paged ---
+#set page(width: 150pt, height: auto)
+#place(right, wrap: "left", rect(width: 40pt, height: 40pt, fill: aqua))
+#lorem(30)
+
+--- place-wrap-right paged ---
+#set page(width: 150pt, height: auto)
+#place(left, wrap: "right", rect(width: 40pt, height: 40pt, fill: aqua))
+#lorem(30)
+
+--- place-wrap-both paged ---
+#set page(width: 180pt, height: auto)
+#place(center, wrap: "both", rect(width: 40pt, height: 40pt, fill: aqua))
+#lorem(30)
+
+--- place-wrap-clearance paged ---
+#set page(width: 150pt, height: auto)
+#place(right, wrap: "left", clearance: 0pt, rect(width: 40pt, height: 20pt))
+#lorem(12)
+#place(right, wrap: "left", clearance: 15pt, rect(width: 40pt, height: 20pt))
+#lorem(12)
+
+--- place-wrap-multiple-paragraphs paged ---
+#set page(width: 150pt, height: auto)
+#place(left, wrap: "right", rect(width: 40pt, height: 60pt, fill: aqua))
+#lorem(8)
+
+#lorem(8)
+
+= Heading
+#lorem(8)
+
+--- place-wrap-columns paged ---
+#set page(width: 220pt, height: 120pt, columns: 2)
+#place(right, wrap: "left", rect(width: 30pt, height: 30pt, fill: aqua))
+#lorem(25)
+#colbreak()
+#place(left, wrap: "right", rect(width: 30pt, height: 30pt, fill: eastern))
+#lorem(20)
+
+--- place-wrap-page-break paged ---
+#set page(width: 150pt, height: 100pt)
+#lorem(10)
+#place(right, wrap: "left", rect(width: 40pt, height: 40pt, fill: aqua))
+#lorem(40)
+
+--- place-wrap-widows-and-orphans paged ---
+#set page(width: 150pt, height: 100pt)
+#v(40pt)
+#place(right, wrap: "left", rect(width: 40pt, height: 20pt, fill: aqua))
+#lorem(25)
+
+--- place-wrap-linebreaks paged ---
+#set page(width: 150pt, height: auto)
+#set par(justify: true)
+#place(right, wrap: "left", rect(width: 40pt, height: 40pt, fill: aqua))
+#lorem(20)
+
+#set par(linebreaks: "simple")
+#place(right, wrap: "left", rect(width: 40pt, height: 40pt, fill: aqua))
+#lorem(20)
+
+--- place-wrap-float paged ---
+// Error: 2-46 wrapping is only available for non-floating placement
+// Hint: 2-46 you can disable floating placement with `place(float: false, ..)`
+#place(top, float: true, wrap: "left")[Hello]
+
+--- place-wrap-vertical-alignment paged ---
+// Error: 2-44 wrapped placement cannot be aligned vertically
+// Hint: 2-44 the element is placed at its position in the flow
+#place(bottom + right, wrap: "left")[Hello]
+
 --- place-flush paged ---
 #set page(height: 120pt)
 #let floater(align, height) = place(
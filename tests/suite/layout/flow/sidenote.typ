@@ -0,0 +1,73 @@
+// Test sidenotes.
+
+--- sidenote-invalid-numbering eval ---
+// Error: 22-23 expected string or function, found integer
+#sidenote(numbering: 1)[Hi]
+
+--- sidenote-entry-set eval ---
+#set sidenote.entry(clearance: 2em, gap: 1em)
+
+--- sidenote-basic paged html ---
+#set page(width: 160pt, height: auto, margin: (right: 60pt, rest: 10pt))
+Typst#sidenote[A typesetting system.] is a markup language.
+
+--- sidenote-placement paged ---
+#set page(width: 160pt, height: 120pt, margin: (right: 60pt, rest: 10pt))
+First line. \
+Second line.#sidenote[Next to the second line.] \
+Third line. \
+Fourth line.#sidenote[Next to the fourth line.]
+
+--- sidenote-stacking paged ---
+#set page(width: 160pt, height: 120pt, margin: (right: 60pt, rest: 10pt))
+#set sidenote.entry(gap: 6pt)
+Notes#sidenote[One.] on#sidenote[Two.] one#sidenote[Three, which is a bit longer.]
+line.
+
+--- sidenote-numbering paged ---
+#set page(width: 160pt, height: auto, margin: (right: 60pt, rest: 10pt))
+#set sidenote(numbering: "a")
+A#sidenote[First] B#footnote[Footnote] C#sidenote[Second]
+
+--- sidenote-overflow paged ---
+#set page(width: 160pt, height: 80pt, margin: (right: 60pt, rest: 10pt))
+#for i in range(1, 5) [
+  Line #i.#sidenote[#lorem(6)] \
+]
+
+--- sidenote-columns paged ---
+#set page(width: 200pt, height: 80pt, margin: (right: 50pt, rest: 10pt), columns: 2)
+Left#sidenote[From the first column.]
+#colbreak()
+#v(20pt)
+Right#sidenote[From the second column.]
+
+--- sidenote-wider-margin paged ---
+// Without two-sided margins, the notes go into the wider margin on every page.
+#set page(width: 160pt, height: 60pt, margin: (left: 60pt, rest: 10pt))
+Left#sidenote[On the left.]
+#pagebreak()
+Still left#sidenote[On the left again.]
+
+--- sidenote-binding-left paged ---
+#set page(width: 160pt, height: 60pt, margin: (inside: 10pt, outside: 60pt, y: 10pt))
+Odd#sidenote[Right margin.]
+#pagebreak()
+Even#sidenote[Left margin.]
+
+--- sidenote-binding-right paged ---
+#set page(
+  width: 160pt,
+  height: 60pt,
+  margin: (inside: 10pt, outside: 60pt, y: 10pt),
+  binding: right,
+)
+Odd#sidenote[Left margin.]
+#pagebreak()
+Even#sidenote[Right margin.]
+
+--- sidenote-entry-show-rule paged ---
+#set page(width: 160pt, height: auto, margin: (right: 60pt, rest: 10pt))
+#show sidenote.entry: set text(blue)
+#set sidenote.entry(clearance: 4pt)
+Blue#sidenote[A blue note.] notes.
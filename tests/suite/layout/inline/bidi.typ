@@ -66,10 +66,14 @@ Lריווח #h(1cm) R
 לתכנת בעברית `אם א == ב:`
 
 --- bidi-vertical eval ---
-// Test setting a vertical direction.
-// Error: 16-19 text direction must be horizontal
+// Test setting a top-to-bottom direction.
 #set text(dir: ttb)
 
+--- bidi-vertical-bottom-to-top eval ---
+// Test setting a bottom-to-top direction.
+// Error: 16-19 text direction cannot be bottom-to-top
+#set text(dir: btt)
+
 --- issue-1373-bidi-tofus paged ---
 // Test that shaping missing characters in both left-to-right and
 // right-to-left directions does not cause a crash.
@@ -0,0 +1,74 @@
+// Test vertical text.
+
+--- vertical-basic paged ---
+#set page(width: auto, height: 100pt)
+#set text(dir: ttb, lang: "ja", font: "Noto Serif CJK JP")
+吾輩は猫である。
+
+--- vertical-line-progression paged ---
+// Lines follow each other from right to left.
+#set page(width: auto, height: 80pt)
+#set text(dir: ttb, lang: "ja", font: "Noto Serif CJK JP")
+吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。
+
+--- vertical-leading paged ---
+#set page(width: auto, height: 80pt)
+#set text(dir: ttb, lang: "ja", font: "Noto Serif CJK JP")
+#set par(leading: 1.5em)
+吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。
+
+--- vertical-leading-between-blocks paged ---
+// Lines that don't fit next to each other continue below. The blocks of lines
+// are separated by the leading.
+#set page(width: 60pt, height: auto)
+#set text(dir: ttb, lang: "ja", font: "Noto Serif CJK JP")
+#set par(leading: 10pt)
+#box(height: 50pt, width: 100%)[
+  吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。何でも薄暗いじめじめした所でニャーニャー泣いていた事だけは記憶している。
+]
+
+--- vertical-paragraphs paged ---
+#set page(width: auto, height: 80pt)
+#set text(dir: ttb, lang: "zh", font: "Noto Serif CJK SC")
+#set par(spacing: 2em)
+第一段。
+
+第二段。
+
+--- vertical-mixed-scripts paged ---
+// Latin text is rotated and short numbers are set horizontally.
+#set page(width: auto, height: 120pt)
+#set text(dir: ttb, lang: "ja", font: ("Libertinus Serif", "Noto Serif CJK JP"))
+第12章では Typst を使う。第123章。
+
+--- vertical-punctuation paged ---
+#set page(width: auto, height: 100pt)
+#set text(dir: ttb, lang: "ja", font: "Noto Serif CJK JP")
+「こんにちは」、（世界）。
+
+--- vertical-box-upright paged ---
+#set page(width: auto, height: 100pt)
+#set text(dir: ttb, lang: "ja", font: "Noto Serif CJK JP")
+猫#box(width: 10pt, height: 20pt, fill: aqua)である。
+
+--- vertical-columns paged ---
+// Columns progress from right to left.
+#set page(width: 120pt, height: 60pt, columns: 2)
+#set text(dir: ttb, lang: "ja", font: "Noto Serif CJK JP")
+吾輩は猫である。
+#colbreak()
+名前はまだ無い。
+
+--- vertical-binding paged ---
+// Pages are bound on the right, so the inside margin is on the right of odd
+// pages.
+#set page(
+  width: 80pt,
+  height: 60pt,
+  margin: (inside: 5pt, outside: 25pt, y: 5pt),
+  fill: aqua,
+)
+#set text(dir: ttb, lang: "ja", font: "Noto Serif CJK JP")
+#rect(width: 100%, height: 100%, fill: white)
+#pagebreak()
+#rect(width: 100%, height: 100%, fill: white)
@@ -452,3 +452,46 @@ text
 --- page-bleed-inner-auto eval ---
 // Error: 18-30 expected relative length, found auto
 #set page(bleed: (left: auto))
+
+--- page-marks eval ---
+#set page(marks: (crop: true))
+#context test(page.marks, (crop: true, registration: false, bars: false))
+#set page(marks: true)
+#context test(page.marks, (crop: true, registration: true, bars: true))
+
+--- page-marks-bleed paged ---
+#set page(width: 80pt, height: 50pt, bleed: 6pt, fill: aqua, marks: true)
+The marks surround the bleed.
+
+--- page-marks-bleed-two-sided paged ---
+#set page(
+  width: 80pt,
+  height: 50pt,
+  bleed: (inside: 0pt, outside: 6pt, y: 6pt),
+  fill: aqua,
+  marks: (crop: true, registration: true),
+)
+Left
+#pagebreak()
+Right
+
+--- page-marks-slug paged ---
+#set page(
+  width: 120pt,
+  height: 50pt,
+  bleed: 4pt,
+  fill: aqua,
+  marks: (crop: true, registration: true),
+  slug: text(4pt)[Proof 2 -- page #context counter(page).display()],
+)
+First
+#pagebreak()
+Second
+
+--- page-marks-slug-only paged ---
+#set page(width: 80pt, height: 40pt, fill: aqua, slug: text(4pt)[Proof])
+The slug alone enlarges the page.
+
+--- page-marks-invalid-key eval ---
+// Error: 18-29 unexpected key "cut", valid keys are "crop", "registration", and "bars"
+#set page(marks: (cut: true))
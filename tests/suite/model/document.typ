@@ -16,6 +16,44 @@ What's up?
 #set document(author: (123,))
 What's up?
 
+--- document-metadata paged empty ---
+#set document(metadata: (
+  "Department": "Legal",
+  "dc:rights": "CC BY 4.0",
+  "xmlns:acme": "https://acme.example/ns/archive/1.0/",
+  "acme:retention": "10 years",
+))
+
+--- document-metadata-pdf-2 pdf pdfstandard(2.0) ---
+// PDF 2.0 keeps custom entries out of the document information dictionary.
+#set document(metadata: (
+  "Department": "Legal",
+  "Job Number": "42",
+  "dc:rights": "CC BY 4.0\u{7}",
+))
+
+--- document-metadata-pdf-a-4 pdf pdfstandard(a-4) ---
+#set document(metadata: (
+  "Department": "Legal",
+  "xmlns:acme": "https://acme.example/ns/archive/1.0/",
+  "acme:retention": "10 years",
+))
+
+--- document-metadata-reserved eval ---
+// Error: 25-37 the `Title` entry is set by Typst
+// Hint: 25-37 use the corresponding document property instead
+#set document(metadata: (Title: "x"))
+
+--- document-metadata-unknown-prefix eval ---
+// Error: 25-44 unknown namespace prefix `acme`
+// Hint: 25-44 declare the namespace with an `xmlns:acme` key
+#set document(metadata: ("acme:case": "42"))
+
+--- document-metadata-unsupported eval ---
+// Error: 25-42 the property `dc:title` is not supported
+// Hint: 25-42 use a custom namespace for other properties
+#set document(metadata: ("dc:title": "x"))
+
 --- document-set-after-content paged ---
 // Document set rules can appear anywhere in top-level realization, also after
 // content.
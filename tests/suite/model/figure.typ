@@ -82,6 +82,11 @@ We can clearly see that @fig-cylinder and
 // Hint: 2-27 you can enable floating placement with `figure(placement: auto, ..)`
 #figure(scope: "parent")[]
 
+--- figure-wrap-with-placement paged ---
+// Error: 2-40 floating figures cannot wrap text
+// Hint: 2-40 you can disable floating placement with `figure(placement: none, ..)`
+#figure(placement: top, wrap: "left")[]
+
 --- figure-theorem paged ---
 // Testing show rules with figures with a simple theorem display
 #show figure.where(kind: "theorem"): it => {
@@ -0,0 +1,25 @@
+--- pdf-annotation-pdf-a pdf pdfstandard(a-2b) ---
+// Error: 2-22 PDF/A-2b error: annotations are not supported
+// Hint: 2-22 the appearance of annotations is generated by the PDF reader
+#pdf.annotation("Fix")
+
+
+--- pdf-annotation-note pdf ---
+Typst is fast.
+#pdf.annotation("Add a citation here.", author: "Martin", open: true)
+
+--- pdf-annotation-highlight pdf ---
+Typst is a #pdf.annotation(
+  "Maybe say what is new about it?",
+  kind: "highlight",
+  author: "Laura",
+)[new] markup-based typesetting system.
+
+--- pdf-annotation-underline pdf ---
+#pdf.annotation("Too long?", kind: "underline", color: blue)[
+  This sentence spans multiple lines, so the underline has to cover more
+  than one line of text.
+]
+
+--- pdf-annotation-free-text pdf ---
+#pdf.annotation("Draft", kind: "free-text", color: red)[Introduction]
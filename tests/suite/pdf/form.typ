@@ -0,0 +1,21 @@
+--- pdf-form-field-kind-conflict pdf ---
+#pdf.text-field("name")
+// Error: 2-22 form field `name` is already used for a different kind of field
+// Hint: 2-22 fields with the same name share their value
+#pdf.checkbox("name")
+
+--- pdf-form-field-missing-tooltip pdf pdfstandard(ua-1) ---
+#set document(title: "Form")
+// Error: 2-24 PDF/UA-1 error: form field is missing a tooltip
+// Hint: 2-24 use the `tooltip` parameter to describe the field's purpose
+#pdf.text-field("name")
+
+--- pdf-form-field-pdf-a pdf pdfstandard(a-2b) ---
+// Error: 2-24 PDF/A-2b error: form fields are not supported
+// Hint: 2-24 the appearance of form fields is generated by the PDF reader
+#pdf.text-field("name")
+
+--- pdf-signature-field pdf ---
+// The exported file is signed in a custom check, both with and without
+// encryption.
+Signed: #pdf.signature-field("client", tooltip: "Client signature")
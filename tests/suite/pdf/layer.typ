@@ -0,0 +1,21 @@
+--- pdf-layer-hidden-pdf-a-1 pdf pdfstandard(a-1b) ---
+// Error: 2-44 PDF/A-1b error: hidden layers are not supported
+// Hint: 2-44 make the layer visible or use PDF/A-2 or later
+#pdf.layer("Notes", visible: false)[Hidden]
+
+--- pdf-layer-hidden-pdf-1-4 pdf pdfstandard(1.4) ---
+// Error: 2-44 hidden layers are not supported in PDF 1.4 files
+// Hint: 2-44 set the version to PDF 1.5 or later
+#pdf.layer("Notes", visible: false)[Hidden]
+
+--- pdf-layer-visibility paged pdf ---
+// The first element of a layer determines its visibility in all formats.
+#set page(width: 120pt, height: auto)
+#pdf.layer("Solutions", visible: false)[The answer is 42.]
+#pdf.layer("Solutions")[Hidden, too.]
+#pdf.layer("Notes")[Visible.]
+#pdf.layer("Notes", visible: false)[Visible, too.]
+
+--- pdf-layer-hidden-pdf-a-2 pdf pdfstandard(a-2b) ---
+#pdf.layer("Notes", visible: false)[Hidden]
+#pdf.layer("Notes")[Hidden, too.]
@@ -0,0 +1,24 @@
+// Test PDF page labels. The tests here so far are unsatisfactory because we
+// have no PDF testing infrastructure. That should be improved in the future.
+
+--- pdf-page-label paged empty ---
+#pdf.page-label(prefix: "Cover")
+#pdf.page-label("i")
+#pdf.page-label(auto)
+#pdf.page-label("A", prefix: "Appendix ", start: 2)
+
+--- pdf-page-label-invalid-style eval ---
+// Error: 17-22 expected "1", "i", "I", "a", "A", none, or auto
+#pdf.page-label("1.1")
+
+--- pdf-page-label-multiple-pages pdf ---
+// The third page continues the labels of the second one.
+#set page(width: 40pt, height: 30pt)
+#pdf.page-label(prefix: "Cover")
+#pagebreak()
+#pdf.page-label("i")
+#pagebreak()
+#pagebreak()
+#pdf.page-label(auto)
+#pagebreak()
+#pdf.page-label("A", prefix: "Appendix ", start: 2)
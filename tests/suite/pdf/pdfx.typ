@@ -0,0 +1,42 @@
+--- pdf-x-version-incompatible pdf pdfstandard(1.7, x-4) ---
+// Error: PDF 1.7 is not compatible with PDF/X-4
+// Hint: PDF/X-4 requires version PDF 1.6
+Hello
+
+--- pdf-x-pdf-a-incompatible pdf pdfstandard(a-2b, x-4) ---
+// Error: PDF/A-2b is not compatible with PDF/X-4
+// Hint: choose either a PDF/A or a PDF/X standard
+Hello
+
+--- pdf-x-missing-title pdf pdfstandard(x-4) ---
+// Error: PDF/X-4 error: missing document title
+// Hint: set the title with `set document(title: [...])`
+#set document(date: datetime(year: 1970, month: 1, day: 1))
+Hello
+
+--- pdf-x-1a-rgb pdf pdfstandard(x-1a) ---
+#set document(title: [Print], date: datetime(year: 1970, month: 1, day: 1))
+// Error: 2-27 PDF/X-1a error: RGB colors are not supported
+// Hint: 2-27 use `cmyk` or `luma` colors instead
+#rect(fill: rgb("#ff0000"))
+
+--- pdf-x-1a-gradient pdf pdfstandard(x-1a) ---
+#set document(title: [Print], date: datetime(year: 1970, month: 1, day: 1))
+#rect(fill: gradient.linear(cmyk(0%, 0%, 0%, 0%), cmyk(0%, 0%, 0%, 100%), space: cmyk))
+// Error: 2-60 PDF/X-1a error: gradients must not use an RGB color space
+// Hint: 2-60 set the gradient's `space` to `cmyk` or `luma`
+#rect(fill: gradient.linear(cmyk(0%, 0%, 0%, 0%), luma(0%)))
+
+--- pdf-x-3-transparency pdf pdfstandard(x-3) ---
+#set document(title: [Print], date: datetime(year: 1970, month: 1, day: 1))
+// Error: 2-30 PDF/X-3 error: the used fill or stroke has transparency
+// Hint: 2-30 try exporting with PDF/X-4, which supports transparency
+// Hint: 2-30 or don't use colors with transparency in this export mode
+#rect(fill: rgb(0, 0, 0, 50%))
+
+--- pdf-x-missing-output-intent pdf pdfstandard(x-4) ---
+// Error: PDF/X-4 error: missing output intent
+// Hint: provide the ICC profile of the printing process to print with
+// Hint: when using the CLI, pass it with `--pdf-output-profile`
+#set document(title: [Print], date: datetime(year: 1970, month: 1, day: 1))
+Hello
@@ -0,0 +1,76 @@
+--- ruby-basic paged html ---
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#ruby[東京][とうきょう]に#ruby[行][い]きます。
+
+--- ruby-size-and-gap paged ---
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#set ruby(size: 0.3em, gap: 3pt)
+#ruby[振][ふ]り#ruby[仮名][がな]
+
+--- ruby-mono paged ---
+// Each annotation is centered above its own character. The wide annotation
+// spreads the characters apart.
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#ruby("熟語", ("じゅく", "ご"), placement: "mono")
+
+--- ruby-group paged ---
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#ruby("熟語", ("じゅく", "ご"), placement: "group")
+
+--- ruby-jukugo paged ---
+// All annotations fit above their characters.
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#ruby("漢字", ("かん", "じ"))
+
+--- ruby-jukugo-wide paged ---
+// The annotations don't fit, so they are joined and distributed over the
+// whole base text.
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#ruby("熟語", ("じゅく", "ご")) \
+#ruby("熟語", ("じゅく", "ご"), placement: "jukugo")
+
+--- ruby-overhang paged ---
+// The annotation overhangs onto the adjacent kana, but not onto the kanji.
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+の#ruby[上][うえ]に \
+漢#ruby[上][うえ]字 \
+の#ruby(overhang: false)[上][うえ]に
+
+--- ruby-overhang-limit paged ---
+// The overhang is at most the annotation's font size on each side.
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+あ#ruby[字][じじじじじ]あ
+
+--- ruby-linebreak-single paged ---
+// A single annotation is never broken across lines.
+#set page(width: 60pt)
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+あいう#ruby[東京都][とうきょうと]
+
+--- ruby-linebreak-mono paged ---
+// Each character moves to the next line with its own annotation.
+#set page(width: 60pt)
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+あいう#ruby("東京都", ("とう", "きょう", "と"), placement: "mono")
+
+--- ruby-linebreak-jukugo paged ---
+#set page(width: 60pt)
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+あいう#ruby("東京都", ("とう", "きょう", "と"))
+
+--- ruby-tags pdftags pdfstandard(ua-1) ---
+// A single annotation results in one `Ruby` element, while per-character
+// annotations result in one `Ruby` element per character.
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#ruby[東京][とうきょう]に
+#ruby("漢字", ("かん", "じ"))を
+
+--- ruby-annotation-count-mismatch paged html ---
+// Error: 2-29 expected 2 ruby annotations, found 3
+// Hint: 2-29 each character of the base text needs its own annotation
+#ruby("ab", ("x", "y", "z"))
+
+--- ruby-per-character-base-not-text paged html ---
+// Error: 2-30 ruby base must be plain text when annotating each character
+// Hint: 2-30 try passing a single annotation instead
+#ruby(strong[ab], ("x", "y"))